@@ -0,0 +1,61 @@
+//! Build script: lists every file in the assets folder, so that the `embedded` feature can compile
+//! them all into the executable without a hand-maintained list.
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // Without the feature nothing includes the list, so don't scan (or watch) the assets.
+    if env::var_os("CARGO_FEATURE_EMBEDDED").is_none() {
+        return;
+    }
+
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let assets = manifest_dir.join("assets");
+    println!("cargo:rerun-if-changed={}", assets.display());
+
+    let mut files = Vec::new();
+    collect_files(&assets, &mut files);
+    files.sort();
+
+    let mut source = String::from("const ASSET_FILES: &[(&str, &[u8])] = &[\n");
+    for file in files {
+        let relative = file
+            .strip_prefix(&assets)
+            .unwrap()
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        source += &format!(
+            "    ({:?}, include_bytes!({:?}).as_slice()),\n",
+            relative,
+            file.display().to_string()
+        );
+    }
+    source += "];\n";
+
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("asset_files.rs");
+    fs::write(out, source).unwrap();
+}
+
+/// Add every file under `dir` to `files`, skipping hidden files such as `.DS_Store`.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
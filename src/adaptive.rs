@@ -0,0 +1,140 @@
+//! Adaptive difficulty: an optional mode which watches how the player is doing, and quietly
+//! adjusts how often saucers bomb and abduct to keep the game near the chosen challenge level.
+use bevy::prelude::*;
+
+use crate::{ShotFired, ShotLanded, damage::ShipLost, settings::Settings};
+
+/// Recent player performance, and the intensity it has led to. Counters decay over time, so that
+/// only recent play counts.
+#[derive(Resource, Debug)]
+pub struct AdaptiveDifficulty {
+    /// Recently lost ships
+    deaths: f32,
+
+    /// Recently fired shots
+    shots_fired: f32,
+
+    /// Recent shots which hit something
+    shots_landed: f32,
+
+    /// Multiplier for the rate of enemy attacks
+    intensity: f32,
+
+    /// Time until the parameters are next logged
+    log_timer: f32,
+}
+
+impl Default for AdaptiveDifficulty {
+    fn default() -> Self {
+        Self {
+            deaths: 0.0,
+            shots_fired: 0.0,
+            shots_landed: 0.0,
+            intensity: 1.0,
+            log_timer: LOG_INTERVAL,
+        }
+    }
+}
+
+impl AdaptiveDifficulty {
+    /// Multiplier for the rate of enemy bombing and abductions. Always 1.0 unless adaptive
+    /// difficulty is enabled.
+    pub fn intensity(&self, settings: &Settings) -> f32 {
+        if settings.gameplay.adaptive {
+            self.intensity
+        } else {
+            1.0
+        }
+    }
+
+    /// Recent accuracy. Assumed to be middling until enough shots have been fired.
+    fn accuracy(&self) -> f32 {
+        if self.shots_fired < 5.0 {
+            0.5
+        } else {
+            self.shots_landed / self.shots_fired
+        }
+    }
+
+    /// How much the player is struggling, from 0 (cruising) to 1 (overwhelmed).
+    fn struggle(&self) -> f32 {
+        (self.deaths * DEATH_WEIGHT + (1.0 - self.accuracy()) * (1.0 - DEATH_WEIGHT))
+            .clamp(0.0, 1.0)
+    }
+}
+
+/// Fraction of each counter which remains after one second
+const DECAY_PER_SECOND: f32 = 0.99;
+
+/// Contribution of a recent death to the struggle estimate; the rest comes from missed shots.
+const DEATH_WEIGHT: f32 = 0.5;
+
+/// Rate at which intensity moves towards the target, per second
+const ADJUST_RATE: f32 = 0.05;
+
+/// Range of intensity
+const MIN_INTENSITY: f32 = 0.5;
+const MAX_INTENSITY: f32 = 1.5;
+
+/// Time between log entries, in seconds
+const LOG_INTERVAL: f32 = 10.0;
+
+pub(crate) fn start_run_adaptive(mut r_adaptive: ResMut<AdaptiveDifficulty>) {
+    *r_adaptive = AdaptiveDifficulty::default();
+}
+
+pub(crate) fn adaptive_on_shot_fired(
+    _trigger: Trigger<ShotFired>,
+    mut r_adaptive: ResMut<AdaptiveDifficulty>,
+) {
+    r_adaptive.shots_fired += 1.0;
+}
+
+pub(crate) fn adaptive_on_shot_landed(
+    _trigger: Trigger<ShotLanded>,
+    mut r_adaptive: ResMut<AdaptiveDifficulty>,
+) {
+    r_adaptive.shots_landed += 1.0;
+}
+
+pub(crate) fn adaptive_on_ship_lost(
+    _trigger: Trigger<ShipLost>,
+    mut r_adaptive: ResMut<AdaptiveDifficulty>,
+) {
+    r_adaptive.deaths += 1.0;
+}
+
+/// Nudge the intensity up when the player is doing better than the target challenge, and down
+/// when they are struggling. The raw parameters are logged periodically for tuning.
+pub(crate) fn update_adaptive_difficulty(
+    mut r_adaptive: ResMut<AdaptiveDifficulty>,
+    r_settings: Res<Settings>,
+    r_time: Res<Time>,
+) {
+    if !r_settings.gameplay.adaptive {
+        return;
+    }
+    let dt = r_time.delta_secs();
+    let decay = DECAY_PER_SECOND.powf(dt);
+    r_adaptive.deaths *= decay;
+    r_adaptive.shots_fired *= decay;
+    r_adaptive.shots_landed *= decay;
+
+    let target = r_settings.gameplay.challenge;
+    let struggle = r_adaptive.struggle();
+    r_adaptive.intensity = (r_adaptive.intensity + (target - struggle) * ADJUST_RATE * dt)
+        .clamp(MIN_INTENSITY, MAX_INTENSITY);
+
+    r_adaptive.log_timer -= dt;
+    if r_adaptive.log_timer <= 0.0 {
+        r_adaptive.log_timer = LOG_INTERVAL;
+        info!(
+            "Adaptive difficulty: deaths {:.2}, accuracy {:.2}, struggle {:.2}, target {:.2}, intensity {:.2}",
+            r_adaptive.deaths,
+            r_adaptive.accuracy(),
+            struggle,
+            target,
+            r_adaptive.intensity
+        );
+    }
+}
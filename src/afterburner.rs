@@ -0,0 +1,135 @@
+//! Afterburner: holding the boost button raises the ship's top speed and acceleration, at the
+//! cost of engine heat. If the engine overheats, boost is unavailable until it has cooled down.
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+
+use crate::{
+    Boost, MainInput,
+    game_state::{DespawnOnExit, GameState},
+    ship::PlayerShip,
+};
+
+/// Engine heat and boost state of the player ship.
+#[derive(Component, Default, Debug)]
+pub struct Afterburner {
+    /// Engine heat, from 0 (cold) to 1 (overheated)
+    heat: f32,
+
+    /// Set when the engine overheats, and cleared once it has cooled down
+    overheated: bool,
+
+    /// Whether the afterburner is currently firing
+    active: bool,
+}
+
+impl Afterburner {
+    /// Whether the afterburner is currently firing.
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    /// Multiplier applied to the ship's maximum speed.
+    pub fn speed_factor(&self) -> f32 {
+        if self.active { BOOST_SPEED_FACTOR } else { 1.0 }
+    }
+
+    /// Multiplier applied to the ship's acceleration.
+    pub fn acceleration_factor(&self) -> f32 {
+        if self.active {
+            BOOST_ACCELERATION_FACTOR
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Marker for the fill bar of the heat gauge
+#[derive(Component, Default, Debug)]
+pub struct HeatGauge;
+
+/// Maximum speed multiplier while boosting
+const BOOST_SPEED_FACTOR: f32 = 1.6;
+
+/// Acceleration multiplier while boosting
+const BOOST_ACCELERATION_FACTOR: f32 = 2.0;
+
+/// Heat gained per second while boosting
+const HEAT_RATE: f32 = 0.4;
+
+/// Heat lost per second while not boosting
+const COOL_RATE: f32 = 0.25;
+
+/// An overheated engine can boost again once it has cooled below this level.
+const RECOVERED_HEAT: f32 = 0.4;
+
+const GAUGE_COLOR: Color = Color::srgb(1.0, 0.6, 0.1);
+const OVERHEAT_COLOR: Color = Color::srgb(1.0, 0.1, 0.1);
+
+/// Fire the afterburner while the boost button is held, and update the engine heat.
+pub(crate) fn update_afterburner(
+    mut q_player: Query<(&Actions<MainInput>, &mut Afterburner), With<PlayerShip>>,
+    r_time: Res<Time>,
+) -> Result<()> {
+    let dt = r_time.delta_secs();
+    for (actions, mut afterburner) in q_player.iter_mut() {
+        let held = actions.get::<Boost>()?.value().as_bool();
+        afterburner.active = held && !afterburner.overheated;
+        if afterburner.active {
+            afterburner.heat = (afterburner.heat + HEAT_RATE * dt).min(1.0);
+            if afterburner.heat >= 1.0 {
+                afterburner.overheated = true;
+                afterburner.active = false;
+            }
+        } else {
+            afterburner.heat = (afterburner.heat - COOL_RATE * dt).max(0.0);
+            if afterburner.heat < RECOVERED_HEAT {
+                afterburner.overheated = false;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn spawn_heat_gauge(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(12.0),
+            bottom: Val::Px(32.0),
+            width: Val::Px(120.0),
+            height: Val::Px(6.0),
+            border: UiRect::all(Val::Px(1.0)),
+            ..default()
+        },
+        BorderColor(Color::srgb(0.5, 0.5, 0.6)),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+        DespawnOnExit(GameState::Playing),
+        children![(
+            Node {
+                width: Val::Percent(0.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(GAUGE_COLOR),
+            HeatGauge,
+        )],
+    ));
+}
+
+/// Fill the gauge according to the engine heat. It turns red while the engine is overheated.
+pub(crate) fn update_heat_gauge(
+    q_player: Query<&Afterburner, With<PlayerShip>>,
+    mut q_gauge: Query<(&mut Node, &mut BackgroundColor), With<HeatGauge>>,
+) {
+    let Ok(afterburner) = q_player.single() else {
+        return;
+    };
+    for (mut node, mut color) in q_gauge.iter_mut() {
+        node.width = Val::Percent(afterburner.heat * 100.0);
+        color.0 = if afterburner.overheated {
+            OVERHEAT_COLOR
+        } else {
+            GAUGE_COLOR
+        };
+    }
+}
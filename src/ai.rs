@@ -0,0 +1,100 @@
+//! Reusable state machine framework for enemy AI
+use std::fmt::Debug;
+
+use bevy::prelude::*;
+
+/// A state in an enemy behavior state machine. Each enemy type defines its own enum of states.
+pub trait AiState: Copy + PartialEq + Debug + Send + Sync + 'static {
+    /// Color used to display this state in the AI debug overlay.
+    fn debug_color(&self) -> Color;
+}
+
+/// Tracks the current state of an enemy. The enemy's update system reads the current state and
+/// requests transitions; enter / exit behavior can be implemented by observing [`AiTransition`].
+#[derive(Component, Debug)]
+pub struct StateMachine<S: AiState> {
+    /// Current state
+    state: S,
+
+    /// Requested state, applied after the AI update
+    next: Option<S>,
+
+    /// Time spent in the current state
+    elapsed: f32,
+}
+
+impl<S: AiState> StateMachine<S> {
+    pub fn new(initial: S) -> Self {
+        Self {
+            state: initial,
+            next: None,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Return the current state.
+    pub fn state(&self) -> S {
+        self.state
+    }
+
+    /// Return how long we have been in the current state, in seconds.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Request a transition to a new state. The transition takes effect when transitions are
+    /// applied, which happens once per frame after the AI update.
+    pub fn transition_to(&mut self, next: S) {
+        self.next = Some(next);
+    }
+}
+
+/// Event triggered on an entity when its state machine changes state.
+#[derive(Event, Debug)]
+pub struct AiTransition<S: AiState> {
+    pub from: S,
+    pub to: S,
+}
+
+/// Whether the AI debug overlay is shown.
+#[derive(Resource, Default, Debug)]
+pub struct AiDebug(pub bool);
+
+/// Advance state timers and apply pending transitions.
+pub(crate) fn apply_ai_transitions<S: AiState>(
+    mut commands: Commands,
+    mut q_machines: Query<(Entity, &mut StateMachine<S>)>,
+    r_time: Res<Time>,
+) {
+    for (entity, mut machine) in q_machines.iter_mut() {
+        machine.elapsed += r_time.delta_secs();
+        if let Some(next) = machine.next.take() {
+            let from = machine.state;
+            machine.state = next;
+            machine.elapsed = 0.0;
+            commands
+                .entity(entity)
+                .trigger(AiTransition::<S> { from, to: next });
+        }
+    }
+}
+
+pub(crate) fn toggle_ai_debug(r_keys: Res<ButtonInput<KeyCode>>, mut r_debug: ResMut<AiDebug>) {
+    if r_keys.just_pressed(KeyCode::F2) {
+        r_debug.0 = !r_debug.0;
+    }
+}
+
+/// Draw a ring around each enemy, colored by its current AI state.
+pub(crate) fn draw_ai_debug<S: AiState>(
+    mut gizmos: Gizmos,
+    q_machines: Query<(&StateMachine<S>, &GlobalTransform)>,
+) {
+    for (machine, transform) in q_machines.iter() {
+        gizmos.circle(
+            Isometry3d::from_translation(transform.translation()),
+            0.05,
+            machine.state().debug_color(),
+        );
+    }
+}
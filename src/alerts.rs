@@ -0,0 +1,211 @@
+//! Audio-visual warnings: low shields, and treasure being abducted.
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+};
+
+use crate::{
+    Minimap, Playfield, UnitPosition, Viewpoint,
+    ai::{AiTransition, StateMachine},
+    damage::Shields,
+    game_state::{DespawnOnExit, GameState},
+    saucer::SaucerState,
+    ship::PlayerShip,
+    treasure::{Treasure, TreasureState},
+};
+
+/// Marker for the full-screen red border which pulses when shields are critical
+#[derive(Component, Default, Debug)]
+pub struct ShieldAlert;
+
+/// Marker for the looping klaxon sound
+#[derive(Component, Default, Debug)]
+pub struct Klaxon;
+
+/// Looping radio distress call from the colonists of a treasure being abducted
+#[derive(Component, Debug)]
+pub struct DistressCall {
+    treasure: Entity,
+}
+
+/// Shields below this fraction of full strength are critical.
+const CRITICAL_SHIELDS: f32 = 0.25;
+
+/// Pulse rate of warning highlights, in cycles per second
+const PULSE_RATE: f32 = 2.0;
+
+/// Volume of a distress call heard from directly overhead
+const DISTRESS_VOLUME: f32 = 0.25;
+
+/// Distress calls fade out completely at this horizontal distance from the viewpoint.
+const DISTRESS_RANGE: f32 = 1.5;
+
+const ALERT_COLOR: Color = Color::srgb(1.0, 0.0, 0.0);
+const MINIMAP_COLOR: Color = Color::srgb(0.0, 0.5, 0.0);
+const ABDUCTION_COLOR: Color = Color::srgb(1.0, 0.5, 0.0);
+
+pub(crate) fn spawn_shield_alert(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            right: Val::Px(0.0),
+            top: Val::Px(0.0),
+            bottom: Val::Px(0.0),
+            border: UiRect::all(Val::Px(16.0)),
+            ..default()
+        },
+        BorderColor(Color::NONE),
+        ShieldAlert,
+    ));
+}
+
+/// Pulse the red border, and sound the klaxon, while shields are critical.
+pub(crate) fn update_shield_alert(
+    mut commands: Commands,
+    q_shields: Query<&Shields, With<PlayerShip>>,
+    mut q_alert: Query<&mut BorderColor, With<ShieldAlert>>,
+    q_klaxon: Query<Entity, With<Klaxon>>,
+    asset_server: Res<AssetServer>,
+    r_time: Res<Time<Real>>,
+) {
+    let critical = q_shields
+        .single()
+        .is_ok_and(|shields| shields.fraction() < CRITICAL_SHIELDS);
+
+    let alpha = if critical {
+        pulse(r_time.elapsed_secs()) * 0.6
+    } else {
+        0.0
+    };
+    for mut border in q_alert.iter_mut() {
+        border.0 = ALERT_COLOR.with_alpha(alpha);
+    }
+
+    match (critical, q_klaxon.is_empty()) {
+        (true, true) => {
+            commands.spawn((
+                AudioPlayer::new(asset_server.load("sounds/buzzdown.ogg")),
+                PlaybackSettings {
+                    mode: PlaybackMode::Loop,
+                    ..default()
+                },
+                Klaxon,
+            ));
+        }
+        (false, false) => {
+            for klaxon in q_klaxon.iter() {
+                commands.entity(klaxon).despawn();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Sound the abduction alarm when a saucer starts grabbing a treasure.
+pub(crate) fn on_abduction_started(
+    trigger: Trigger<AiTransition<SaucerState>>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    if trigger.event().to != SaucerState::Grabbing {
+        return;
+    }
+    commands.spawn((
+        AudioPlayer::new(asset_server.load("sounds/abduct.ogg")),
+        PlaybackSettings {
+            mode: PlaybackMode::Despawn,
+            ..default()
+        },
+    ));
+}
+
+/// Flash the minimap frame while any treasure is being grabbed.
+pub(crate) fn update_abduction_alert(
+    q_saucers: Query<&StateMachine<SaucerState>>,
+    mut q_minimap: Query<&mut BorderColor, With<Minimap>>,
+    r_time: Res<Time<Real>>,
+) {
+    let grabbing = q_saucers
+        .iter()
+        .any(|machine| machine.state() == SaucerState::Grabbing);
+    let color = if grabbing {
+        MINIMAP_COLOR.mix(&ABDUCTION_COLOR, pulse(r_time.elapsed_secs()))
+    } else {
+        MINIMAP_COLOR
+    };
+    for mut border in q_minimap.iter_mut() {
+        border.0 = color;
+    }
+}
+
+/// Play a faint, crackling radio distress call while a treasure is being carried off, louder the
+/// closer it is to the viewpoint, so that abductions can be heard before they are seen. If the
+/// abductor is destroyed, the call cuts to a brief burst of relieved chatter; if the saucer
+/// escapes, it simply goes dead.
+pub(crate) fn update_distress_calls(
+    mut commands: Commands,
+    q_treasures: Query<(Entity, &Treasure, &UnitPosition)>,
+    mut q_calls: Query<(Entity, &DistressCall, Option<&mut AudioSink>)>,
+    asset_server: Res<AssetServer>,
+    r_viewpoint: Res<Viewpoint>,
+    r_playfield: Res<Playfield>,
+) {
+    let volume_at = |position: &UnitPosition| {
+        let distance = r_playfield
+            .relative(position.0.x, r_viewpoint.position)
+            .abs();
+        DISTRESS_VOLUME * (1.0 - distance / DISTRESS_RANGE).clamp(0.0, 1.0)
+    };
+
+    for (entity, call, sink) in q_calls.iter_mut() {
+        let Ok((_, treasure, position)) = q_treasures.get(call.treasure) else {
+            // Carried off, the signal is lost.
+            commands.entity(entity).despawn();
+            continue;
+        };
+        if treasure.state == TreasureState::Abducted {
+            if let Some(mut sink) = sink {
+                sink.set_volume(Volume::Linear(volume_at(position)));
+            }
+            continue;
+        }
+
+        // Dropped by its abductor
+        commands.entity(entity).despawn();
+        commands.spawn((
+            AudioPlayer::new(asset_server.load("sounds/ming.ogg")),
+            PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                volume: Volume::Linear(volume_at(position)),
+                speed: 1.3,
+                ..default()
+            },
+        ));
+    }
+
+    for (treasure, _, position) in q_treasures
+        .iter()
+        .filter(|(_, treasure, _)| treasure.state == TreasureState::Abducted)
+    {
+        if q_calls.iter().any(|(_, call, _)| call.treasure == treasure) {
+            continue;
+        }
+        commands.spawn((
+            AudioPlayer::new(asset_server.load("sounds/rattle.ogg")),
+            PlaybackSettings {
+                mode: PlaybackMode::Loop,
+                volume: Volume::Linear(volume_at(position)),
+                speed: 2.2,
+                ..default()
+            },
+            DistressCall { treasure },
+            DespawnOnExit(GameState::Playing),
+        ));
+    }
+}
+
+/// Smooth 0..1 pulse
+fn pulse(time: f32) -> f32 {
+    0.5 - 0.5 * (time * PULSE_RATE * std::f32::consts::TAU).cos()
+}
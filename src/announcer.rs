@@ -0,0 +1,106 @@
+//! Kill streak tracking, and the announcer which calls out streaks with escalating sounds.
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+};
+
+use crate::{Enemy, EnemyDestroyed, Playfield, UnitPosition, Viewpoint, settings::Settings};
+
+/// Combo tracker: counts enemies destroyed in quick succession.
+#[derive(Resource, Default, Debug)]
+pub struct KillStreak {
+    /// Number of kills in the current streak
+    pub count: u32,
+
+    /// Time remaining to extend the streak
+    timer: f32,
+}
+
+/// Event sent when a kill extends a streak to two or more.
+#[derive(Event, Debug)]
+pub struct StreakExtended {
+    /// Number of kills in the streak
+    pub count: u32,
+
+    /// Whether the kill left no enemies on screen
+    pub screen_clear: bool,
+}
+
+/// Time allowed between kills for them to count as a streak, in seconds
+const STREAK_WINDOW: f32 = 1.5;
+
+/// Enemies within this horizontal distance of the viewpoint count as on screen. This matches
+/// the reach of a smart bomb.
+const SCREEN_HALF_WIDTH: f32 = 1.25;
+
+/// Extend or restart the streak when an enemy is destroyed. Kills which earn no reward don't
+/// count.
+pub(crate) fn on_enemy_destroyed_streak(
+    trigger: Trigger<EnemyDestroyed>,
+    mut commands: Commands,
+    q_enemies: Query<&UnitPosition, With<Enemy>>,
+    mut r_streak: ResMut<KillStreak>,
+    r_viewpoint: Res<Viewpoint>,
+    r_playfield: Res<Playfield>,
+) {
+    if !trigger.event().kind.rewarded() {
+        return;
+    }
+    r_streak.count = if r_streak.timer > 0.0 {
+        r_streak.count + 1
+    } else {
+        1
+    };
+    r_streak.timer = STREAK_WINDOW;
+    if r_streak.count < 2 {
+        return;
+    }
+    let screen_clear = !q_enemies.iter().any(|position| {
+        r_playfield
+            .relative(position.0.x, r_viewpoint.position)
+            .abs()
+            < SCREEN_HALF_WIDTH
+    });
+    commands.trigger(StreakExtended {
+        count: r_streak.count,
+        screen_clear,
+    });
+}
+
+/// Let the streak lapse when no kills are made within the window.
+pub(crate) fn update_kill_streak(mut r_streak: ResMut<KillStreak>, r_time: Res<Time>) {
+    if r_streak.timer > 0.0 {
+        r_streak.timer -= r_time.delta_secs();
+        if r_streak.timer <= 0.0 {
+            r_streak.count = 0;
+        }
+    }
+}
+
+/// Call out streaks. Each callout is pitched higher than the last, and clearing the screen gets
+/// a callout of its own.
+pub(crate) fn announce_streak(
+    trigger: Trigger<StreakExtended>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    r_settings: Res<Settings>,
+) {
+    if !r_settings.audio.announcer {
+        return;
+    }
+    let event = trigger.event();
+    let (sample, speed) = if event.screen_clear {
+        ("sounds/warpin.ogg", 1.5)
+    } else {
+        ("sounds/ming.ogg", 0.9 + 0.15 * event.count.min(6) as f32)
+    };
+    commands.spawn((
+        AudioPlayer::new(asset_server.load(sample)),
+        PlaybackSettings {
+            mode: PlaybackMode::Despawn,
+            volume: Volume::Linear(0.7),
+            speed,
+            ..default()
+        },
+    ));
+}
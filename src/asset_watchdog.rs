@@ -0,0 +1,108 @@
+//! Asset watchdog: detects models, textures and sounds which fail to load (which can happen on
+//! the web if the server is flaky), logs them, and substitutes simple placeholders so that the
+//! game remains playable rather than silently showing nothing.
+use bevy::{
+    asset::{AssetLoadFailedEvent, LoadState, UntypedAssetId},
+    audio::PlaybackMode,
+    prelude::*,
+};
+
+/// Shared mesh and material for placeholder models
+#[derive(Resource, Default, Debug)]
+pub struct PlaceholderAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+/// Marker for an entity whose model failed to load, and was replaced by a placeholder
+#[derive(Component, Debug)]
+pub struct PlaceholderModel;
+
+/// Models are authored at roughly this size, and scaled down when spawned.
+const PLACEHOLDER_SIZE: Vec3 = Vec3::new(4.0, 1.5, 1.5);
+
+/// Bright magenta, so that placeholders are obvious in testing.
+const PLACEHOLDER_COLOR: Color = Color::srgb(1.0, 0.0, 1.0);
+
+pub(crate) fn setup_placeholders(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut r_assets: ResMut<PlaceholderAssets>,
+) {
+    r_assets.mesh = meshes.add(Cuboid::from_size(PLACEHOLDER_SIZE));
+    r_assets.material = materials.add(StandardMaterial {
+        base_color: PLACEHOLDER_COLOR,
+        emissive: LinearRgba::new(0.3, 0.0, 0.3, 1.0),
+        ..default()
+    });
+}
+
+/// Log every asset of a given type which fails to load.
+pub(crate) fn report_failed_assets<A: Asset>(mut events: EventReader<AssetLoadFailedEvent<A>>) {
+    for event in events.read() {
+        error!("Failed to load {}: {}", event.path, event.error);
+    }
+}
+
+/// Replace textures which fail to load with a plain white image, so that materials using them
+/// still render in their base color.
+pub(crate) fn substitute_failed_images(
+    mut events: EventReader<AssetLoadFailedEvent<Image>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for event in events.read() {
+        images.insert(event.id, Image::default());
+    }
+}
+
+/// Replace models which fail to load with a placeholder box.
+pub(crate) fn substitute_failed_scenes(
+    mut commands: Commands,
+    q_scenes: Query<(Entity, &SceneRoot), Without<PlaceholderModel>>,
+    asset_server: Res<AssetServer>,
+    r_assets: Res<PlaceholderAssets>,
+) {
+    for (entity, scene) in q_scenes.iter() {
+        if !load_failed(&asset_server, &scene.0) {
+            continue;
+        }
+        commands.entity(entity).remove::<SceneRoot>().insert((
+            Mesh3d(r_assets.mesh.clone()),
+            MeshMaterial3d(r_assets.material.clone()),
+            PlaceholderModel,
+        ));
+    }
+}
+
+/// Sounds which fail to load never finish playing, so one-shot sound entities would never be
+/// despawned. Remove them; sounds attached to other entities (such as the ship's thrust) are
+/// simply removed from the entity.
+pub(crate) fn remove_failed_sounds(
+    mut commands: Commands,
+    q_sounds: Query<(Entity, &AudioPlayer, Option<&PlaybackSettings>)>,
+    asset_server: Res<AssetServer>,
+) {
+    for (entity, player, settings) in q_sounds.iter() {
+        if !load_failed(&asset_server, &player.0) {
+            continue;
+        }
+        if settings.is_some_and(|settings| matches!(settings.mode, PlaybackMode::Despawn)) {
+            commands.entity(entity).despawn();
+        } else {
+            commands.entity(entity).remove::<AudioPlayer>();
+        }
+    }
+}
+
+/// Whether an asset, or the file it was loaded from, failed to load.
+fn load_failed<A: Asset>(asset_server: &AssetServer, handle: &Handle<A>) -> bool {
+    let failed =
+        |id: UntypedAssetId| matches!(asset_server.get_load_state(id), Some(LoadState::Failed(_)));
+    // Failures are reported against the file rather than assets labeled within it, such as the
+    // scenes of a glTF model.
+    failed(handle.id().untyped())
+        || handle
+            .path()
+            .and_then(|path| asset_server.get_path_id(path.without_label()))
+            .is_some_and(failed)
+}
@@ -0,0 +1,22 @@
+// Shimmering heat haze behind the ship's thrusters. There is no access to the rendered scene, so
+// instead of true refraction this draws a faint, noise-warped transparent overlay.
+#import bevy_pbr::forward_io::VertexOutput
+#import bevy_pbr::mesh_view_bindings::globals
+
+// x: strength, follows ship thrust
+@group(2) @binding(0)
+var<uniform> params: vec4<f32>;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let t = globals.time;
+    // u: 0 at the nozzles, 1 at the far end of the plume; v: distance from the plume axis
+    let u = 1.0 - in.uv.x;
+    let v = in.uv.y - 0.5;
+
+    let warp = sin(u * 18.0 - t * 20.0 + sin(v * 25.0 + t * 7.0) * 1.5);
+    let ripple = sin(v * 40.0 + warp * 2.0 + t * 9.0);
+    let shimmer = 0.5 + 0.5 * ripple * warp;
+    let envelope = (1.0 - u) * smoothstep(0.0, 0.1, u) * smoothstep(0.5, 0.1, abs(v));
+    return vec4<f32>(1.0, 0.9, 0.8, params.x * envelope * shimmer * 0.25);
+}
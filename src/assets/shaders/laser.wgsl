@@ -5,6 +5,18 @@
 @group(2) @binding(100)
 var<uniform> color: vec4<f32>;
 
+// x: hue cycle rate, in degrees per second
+// y: direction of travel, 1.0 for right or -1.0 for left
+@group(2) @binding(101)
+var<uniform> params: vec4<f32>;
+
+// Rotate the hue of a color around the gray axis.
+fn hue_rotate(c: vec3<f32>, angle: f32) -> vec3<f32> {
+    let k = vec3<f32>(0.57735);
+    let cos_a = cos(angle);
+    return c * cos_a + cross(k, c) * sin(angle) + k * dot(k, c) * (1.0 - cos_a);
+}
+
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let t = globals.time;
@@ -15,5 +27,9 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
         sin(in.uv.x * 45. - t * 8.) +
         sin(in.uv.x * 93. + t * 10.) +
         sin(in.uv.x * 267. - t * 14.));
-    return vec4<f32>(color.rgb, color.a * ya * xa * xb);
+    // The beam is brightest at its leading end, and fades towards the tail.
+    let lead = select(1.0 - in.uv.x, in.uv.x, params.y > 0.0);
+    let xc = mix(0.4, 1.0, lead);
+    let rgb = clamp(hue_rotate(color.rgb, radians(params.x) * t), vec3<f32>(0.0), vec3<f32>(1.0));
+    return vec4<f32>(rgb, color.a * ya * xa * xb * xc);
 }
@@ -1,5 +1,6 @@
-// Draw mountains with gradients in SRGB
-#import bevy_pbr::forward_io::VertexOutput
+// Draw mountains with gradients in SRGB, with snow caps above the snow line, a highlight
+// along the ridge, and an optional glow around the peaks.
+#import bevy_pbr::{forward_io::VertexOutput, mesh_view_bindings::globals}
 
 @group(2) @binding(100)
 var<uniform> color_start: vec4<f32>;
@@ -7,11 +8,42 @@ var<uniform> color_start: vec4<f32>;
 @group(2) @binding(101)
 var<uniform> color_end: vec4<f32>;
 
+@group(2) @binding(102)
+var<uniform> snow_color: vec4<f32>;
+
+// Alpha is the strength of the highlight
+@group(2) @binding(103)
+var<uniform> ridge_color: vec4<f32>;
+
+// x: snow line height, y: snow blend height
+@group(2) @binding(104)
+var<uniform> params: vec4<f32>;
+
+// Alpha is the strength of the glow
+@group(2) @binding(105)
+var<uniform> glow_color: vec4<f32>;
+
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     // Gradient along the Y axis
     let t = 1.0 - clamp(in.uv.y * 3., 0.0, 1.0);
-    let color = mix(color_start, color_end, t);
+    var color = mix(color_start, color_end, t);
+
+    // Snow caps
+    let snow = smoothstep(params.x, params.x + params.y, in.uv.y);
+    color = mix(color, snow_color, snow);
+
+#ifdef VERTEX_UVS_B
+    // Ridge highlight: the second UV set is 1.0 along the ridge line.
+    let ridge = smoothstep(0.92, 1.0, in.uv_b.y) * ridge_color.a;
+    color = vec4<f32>(mix(color.rgb, ridge_color.rgb, ridge), color.a);
+#endif
+
+    // Glow around the peaks, e.g. lava in volcanic craters
+    let pulse = 0.75 + 0.25 * sin(globals.time * 2.0 + in.uv.x * 20.0);
+    let glow = smoothstep(0.18, 0.3, in.uv.y) * glow_color.a * pulse;
+    color = vec4<f32>(mix(color.rgb, glow_color.rgb, glow), color.a);
+
     return vec4<f32>(srgb_to_linear(color.rgb), color.a);
 }
 
@@ -0,0 +1,37 @@
+// Scanner-style minimap: the marker texture rendered by the scanner camera, tinted like an old
+// phosphor display, with a sweep line and a little static.
+#import bevy_ui::ui_vertex_output::UiVertexOutput
+
+// x: elapsed time in seconds
+@group(1) @binding(0)
+var<uniform> params: vec4<f32>;
+@group(1) @binding(1)
+var scanner_texture: texture_2d<f32>;
+@group(1) @binding(2)
+var scanner_sampler: sampler;
+
+const SWEEP_PERIOD: f32 = 2.0;
+const PHOSPHOR: vec3<f32> = vec3<f32>(0.3, 1.0, 0.5);
+
+fn hash(p: vec2<f32>) -> f32 {
+    return fract(sin(dot(p, vec2<f32>(12.9898, 78.233))) * 43758.5453);
+}
+
+@fragment
+fn fragment(in: UiVertexOutput) -> @location(0) vec4<f32> {
+    let t = params.x;
+    let markers = textureSample(scanner_texture, scanner_sampler, in.uv);
+
+    // Distance behind the sweep line, wrapping around; markers glow brightest just after the
+    // sweep passes over them.
+    let sweep = fract(t / SWEEP_PERIOD);
+    let behind = fract(sweep - in.uv.x);
+    let afterglow = mix(0.5, 1.0, exp(-behind * 6.0));
+    let line = smoothstep(0.01, 0.0, behind) * 0.6;
+
+    let cell = floor(in.uv * vec2<f32>(256.0, 64.0));
+    let noise = hash(cell + floor(t * 24.0)) * 0.08;
+
+    let color = markers.rgb * afterglow + PHOSPHOR * (line + noise);
+    return vec4<f32>(color, 0.85);
+}
@@ -0,0 +1,36 @@
+// Translucent shield bubble around the player ship. The rim glows faintly; when a hit is
+// absorbed, the whole bubble flashes and a ring ripples outwards from the point of impact.
+#import bevy_pbr::forward_io::VertexOutput
+#import bevy_pbr::mesh_view_bindings::view
+
+@group(2) @binding(0)
+var<uniform> color: vec4<f32>;
+
+// x: opacity, follows shield strength; y: flash brightness
+@group(2) @binding(1)
+var<uniform> params: vec4<f32>;
+
+// xyz: world-space direction of the last impact; w: seconds since the impact
+@group(2) @binding(2)
+var<uniform> impact: vec4<f32>;
+
+// Speed at which the ripple spreads, in radians per second
+const RIPPLE_SPEED: f32 = 6.0;
+// Duration of the ripple, in seconds
+const RIPPLE_TIME: f32 = 0.6;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let normal = normalize(in.world_normal);
+    let to_eye = normalize(view.world_position - in.world_position.xyz);
+    let rim = pow(1.0 - abs(dot(normal, to_eye)), 3.0);
+
+    // Angular distance from the impact point, compared with the radius of the ripple ring
+    let angle = acos(clamp(dot(normal, normalize(impact.xyz)), -1.0, 1.0));
+    let front = impact.w * RIPPLE_SPEED;
+    let fade = clamp(1.0 - impact.w / RIPPLE_TIME, 0.0, 1.0);
+    let ring = exp(-pow((angle - front) * 6.0, 2.0)) * fade;
+
+    let alpha = params.x * (0.15 + rim * 0.6) + params.y * 0.3 + ring * 0.8;
+    return vec4<f32>(color.rgb * (1.0 + ring * 2.0 + params.y), clamp(alpha, 0.0, 1.0) * color.a);
+}
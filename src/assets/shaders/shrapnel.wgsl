@@ -0,0 +1,77 @@
+// Animate explosion fragments. Each fragment spins around its own axis and radiates outward,
+// computed from the elapsed time of the effect. Fragments which reach the ground bounce off it.
+#import bevy_pbr::{
+    forward_io::VertexOutput,
+    mesh_functions,
+    view_transformations::position_world_to_clip,
+}
+
+// x: elapsed time of the effect, y: ground height relative to the effect origin,
+// z: rotation of the effect around the Z axis
+@group(2) @binding(100)
+var<uniform> params: vec4<f32>;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) velocity: vec2<f32>,
+    @location(3) spin_axis: vec3<f32>,
+};
+
+const SPIN_RATE: f32 = 16.0;
+
+// Fraction of velocity retained after bouncing off the ground
+const BOUNCE_DAMPING: f32 = 0.4;
+
+// Offset of a fragment from the effect origin at time t, in world orientation, bouncing off the
+// ground line.
+fn fragment_offset(velocity: vec2<f32>, t: f32, ground: f32) -> vec2<f32> {
+    let offset = velocity * t;
+    if velocity.y >= 0.0 || offset.y >= ground {
+        return offset;
+    }
+    let t_hit = max(ground / velocity.y, 0.0);
+    let after = (t - t_hit) * BOUNCE_DAMPING;
+    return vec2<f32>(velocity.x * (t_hit + after), min(ground, 0.0) - velocity.y * after);
+}
+
+// Rotate a vector around a unit axis (Rodrigues' rotation formula).
+fn rotate(v: vec3<f32>, axis: vec3<f32>, angle: f32) -> vec3<f32> {
+    let c = cos(angle);
+    let s = sin(angle);
+    return v * c + cross(axis, v) * s + axis * dot(axis, v) * (1.0 - c);
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let t = params.x;
+    let angle = t * SPIN_RATE;
+    // The effect is rotated around Z, so convert the velocity to world orientation to find the
+    // ground, and the resulting offset back again.
+    let c = cos(params.z);
+    let s = sin(params.z);
+    let world_velocity = vec2<f32>(
+        c * vertex.velocity.x - s * vertex.velocity.y,
+        s * vertex.velocity.x + c * vertex.velocity.y);
+    let offset = fragment_offset(world_velocity, t, params.y);
+    let local_offset = vec2<f32>(c * offset.x + s * offset.y, -s * offset.x + c * offset.y);
+    let local_position = rotate(vertex.position, vertex.spin_axis, angle)
+        + vec3<f32>(local_offset, 0.0);
+    let local_normal = rotate(vertex.normal, vertex.spin_axis, angle);
+
+    var out: VertexOutput;
+    let world_from_local = mesh_functions::get_world_from_local(vertex.instance_index);
+    out.world_position =
+        mesh_functions::mesh_position_local_to_world(world_from_local, vec4<f32>(local_position, 1.0));
+    out.position = position_world_to_clip(out.world_position.xyz);
+    out.world_normal = mesh_functions::mesh_normal_local_to_world(local_normal, vertex.instance_index);
+#ifdef VERTEX_OUTPUT_INSTANCE_INDEX
+    out.instance_index = vertex.instance_index;
+#endif
+#ifdef VISIBILITY_RANGE_DITHER
+    out.visibility_range_dither = mesh_functions::get_visibility_range_dither_level(
+        vertex.instance_index, world_from_local[3]);
+#endif
+    return out;
+}
@@ -0,0 +1,63 @@
+// Draw the starfield. Each star is a quad; parallax scrolling and wraparound are computed here
+// so that the whole starfield is a single mesh.
+#import bevy_pbr::mesh_functions::{get_world_from_local, mesh_position_local_to_world}
+#import bevy_pbr::view_transformations::position_world_to_clip
+
+// x: viewpoint position, y: playfield width, z: speed streak amount
+@group(2) @binding(0)
+var<uniform> params: vec4<f32>;
+
+@group(2) @binding(1)
+var star_texture: texture_2d<f32>;
+
+@group(2) @binding(2)
+var star_sampler: sampler;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) color: vec4<f32>,
+    // x, y: offset, z: parallax speed, w: size
+    @location(3) star: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let viewpoint = params.x;
+    let width = params.y;
+    let speed = vertex.star.z;
+    let size = vertex.star.w;
+
+    // Parallax scrolling: offset each star by its speed relative to the camera offset, and then
+    // use modulo to implement wrap-around.
+    let dist_traveled = width * speed;
+    let x = vertex.star.x + dist_traveled * 0.5 - viewpoint * speed;
+    let center = vec2<f32>(
+        x - dist_traveled * floor(x / dist_traveled) - dist_traveled * 0.5,
+        vertex.star.y);
+
+    // At high ship speed, stretch each star along the direction of travel. Nearer (faster) stars
+    // are stretched more. The star is dimmed to keep its overall brightness about the same.
+    let stretch = 1.0 + abs(params.z) * speed * 12.0;
+    let local = vec4<f32>(center + vertex.position.xy * vec2<f32>(size * stretch, size), 0.0, 1.0);
+    let world_from_local = get_world_from_local(vertex.instance_index);
+    let world_position = mesh_position_local_to_world(world_from_local, local);
+
+    var out: VertexOutput;
+    out.clip_position = position_world_to_clip(world_position.xyz);
+    out.uv = vertex.uv;
+    out.color = vec4<f32>(vertex.color.rgb, vertex.color.a / sqrt(stretch));
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(star_texture, star_sampler, in.uv) * in.color;
+}
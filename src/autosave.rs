@@ -0,0 +1,100 @@
+//! Incremental saving of persistent data, so that a crash or force-quit loses at most a few
+//! seconds of progress.
+use bevy::prelude::*;
+
+use crate::{
+    high_scores::{HighScores, save_high_scores},
+    profile::{Profile, save_profile},
+    settings::{Settings, save_settings},
+};
+
+/// Once persistent data has changed, it is saved after it has been left alone for this long, in
+/// seconds...
+const SETTLE_TIME: f32 = 1.0;
+
+/// ...or after this long, if it keeps changing. Lifetime statistics such as distance traveled
+/// change every frame during play.
+const MAX_DELAY: f32 = 10.0;
+
+/// When each kind of persistent data first and last changed since it was saved.
+#[derive(Default, Debug)]
+struct Dirty {
+    first: f32,
+    last: f32,
+}
+
+impl Dirty {
+    fn due(&self, now: f32) -> bool {
+        now - self.last >= SETTLE_TIME || now - self.first >= MAX_DELAY
+    }
+}
+
+/// Unsaved changes to persistent data
+#[derive(Resource, Default, Debug)]
+pub struct Autosave {
+    profile: Option<Dirty>,
+    settings: Option<Dirty>,
+    high_scores: Option<Dirty>,
+}
+
+fn mark(dirty: &mut Option<Dirty>, changed: bool, now: f32) {
+    if !changed {
+        return;
+    }
+    match dirty {
+        Some(dirty) => dirty.last = now,
+        None => {
+            *dirty = Some(Dirty {
+                first: now,
+                last: now,
+            })
+        }
+    }
+}
+
+/// Take a pending save if it is due, or unconditionally if `force` is set.
+fn take_due(dirty: &mut Option<Dirty>, now: f32, force: bool) -> bool {
+    dirty.take_if(|dirty| force || dirty.due(now)).is_some()
+}
+
+/// Note which persistent data has changed this frame, and write any which is due. Everything
+/// outstanding is written when the app exits. This uses the real clock, so that saving carries
+/// on while the game is paused.
+pub(crate) fn autosave(
+    mut r_autosave: ResMut<Autosave>,
+    r_profile: Res<Profile>,
+    r_settings: Res<Settings>,
+    r_scores: Res<HighScores>,
+    r_time: Res<Time<Real>>,
+    mut e_exit: EventReader<AppExit>,
+) {
+    let now = r_time.elapsed_secs();
+    let exiting = e_exit.read().count() > 0;
+    let autosave = &mut *r_autosave;
+
+    mark(
+        &mut autosave.profile,
+        r_profile.is_changed() && !r_profile.is_added(),
+        now,
+    );
+    mark(
+        &mut autosave.settings,
+        r_settings.is_changed() && !r_settings.is_added(),
+        now,
+    );
+    mark(
+        &mut autosave.high_scores,
+        r_scores.is_changed() && !r_scores.is_added(),
+        now,
+    );
+
+    if take_due(&mut autosave.profile, now, exiting) {
+        save_profile(&r_profile);
+    }
+    if take_due(&mut autosave.settings, now, exiting) {
+        save_settings(&r_settings);
+    }
+    if take_due(&mut autosave.high_scores, now, exiting) {
+        save_high_scores(&r_scores);
+    }
+}
@@ -0,0 +1,184 @@
+//! Barrel roll: a quick dodge which spins the ship about its long axis and hops it sideways out
+//! of the line of fire, leaving a trail behind it. The ship can't be hit while rolling, and the
+//! move has a cooldown, shown on the HUD.
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+
+use crate::{
+    Dodge, MainInput, Move, Playfield, UnitPosition,
+    damage::Invulnerable,
+    explosion::DustEffect,
+    game_state::{DespawnOnExit, GameState, PauseState},
+    ship::PlayerShip,
+};
+
+/// Barrel roll state of the player ship
+#[derive(Component, Default, Debug)]
+pub struct BarrelRoll {
+    /// Time remaining in the current roll, in seconds
+    remaining: f32,
+
+    /// Time until the ship can roll again, in seconds
+    cooldown: f32,
+
+    /// Direction of the hop: 1 for up, -1 for down
+    direction: f32,
+
+    /// Time until the next trail puff
+    trail_timer: f32,
+}
+
+impl BarrelRoll {
+    /// Whether the ship is rolling.
+    pub fn active(&self) -> bool {
+        self.remaining > 0.0
+    }
+
+    /// Extra rotation about the ship's long axis, in radians. The roll starts fast and eases
+    /// out, turning the ship over more than once.
+    pub fn angle(&self) -> f32 {
+        if !self.active() {
+            return 0.0;
+        }
+        let t = 1.0 - self.remaining / ROLL_TIME;
+        let eased = 1.0 - (1.0 - t) * (1.0 - t);
+        eased * ROLL_TURNS * TAU * -self.direction
+    }
+}
+
+/// Marker for the fill bar of the roll cooldown gauge
+#[derive(Component, Default, Debug)]
+pub struct RollGauge;
+
+/// Length of a roll, in seconds
+const ROLL_TIME: f32 = 0.45;
+
+/// Number of complete turns in a roll. This must be whole, so that the ship ends the roll the
+/// right way up.
+const ROLL_TURNS: f32 = 2.0;
+
+/// Time between rolls, measured from the start of a roll, in seconds
+const ROLL_COOLDOWN: f32 = 2.0;
+
+/// Invulnerability granted by a roll, in seconds
+const ROLL_INVULNERABILITY: f32 = 0.4;
+
+/// Initial vertical speed of the hop, slowing to zero by the end of the roll
+const HOP_SPEED: f32 = 0.6;
+
+/// Time between trail puffs, in seconds
+const TRAIL_INTERVAL: f32 = 0.03;
+
+const TRAIL_COLOR: Color = Color::srgb(0.5, 0.7, 1.0);
+const GAUGE_COLOR: Color = Color::srgb(0.4, 0.7, 1.0);
+const READY_COLOR: Color = Color::srgb(0.3, 1.0, 0.5);
+
+/// Start a roll when the dodge button is pressed. The ship hops in the direction the stick is
+/// pushed, or upwards if it is centered.
+pub(crate) fn start_barrel_roll(
+    trigger: Trigger<Started<Dodge>>,
+    mut commands: Commands,
+    mut q_player: Query<(&Actions<MainInput>, &mut BarrelRoll, Has<Invulnerable>)>,
+    r_pause: Option<Res<State<PauseState>>>,
+) {
+    if r_pause.is_none_or(|state| *state.get() != PauseState::Running) {
+        return;
+    }
+    let Ok((actions, mut roll, invulnerable)) = q_player.get_mut(trigger.target()) else {
+        return;
+    };
+    if roll.cooldown > 0.0 {
+        return;
+    }
+    let stick = actions
+        .get::<Move>()
+        .map_or(Vec2::ZERO, |action| action.value().as_axis2d());
+    roll.direction = if stick.y < 0.0 { -1.0 } else { 1.0 };
+    roll.remaining = ROLL_TIME;
+    roll.cooldown = ROLL_COOLDOWN;
+    roll.trail_timer = 0.0;
+    // Don't cut short a longer invulnerability, such as after respawning.
+    if !invulnerable {
+        commands
+            .entity(trigger.target())
+            .insert(Invulnerable::brief(ROLL_INVULNERABILITY));
+    }
+}
+
+/// Advance the roll, moving the ship through its hop and leaving a trail.
+pub(crate) fn update_barrel_roll(
+    mut commands: Commands,
+    mut q_player: Query<(&mut BarrelRoll, &mut UnitPosition), With<PlayerShip>>,
+    r_playfield: Res<Playfield>,
+    r_time: Res<Time>,
+) {
+    let dt = r_time.delta_secs();
+    for (mut roll, mut position) in q_player.iter_mut() {
+        roll.cooldown = (roll.cooldown - dt).max(0.0);
+        if !roll.active() {
+            continue;
+        }
+        let speed = HOP_SPEED * roll.remaining / ROLL_TIME;
+        position.0.y = (position.0.y + roll.direction * speed * dt)
+            .clamp(r_playfield.floor, r_playfield.ceiling);
+        roll.remaining = (roll.remaining - dt).max(0.0);
+
+        roll.trail_timer -= dt;
+        if roll.trail_timer <= 0.0 {
+            roll.trail_timer = TRAIL_INTERVAL;
+            commands.spawn((
+                DustEffect {
+                    size: 0.01,
+                    color: TRAIL_COLOR,
+                },
+                UnitPosition(position.0),
+            ));
+        }
+    }
+}
+
+pub(crate) fn spawn_roll_gauge(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(12.0),
+            bottom: Val::Px(52.0),
+            width: Val::Px(120.0),
+            height: Val::Px(6.0),
+            border: UiRect::all(Val::Px(1.0)),
+            ..default()
+        },
+        BorderColor(Color::srgb(0.5, 0.5, 0.6)),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+        DespawnOnExit(GameState::Playing),
+        children![(
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(READY_COLOR),
+            RollGauge,
+        )],
+    ));
+}
+
+/// Fill the gauge as the roll recharges. It is full, and changes color, when a roll is ready.
+pub(crate) fn update_roll_gauge(
+    q_player: Query<&BarrelRoll, With<PlayerShip>>,
+    mut q_gauge: Query<(&mut Node, &mut BackgroundColor), With<RollGauge>>,
+) {
+    let Ok(roll) = q_player.single() else {
+        return;
+    };
+    for (mut node, mut color) in q_gauge.iter_mut() {
+        node.width = Val::Percent((1.0 - roll.cooldown / ROLL_COOLDOWN) * 100.0);
+        color.0 = if roll.cooldown > 0.0 {
+            GAUGE_COLOR
+        } else {
+            READY_COLOR
+        };
+    }
+}
@@ -0,0 +1,222 @@
+//! Bombs dropped by the player ship, used against targets on the ground.
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+};
+use bevy_enhanced_input::prelude::*;
+
+use crate::{
+    DropBomb, Enemy, EnemyHit, FX_DEPTH, Playfield, SeamMirrored, SmartBomb, UnitPosition,
+    Viewpoint,
+    explosion::{FlareEffect, ShrapnelEffect},
+    game_state::{DespawnOnExit, GameState, PauseState},
+    score::Reserves,
+    ship::PlayerShip,
+    spatial::SpatialHash,
+    terrain::Terrain,
+    time_scale::SlowMotion,
+};
+
+/// A falling bomb
+#[derive(Component, Default, Debug)]
+pub struct Bomb {
+    velocity: Vec2,
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct BombMesh {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+/// Downward acceleration of bombs
+const BOMB_GRAVITY: f32 = 0.8;
+
+/// Initial downward velocity of a bomb as it leaves the ship
+const BOMB_DROP_SPEED: f32 = 0.1;
+
+/// Fraction of the ship's horizontal velocity inherited by the bomb
+const BOMB_INHERIT: f32 = 0.6;
+
+/// Maximum number of bombs in flight at once
+const MAX_BOMBS: usize = 2;
+
+/// Enemies within this distance of the impact are hit by the blast.
+const BLAST_RADIUS: f32 = 0.12;
+
+/// A smart bomb hits every enemy within this horizontal distance of the viewpoint, which covers
+/// the widest allowed screen.
+const SMART_BOMB_RANGE: f32 = 1.25;
+
+/// Damage dealt by a smart bomb; enough to destroy anything.
+const SMART_BOMB_DAMAGE: f32 = 10.0;
+
+/// Game speed during the slow motion after a smart bomb clears the screen
+const SMART_BOMB_SLOW_MOTION: f32 = 0.3;
+
+pub(crate) fn setup_bombs(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut bomb_mesh: ResMut<BombMesh>,
+) {
+    bomb_mesh.mesh = meshes.add(Sphere::new(0.008).mesh().ico(2).unwrap());
+    bomb_mesh.material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.3, 0.3, 0.3),
+        emissive: LinearRgba::new(2.0, 0.6, 0.1, 1.0),
+        ..default()
+    });
+}
+
+/// Drop a bomb from the ship, which falls in an arc under gravity.
+pub(crate) fn drop_bomb(
+    _trigger: Trigger<Started<DropBomb>>,
+    mut commands: Commands,
+    q_player: Query<(&PlayerShip, &UnitPosition)>,
+    q_bombs: Query<(), With<Bomb>>,
+    r_pause: Option<Res<State<PauseState>>>,
+    bomb_mesh: Res<BombMesh>,
+) {
+    if r_pause.is_none_or(|state| *state.get() != PauseState::Running) {
+        return;
+    }
+    if q_bombs.iter().count() >= MAX_BOMBS {
+        return;
+    }
+    let Ok((ship, position)) = q_player.single() else {
+        return;
+    };
+    commands.spawn((
+        Bomb {
+            velocity: Vec2::new(ship.speed() * BOMB_INHERIT, -BOMB_DROP_SPEED),
+        },
+        UnitPosition(position.0),
+        Mesh3d(bomb_mesh.mesh.clone()),
+        MeshMaterial3d(bomb_mesh.material.clone()),
+        Transform::from_xyz(0., 0., FX_DEPTH),
+        SeamMirrored,
+        DespawnOnExit(GameState::Playing),
+    ));
+}
+
+/// Detonate a smart bomb, destroying every enemy on screen. With none left in reserve, the
+/// launcher just fizzles.
+pub(crate) fn detonate_smart_bomb(
+    _trigger: Trigger<Started<SmartBomb>>,
+    mut commands: Commands,
+    q_player: Query<&UnitPosition, With<PlayerShip>>,
+    q_enemies: Query<(Entity, &UnitPosition), With<Enemy>>,
+    r_pause: Option<Res<State<PauseState>>>,
+    mut r_reserves: ResMut<Reserves>,
+    r_viewpoint: Res<Viewpoint>,
+    r_playfield: Res<Playfield>,
+    asset_server: Res<AssetServer>,
+) {
+    if r_pause.is_none_or(|state| *state.get() != PauseState::Running) {
+        return;
+    }
+    if r_reserves.smart_bombs == 0 {
+        commands.spawn((
+            AudioPlayer::new(asset_server.load("sounds/rattle.ogg")),
+            PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                volume: Volume::Linear(0.5),
+                speed: 1.8,
+                ..default()
+            },
+        ));
+        return;
+    }
+    let Ok(position) = q_player.single() else {
+        return;
+    };
+
+    r_reserves.smart_bombs -= 1;
+    let mut cleared = false;
+    for (enemy, enemy_pos) in q_enemies.iter() {
+        if r_playfield
+            .relative(enemy_pos.0.x, r_viewpoint.position)
+            .abs()
+            < SMART_BOMB_RANGE
+        {
+            commands.entity(enemy).trigger(EnemyHit {
+                damage: SMART_BOMB_DAMAGE,
+            });
+            cleared = true;
+        }
+    }
+    // Clearing the screen is worth savoring.
+    if cleared {
+        commands.trigger(SlowMotion::slow(SMART_BOMB_SLOW_MOTION, 0.8));
+    }
+    commands.spawn((
+        AudioPlayer::new(asset_server.load("sounds/softexplode.ogg")),
+        PlaybackSettings {
+            mode: PlaybackMode::Despawn,
+            speed: 0.6,
+            ..default()
+        },
+    ));
+    commands.spawn((
+        FlareEffect {
+            size: 0.1,
+            velocity: Vec2::default(),
+        },
+        UnitPosition(position.0),
+    ));
+}
+
+/// Move bombs, and detonate them when they reach the ground. The blast destroys any enemies
+/// nearby.
+pub(crate) fn update_bombs(
+    mut commands: Commands,
+    mut q_bombs: Query<(Entity, &mut Bomb, &mut UnitPosition), Without<Enemy>>,
+    q_enemies: Query<(Entity, &UnitPosition), With<Enemy>>,
+    asset_server: Res<AssetServer>,
+    r_terrain: Res<Terrain>,
+    r_playfield: Res<Playfield>,
+    r_hash: Res<SpatialHash>,
+    r_time: Res<Time>,
+) {
+    let dt = r_time.delta_secs();
+    for (entity, mut bomb, mut position) in q_bombs.iter_mut() {
+        bomb.velocity.y -= BOMB_GRAVITY * dt;
+        position.0 += bomb.velocity * dt;
+        position.0.x = r_playfield.wrap(position.0.x);
+
+        if !r_terrain.is_below(position.0) {
+            continue;
+        }
+
+        let impact = Vec2::new(position.0.x, r_terrain.height_at(position.0.x));
+        commands.entity(entity).despawn();
+        // Candidates come from the spatial hash, but use up-to-date positions for the blast.
+        let candidates = r_hash
+            .within(impact, BLAST_RADIUS * 2.0)
+            .filter_map(|(enemy, _)| q_enemies.get(enemy).ok());
+        for (enemy, enemy_pos) in candidates {
+            if r_playfield.distance(impact, enemy_pos.0) < BLAST_RADIUS {
+                commands.entity(enemy).trigger(EnemyHit { damage: 1.0 });
+            }
+        }
+        commands.spawn((
+            AudioPlayer::new(asset_server.load("sounds/softexplode.ogg")),
+            PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                ..default()
+            },
+        ));
+        commands.spawn((
+            FlareEffect {
+                size: 0.02,
+                velocity: Vec2::default(),
+            },
+            UnitPosition(impact),
+        ));
+        commands.spawn((
+            ShrapnelEffect {
+                velocity: Vec2::Y * 0.2,
+            },
+            UnitPosition(impact),
+        ));
+    }
+}
@@ -0,0 +1,439 @@
+//! Boss: a mothership which joins the last wave of each loop and hovers over the player ship.
+//! Each of its parts is a child collider with its own health: a ring of turrets which fire at
+//! the player, and the core, which is shielded until every turret has been destroyed. Turrets
+//! and the core each explode in their own way.
+//!
+//! The parts are children of the mothership rather than units of their own, so they aren't seam
+//! mirrored; the mothership keeps close to the player ship, well away from the wrap seam.
+use avian2d::prelude::{Collider, CollisionLayers, RigidBody};
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+};
+use rand::Rng;
+
+use crate::{
+    ENEMY_LAYER, Enemy, EnemyDestroyed, EnemyHit, EnemyKind, PLAYER_LAYER, PLAYER_SHOT_LAYER,
+    Playfield, RandomGenerator, SHIP_DEPTH, UnitPosition, Viewpoint,
+    enemy_shot::{EnemyShotAssets, spawn_enemy_shot},
+    explosion::{ExplosionSound, FlareEffect, ShrapnelEffect, SparkEffect},
+    game_state::{DespawnOnExit, GameState, Level},
+    scene_anim::PlaySceneAnimation,
+    ship::PlayerShip,
+    steering::{apply_steering, arrive},
+    ticker::TickerMessage,
+};
+
+/// The mothership. Its parts are child entities.
+#[derive(Component, Debug)]
+pub struct Boss {
+    velocity: Vec2,
+
+    /// Number of turrets still standing. The core is shielded until this reaches zero.
+    turrets: usize,
+}
+
+/// A part of the mothership which can be shot at
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BossPart {
+    Turret,
+    Core,
+}
+
+/// Remaining hit points of a part which takes several hits to destroy
+#[derive(Component, Debug)]
+pub struct Health(pub f32);
+
+/// A turret on the mothership, which fires at the player
+#[derive(Component, Debug)]
+pub struct BossTurret {
+    /// Time until the next shot
+    cooldown: f32,
+}
+
+/// Shield around the mothership's core
+#[derive(Component, Default, Debug)]
+pub struct BossShield;
+
+/// Shared meshes and materials for the mothership
+#[derive(Resource, Default, Debug)]
+pub struct BossAssets {
+    turret_mesh: Handle<Mesh>,
+    turret_material: Handle<StandardMaterial>,
+    shield_mesh: Handle<Mesh>,
+    shield_material: Handle<StandardMaterial>,
+}
+
+/// Altitude at which the mothership hovers
+const BOSS_ALTITUDE: f32 = 0.25;
+
+/// Top speed of the mothership; a little slower than the player ship's cruising speed
+const BOSS_SPEED: Vec2 = Vec2::new(0.35, 0.15);
+
+/// Acceleration of the mothership
+const BOSS_ACCEL: f32 = 0.4;
+
+/// The mothership slows as it comes within this distance of its station over the player.
+const BOSS_SLOWING_RADIUS: f32 = 0.5;
+
+/// Scale of the saucer model used for the core
+const CORE_SCALE: f32 = 0.035;
+
+/// Hit points of the core, once its shield is down
+const CORE_HEALTH: f32 = 12.0;
+
+/// Hit points of each turret
+const TURRET_HEALTH: f32 = 3.0;
+
+/// Radius of a turret's body and collider
+const TURRET_RADIUS: f32 = 0.016;
+
+/// Position of each turret relative to the core. They sit clear of the core above and below, so
+/// that a shot aimed at a turret isn't caught by the core's shield.
+const TURRET_OFFSETS: [Vec2; 4] = [
+    Vec2::new(-0.07, 0.07),
+    Vec2::new(0.07, 0.07),
+    Vec2::new(-0.07, -0.065),
+    Vec2::new(0.07, -0.065),
+];
+
+/// Time between shots from each turret, in seconds
+const TURRET_INTERVAL: f32 = 3.0;
+
+/// Turrets only fire at a player within this horizontal distance.
+const TURRET_RANGE: f32 = 1.2;
+
+/// Number of sparks thrown out by a destroyed turret
+const TURRET_SPARKS: usize = 12;
+
+/// Size of the shield around the core
+const SHIELD_SIZE: Vec3 = Vec3::new(0.15, 0.065, 0.065);
+
+const SHIELD_DOWN_COLOR: Color = Color::srgb(0.4, 1.0, 0.5);
+
+pub(crate) fn setup_boss(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut r_assets: ResMut<BossAssets>,
+) {
+    r_assets.turret_mesh = meshes.add(Sphere::new(TURRET_RADIUS).mesh().ico(2).unwrap());
+    r_assets.turret_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.6, 0.15, 0.1),
+        emissive: LinearRgba::new(0.8, 0.1, 0.05, 1.0),
+        metallic: 0.8,
+        perceptual_roughness: 0.3,
+        ..default()
+    });
+    r_assets.shield_mesh = meshes.add(Sphere::new(1.0).mesh().ico(3).unwrap());
+    r_assets.shield_material = materials.add(StandardMaterial {
+        base_color: Color::srgba(0.3, 0.7, 1.0, 0.25),
+        alpha_mode: AlphaMode::Add,
+        unlit: true,
+        ..default()
+    });
+}
+
+/// The mothership arrives from above at the start of the last wave of each loop.
+pub(crate) fn spawn_boss(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    r_assets: Res<BossAssets>,
+    r_level: Res<Level>,
+    r_viewpoint: Res<Viewpoint>,
+    r_playfield: Res<Playfield>,
+) {
+    if !r_level.is_final_wave() {
+        return;
+    }
+    let boss = commands
+        .spawn((
+            Boss {
+                velocity: Vec2::ZERO,
+                turrets: TURRET_OFFSETS.len(),
+            },
+            Enemy,
+            RigidBody::Kinematic,
+            UnitPosition(Vec2::new(
+                r_playfield.wrap(r_viewpoint.position + 1.0),
+                r_playfield.ceiling + 0.3,
+            )),
+            Transform::from_xyz(0., 0., SHIP_DEPTH),
+            Visibility::default(),
+            DespawnOnExit(GameState::Playing),
+        ))
+        .observe(boss_hit)
+        .id();
+
+    commands
+        .spawn((
+            BossPart::Core,
+            Health(CORE_HEALTH),
+            SceneRoot(asset_server.load(GltfAssetLabel::Scene(0).from_asset("models/saucer.glb"))),
+            PlaySceneAnimation {
+                clip: asset_server
+                    .load(GltfAssetLabel::Animation(0).from_asset("models/saucer.glb")),
+                repeat: true,
+            },
+            Collider::capsule_endpoints(1.0, Vec2::new(-2.5, 0.2), Vec2::new(2.5, 0.2)),
+            CollisionLayers::from_bits(ENEMY_LAYER, PLAYER_LAYER | PLAYER_SHOT_LAYER),
+            Transform::from_scale(Vec3::splat(CORE_SCALE)).with_rotation(Quat::from_euler(
+                EulerRot::XYZ,
+                0.1,
+                0.2,
+                0.0,
+            )),
+            ChildOf(boss),
+        ))
+        .observe(boss_part_hit);
+
+    for (index, offset) in TURRET_OFFSETS.into_iter().enumerate() {
+        commands
+            .spawn((
+                BossPart::Turret,
+                Health(TURRET_HEALTH),
+                BossTurret {
+                    // Stagger the turrets so that they don't all fire at once.
+                    cooldown: TURRET_INTERVAL * (1.0 + index as f32 / TURRET_OFFSETS.len() as f32),
+                },
+                Collider::circle(TURRET_RADIUS),
+                CollisionLayers::from_bits(ENEMY_LAYER, PLAYER_LAYER | PLAYER_SHOT_LAYER),
+                Mesh3d(r_assets.turret_mesh.clone()),
+                MeshMaterial3d(r_assets.turret_material.clone()),
+                Transform::from_translation(offset.extend(0.)),
+                ChildOf(boss),
+            ))
+            .observe(boss_part_hit);
+    }
+
+    commands.spawn((
+        BossShield,
+        Mesh3d(r_assets.shield_mesh.clone()),
+        MeshMaterial3d(r_assets.shield_material.clone()),
+        Transform::from_scale(SHIELD_SIZE),
+        ChildOf(boss),
+    ));
+}
+
+/// Hold station above the player ship.
+pub(crate) fn update_boss(
+    mut q_bosses: Query<(&mut Boss, &mut UnitPosition)>,
+    q_player: Query<&UnitPosition, (With<PlayerShip>, Without<Boss>)>,
+    r_playfield: Res<Playfield>,
+    r_time: Res<Time>,
+) {
+    let dt = r_time.delta_secs();
+    let player = q_player.single().ok().map(|position| position.0.x);
+    for (mut boss, mut position) in q_bosses.iter_mut() {
+        let station = Vec2::new(player.unwrap_or(position.0.x), BOSS_ALTITUDE);
+        let force = arrive(
+            &r_playfield,
+            position.0,
+            boss.velocity,
+            station,
+            BOSS_SPEED.x,
+            BOSS_SLOWING_RADIUS,
+        );
+        boss.velocity = apply_steering(boss.velocity, force, BOSS_ACCEL, BOSS_SPEED, dt);
+        position.0 += boss.velocity * dt;
+        position.0.x = r_playfield.wrap(position.0.x);
+    }
+}
+
+/// Turrets fire a bolt at the player ship every so often, if it is within range.
+pub(crate) fn fire_boss_turrets(
+    mut commands: Commands,
+    mut q_turrets: Query<(&mut BossTurret, &Transform, &ChildOf)>,
+    q_bosses: Query<&UnitPosition, With<Boss>>,
+    q_player: Query<&UnitPosition, With<PlayerShip>>,
+    r_assets: Res<EnemyShotAssets>,
+    r_playfield: Res<Playfield>,
+    r_time: Res<Time>,
+) {
+    let Ok(target) = q_player.single() else {
+        return;
+    };
+    for (mut turret, transform, child_of) in q_turrets.iter_mut() {
+        let Ok(boss) = q_bosses.get(child_of.parent()) else {
+            continue;
+        };
+        turret.cooldown -= r_time.delta_secs();
+        if turret.cooldown > 0.0 {
+            continue;
+        }
+        let origin = boss.0 + transform.translation.truncate();
+        let delta = r_playfield.delta(origin, target.0);
+        if delta.x.abs() > TURRET_RANGE {
+            continue;
+        }
+        turret.cooldown = TURRET_INTERVAL;
+        let origin = Vec2::new(r_playfield.wrap(origin.x), origin.y);
+        spawn_enemy_shot(&mut commands, origin, delta, &r_assets);
+    }
+}
+
+/// Hits on the mothership as a whole, such as from a smart bomb, land on a turret if any are
+/// left, otherwise on the core.
+fn boss_hit(
+    trigger: Trigger<EnemyHit>,
+    mut commands: Commands,
+    q_children: Query<&Children>,
+    q_parts: Query<&BossPart>,
+) {
+    let Ok(children) = q_children.get(trigger.target()) else {
+        return;
+    };
+    let target = children
+        .iter()
+        .filter_map(|child| q_parts.get(child).ok().map(|part| (child, *part)))
+        .min_by_key(|(_, part)| *part == BossPart::Core);
+    if let Some((part, _)) = target {
+        commands.entity(part).trigger(EnemyHit {
+            damage: trigger.event().damage,
+        });
+    }
+}
+
+/// Damage a part of the mothership. The core shrugs off hits while its shield is up. Destroying
+/// the last turret drops the shield, and destroying the core destroys the whole mothership.
+fn boss_part_hit(
+    trigger: Trigger<EnemyHit>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut q_parts: Query<(&BossPart, &mut Health, &Transform, &ChildOf)>,
+    mut q_bosses: Query<(&mut Boss, &UnitPosition, &Children)>,
+    q_shields: Query<(), With<BossShield>>,
+    mut rng: ResMut<RandomGenerator>,
+) {
+    let Ok((part, mut health, transform, child_of)) = q_parts.get_mut(trigger.target()) else {
+        return;
+    };
+    let Ok((mut boss, boss_position, children)) = q_bosses.get_mut(child_of.parent()) else {
+        return;
+    };
+    let position = boss_position.0 + transform.translation.truncate();
+    if *part == BossPart::Core && boss.turrets > 0 {
+        commands.spawn((
+            FlareEffect {
+                size: 0.008,
+                velocity: Vec2::default(),
+            },
+            UnitPosition(position),
+        ));
+        return;
+    }
+    health.0 -= trigger.event().damage;
+    if health.0 > 0.0 {
+        return;
+    }
+
+    match part {
+        BossPart::Turret => {
+            commands.entity(trigger.target()).despawn();
+            commands.trigger(EnemyDestroyed {
+                kind: EnemyKind::BossTurret,
+                position,
+            });
+            explode_turret(&mut commands, &asset_server, &mut rng, position);
+
+            boss.turrets = boss.turrets.saturating_sub(1);
+            if boss.turrets == 0 {
+                for shield in children.iter().filter(|child| q_shields.contains(*child)) {
+                    commands.entity(shield).despawn();
+                }
+                drop_shield(&mut commands, &asset_server, boss_position.0);
+            }
+        }
+        BossPart::Core => {
+            commands.entity(child_of.parent()).despawn();
+            commands.trigger(EnemyDestroyed {
+                kind: EnemyKind::Boss,
+                position,
+            });
+            explode_core(&mut commands, &asset_server, &mut rng, position);
+        }
+    }
+}
+
+/// A turret goes up in a shower of sparks.
+fn explode_turret(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    rng: &mut RandomGenerator,
+    position: Vec2,
+) {
+    commands.spawn(ExplosionSound::for_enemy(EnemyKind::BossTurret).play(asset_server, &mut rng.0));
+    commands.spawn((
+        FlareEffect {
+            size: 0.012,
+            velocity: Vec2::default(),
+        },
+        UnitPosition(position),
+    ));
+    for _ in 0..TURRET_SPARKS {
+        let angle = rng.0.random_range(0.0..std::f32::consts::TAU);
+        commands.spawn((
+            SparkEffect {
+                velocity: Vec2::from_angle(angle) * rng.0.random_range(0.2..0.5),
+            },
+            UnitPosition(position),
+        ));
+    }
+}
+
+/// The core's shield collapses with a flash once the last turret is gone.
+fn drop_shield(commands: &mut Commands, asset_server: &AssetServer, position: Vec2) {
+    commands.spawn((
+        AudioPlayer::new(asset_server.load("sounds/buzzdown.ogg")),
+        PlaybackSettings {
+            mode: PlaybackMode::Despawn,
+            volume: Volume::Linear(0.7),
+            speed: 0.5,
+            ..default()
+        },
+    ));
+    commands.spawn((
+        FlareEffect {
+            size: 0.05,
+            velocity: Vec2::default(),
+        },
+        UnitPosition(position),
+    ));
+    commands.trigger(TickerMessage {
+        text: "Mothership shield down",
+        color: SHIELD_DOWN_COLOR,
+    });
+}
+
+/// The core breaks up in a chain of blasts across the hull.
+fn explode_core(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    rng: &mut RandomGenerator,
+    position: Vec2,
+) {
+    commands.spawn(ExplosionSound::for_enemy(EnemyKind::Boss).play(asset_server, &mut rng.0));
+    commands.spawn((
+        FlareEffect {
+            size: 0.06,
+            velocity: Vec2::default(),
+        },
+        UnitPosition(position),
+    ));
+    for _ in 0..4 {
+        let offset = Vec2::new(
+            rng.0.random_range(-0.12..0.12),
+            rng.0.random_range(-0.04..0.04),
+        );
+        commands.spawn((
+            FlareEffect {
+                size: 0.02,
+                velocity: offset * 0.5,
+            },
+            UnitPosition(position + offset),
+        ));
+        commands.spawn((
+            ShrapnelEffect { velocity: offset },
+            UnitPosition(position + offset),
+        ));
+    }
+}
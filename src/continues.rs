@@ -0,0 +1,136 @@
+//! Arcade-style continues: after game over, the player has a few seconds to press Fire and carry
+//! on from the current wave. Continues are free: the score carries over untouched.
+use bevy::prelude::*;
+use bevy_enhanced_input::prelude::*;
+
+use crate::{
+    Fire,
+    damage::{Invulnerable, Shields},
+    game_state::{DespawnOnExit, GameState, PauseState},
+    input_glyphs::{InputDevice, PromptButton},
+    score::Reserves,
+    ship::PlayerShip,
+    ui_audio::UiSound,
+};
+
+/// Continues used during the current run, and the countdown to accept one.
+#[derive(Resource, Default, Debug)]
+pub struct Continues {
+    /// Number of continues used so far in this run
+    used: u32,
+
+    /// Time remaining to accept a continue
+    countdown: f32,
+}
+
+impl Continues {
+    fn available(&self) -> bool {
+        self.used < MAX_CONTINUES
+    }
+}
+
+/// Marker for the continue countdown text
+#[derive(Component, Default, Debug)]
+pub struct ContinuePrompt;
+
+/// Number of continues allowed per run
+const MAX_CONTINUES: u32 = 3;
+
+/// Time the player has to accept a continue, in seconds
+const CONTINUE_TIME: f32 = 10.0;
+
+const PROMPT_COLOR: Color = Color::srgb(1.0, 0.9, 0.3);
+
+pub(crate) fn start_run_continues(mut r_continues: ResMut<Continues>) {
+    *r_continues = Continues::default();
+}
+
+/// Start the countdown and show the prompt when the game is over.
+pub(crate) fn spawn_continue_prompt(
+    mut commands: Commands,
+    mut r_continues: ResMut<Continues>,
+    r_device: Res<InputDevice>,
+) {
+    r_continues.countdown = CONTINUE_TIME;
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            right: Val::Px(0.0),
+            bottom: Val::Px(48.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        DespawnOnExit(PauseState::GameOver),
+        children![(
+            Text::new(prompt_text(&r_continues, *r_device)),
+            TextFont {
+                font_size: 32.0,
+                ..default()
+            },
+            TextColor(PROMPT_COLOR),
+            ContinuePrompt,
+        )],
+    ));
+}
+
+/// Count down, returning to the intro menu when time runs out. The countdown uses the real
+/// clock, since the game clock is stopped after game over.
+pub(crate) fn update_continue_prompt(
+    mut q_prompt: Query<&mut Text, With<ContinuePrompt>>,
+    mut r_continues: ResMut<Continues>,
+    mut next_state: ResMut<NextState<GameState>>,
+    r_device: Res<InputDevice>,
+    r_time: Res<Time<Real>>,
+) {
+    r_continues.countdown -= r_time.delta_secs();
+    if r_continues.countdown <= 0.0 {
+        next_state.set(GameState::Intro);
+        return;
+    }
+    for mut text in q_prompt.iter_mut() {
+        text.0 = prompt_text(&r_continues, *r_device);
+    }
+}
+
+/// Accept a continue: the ship is restored with a fresh set of lives and play resumes on the
+/// current wave, keeping the score.
+pub(crate) fn accept_continue(
+    _trigger: Trigger<Started<Fire>>,
+    mut commands: Commands,
+    mut q_shields: Query<(Entity, &mut Shields), With<PlayerShip>>,
+    r_pause: Option<Res<State<PauseState>>>,
+    mut next_state: ResMut<NextState<PauseState>>,
+    mut r_continues: ResMut<Continues>,
+    mut r_reserves: ResMut<Reserves>,
+) {
+    if r_pause.is_none_or(|state| *state.get() != PauseState::GameOver) {
+        return;
+    }
+    if !r_continues.available() || r_continues.countdown <= 0.0 {
+        return;
+    }
+
+    r_continues.used += 1;
+    r_reserves.lives = Reserves::default().lives;
+    for (ship, mut shields) in q_shields.iter_mut() {
+        shields.current = shields.max;
+        commands.entity(ship).insert(Invulnerable::default());
+    }
+    next_state.set(PauseState::Running);
+    commands.trigger(UiSound::Confirm);
+}
+
+fn prompt_text(continues: &Continues, device: InputDevice) -> String {
+    let seconds = continues.countdown.ceil() as u32;
+    if continues.available() {
+        format!(
+            "CONTINUE? {}   Press {} ({} left)",
+            seconds,
+            device.glyph(PromptButton::Fire),
+            MAX_CONTINUES - continues.used
+        )
+    } else {
+        format!("NO CONTINUES LEFT {}", seconds)
+    }
+}
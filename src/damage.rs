@@ -0,0 +1,229 @@
+//! Player shields and damage
+use avian2d::prelude::{ColliderOf, CollidingEntities, CollisionLayers};
+use bevy::prelude::*;
+
+use crate::{
+    ENEMY_LAYER, ENEMY_SHOT_LAYER, Enemy, SeamGhostOf, UnitPosition,
+    enemy_shot::EnemyShot,
+    explosion::{FlareEffect, ShrapnelEffect},
+    game_state::{PauseState, RunState},
+    hurtbox::Hurtbox,
+    score::Reserves,
+    seam_source,
+    ship::PlayerShip,
+    swarmlet::Swarmlet,
+};
+
+/// Player ship shields. The game is over when they are exhausted.
+#[derive(Component, Debug)]
+pub struct Shields {
+    /// Remaining shield strength
+    pub current: f32,
+
+    /// Full shield strength
+    pub max: f32,
+
+    /// Time remaining during which further hits are ignored
+    cooldown: f32,
+}
+
+impl Default for Shields {
+    fn default() -> Self {
+        Self {
+            current: 1.0,
+            max: 1.0,
+            cooldown: 0.0,
+        }
+    }
+}
+
+impl Shields {
+    /// Remaining shield strength as a fraction of full strength.
+    pub fn fraction(&self) -> f32 {
+        if self.max > 0.0 {
+            self.current / self.max
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Event sent when the player ship takes damage.
+#[derive(Event, Debug)]
+pub struct PlayerHit {
+    /// Where the damage came from
+    pub source: Vec2,
+}
+
+/// Event sent when the player's shields run out and a ship is lost.
+#[derive(Event, Debug)]
+pub struct ShipLost;
+
+/// Temporary invulnerability granted when the player's ship is replaced. While it lasts, the
+/// ship's hurtbox ignores enemies and the ship blinks.
+#[derive(Component, Debug)]
+pub struct Invulnerable {
+    /// Time remaining, in seconds
+    remaining: f32,
+
+    /// Whether the ship blinks while invulnerable
+    blink: bool,
+}
+
+impl Invulnerable {
+    /// Brief invulnerability which doesn't blink the ship, for evasive moves.
+    pub fn brief(duration: f32) -> Self {
+        Self {
+            remaining: duration,
+            blink: false,
+        }
+    }
+}
+
+impl Default for Invulnerable {
+    fn default() -> Self {
+        Self {
+            remaining: RESPAWN_INVULNERABILITY,
+            blink: true,
+        }
+    }
+}
+
+/// Length of invulnerability after respawning, in seconds
+const RESPAWN_INVULNERABILITY: f32 = 3.0;
+
+/// Rate at which the ship blinks while invulnerable, in blinks per second
+const BLINK_RATE: f32 = 8.0;
+
+/// Damage dealt by ramming an enemy
+const CONTACT_DAMAGE: f32 = 0.34;
+
+/// Time after a hit during which the ship can't be hit again, in seconds
+const HIT_COOLDOWN: f32 = 1.0;
+
+/// Damage the ship when an enemy or enemy shot touches its hurtbox. Enemy shots and swarmlets
+/// are used up by the hit. When shields run out, a ship is lost from the reserves and the
+/// shields are restored; once there are no ships left, the game is over. Practice runs never
+/// run out of ships.
+pub(crate) fn detect_player_hits(
+    mut commands: Commands,
+    q_player: Single<(Entity, &mut Shields, &UnitPosition, Has<Invulnerable>), With<PlayerShip>>,
+    q_hurtboxes: Query<&CollidingEntities, With<Hurtbox>>,
+    q_enemies: Query<
+        (&UnitPosition, Has<EnemyShot>, Has<Swarmlet>),
+        Or<(With<Enemy>, With<EnemyShot>)>,
+    >,
+    q_ghosts: Query<&SeamGhostOf>,
+    q_colliders: Query<&ColliderOf>,
+    mut next_state: ResMut<NextState<PauseState>>,
+    mut r_reserves: ResMut<Reserves>,
+    r_run: Res<RunState>,
+    r_time: Res<Time>,
+) {
+    let (ship, mut shields, position, invulnerable) = q_player.into_inner();
+    shields.cooldown -= r_time.delta_secs();
+    if shields.cooldown > 0.0 || invulnerable {
+        return;
+    }
+    let Some((entity, (source, is_shot, is_swarmlet))) = q_hurtboxes
+        .iter()
+        .flat_map(|collisions| collisions.iter())
+        .map(|entity| seam_source(&q_ghosts, *entity))
+        // Parts of larger enemies are colliders attached to the enemy's body.
+        .map(|entity| {
+            q_colliders
+                .get(entity)
+                .map_or(entity, |collider| collider.body)
+        })
+        .find_map(|entity| q_enemies.get(entity).ok().map(|enemy| (entity, enemy)))
+    else {
+        return;
+    };
+    if is_shot || is_swarmlet {
+        commands.entity(entity).despawn();
+    }
+
+    shields.current = (shields.current - CONTACT_DAMAGE).max(0.0);
+    shields.cooldown = HIT_COOLDOWN;
+    commands.trigger(PlayerHit { source: source.0 });
+    if shields.current > 0.0 {
+        return;
+    }
+    commands.trigger(ShipLost);
+    // Lives are unlimited in practice runs.
+    let practice = r_run.practice.is_some();
+    if r_reserves.lives == 0 && !practice {
+        next_state.set(PauseState::GameOver);
+        return;
+    }
+
+    if !practice {
+        r_reserves.lives -= 1;
+    }
+    shields.current = shields.max;
+    commands.entity(ship).insert(Invulnerable::default());
+    commands.spawn((
+        FlareEffect {
+            size: 0.03,
+            velocity: Vec2::default(),
+        },
+        UnitPosition(position.0),
+    ));
+    commands.spawn((
+        ShrapnelEffect {
+            velocity: Vec2::default(),
+        },
+        UnitPosition(position.0),
+    ));
+}
+
+/// Stop the ship's hurtbox colliding with enemies while invulnerable.
+pub(crate) fn on_add_invulnerable(
+    trigger: Trigger<OnAdd, Invulnerable>,
+    mut q_hurtboxes: Query<(&ChildOf, &mut CollisionLayers), With<Hurtbox>>,
+) {
+    for (child_of, mut layers) in q_hurtboxes.iter_mut() {
+        if child_of.parent() == trigger.target() {
+            layers.filters.remove(ENEMY_LAYER | ENEMY_SHOT_LAYER);
+        }
+    }
+}
+
+/// Restore enemy collisions and visibility once invulnerability ends.
+pub(crate) fn on_remove_invulnerable(
+    trigger: Trigger<OnRemove, Invulnerable>,
+    mut q_hurtboxes: Query<(&ChildOf, &mut CollisionLayers), With<Hurtbox>>,
+    mut q_visibility: Query<&mut Visibility>,
+) {
+    for (child_of, mut layers) in q_hurtboxes.iter_mut() {
+        if child_of.parent() == trigger.target() {
+            layers.filters.add(ENEMY_LAYER | ENEMY_SHOT_LAYER);
+        }
+    }
+    if let Ok(mut visibility) = q_visibility.get_mut(trigger.target()) {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/// Count down invulnerability, blinking the ship while it lasts.
+pub(crate) fn update_invulnerability(
+    mut commands: Commands,
+    mut q_ship: Query<(Entity, &mut Invulnerable, &mut Visibility)>,
+    r_time: Res<Time>,
+) {
+    for (entity, mut invulnerable, mut visibility) in q_ship.iter_mut() {
+        invulnerable.remaining -= r_time.delta_secs();
+        if invulnerable.remaining <= 0.0 {
+            commands.entity(entity).remove::<Invulnerable>();
+            continue;
+        }
+        if !invulnerable.blink {
+            continue;
+        }
+        *visibility = if (invulnerable.remaining * BLINK_RATE).fract() < 0.5 {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+    }
+}
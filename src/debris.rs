@@ -0,0 +1,152 @@
+//! Scorched debris left behind by explosions. A few fragments from each explosion fall to the
+//! ground and stay there for the rest of the wave. The number of pieces is capped; once the cap
+//! is reached, the oldest pieces are recycled.
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    FX_DEPTH, Playfield, RandomGenerator, UnitPosition,
+    explosion::ShrapnelEffect,
+    game_state::{DespawnOnExit, GameState},
+    terrain::Terrain,
+    wave::WaveConfig,
+};
+
+/// A piece of debris, falling or resting on the ground.
+#[derive(Component, Default, Debug)]
+pub struct Debris {
+    velocity: Vec2,
+
+    /// Spin rate while falling, in radians per second
+    spin: f32,
+
+    /// Whether the debris has come to rest
+    settled: bool,
+}
+
+/// Pool of debris entities, oldest first.
+#[derive(Resource, Default, Debug)]
+pub struct DebrisPool {
+    active: VecDeque<Entity>,
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+/// Maximum number of debris pieces in the world
+const MAX_DEBRIS: usize = 48;
+
+/// Number of pieces left by each explosion
+const DEBRIS_PER_EXPLOSION: usize = 3;
+
+/// Downward acceleration of falling debris
+const DEBRIS_GRAVITY: f32 = 0.8;
+
+pub(crate) fn setup_debris(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut r_pool: ResMut<DebrisPool>,
+) {
+    r_pool.mesh = meshes.add(Triangle2d::new(
+        Vec2::new(0.0, 0.006),
+        Vec2::new(-0.006, -0.004),
+        Vec2::new(0.006, -0.003),
+    ));
+    r_pool.material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.12, 0.1, 0.09),
+        perceptual_roughness: 0.9,
+        double_sided: true,
+        cull_mode: None,
+        ..default()
+    });
+}
+
+/// Each explosion throws out a few pieces of debris.
+pub(crate) fn on_add_shrapnel_debris(
+    trigger: Trigger<OnAdd, ShrapnelEffect>,
+    mut commands: Commands,
+    q_position: Query<&UnitPosition>,
+    mut r_pool: ResMut<DebrisPool>,
+    mut r_rng: ResMut<RandomGenerator>,
+) {
+    let Ok(origin) = q_position.get(trigger.target()) else {
+        return;
+    };
+    for _ in 0..DEBRIS_PER_EXPLOSION {
+        let debris = Debris {
+            velocity: Vec2::new(
+                r_rng.0.random_range(-0.3..0.3),
+                r_rng.0.random_range(0.0..0.3),
+            ),
+            spin: r_rng.0.random_range(-12.0..12.0),
+            settled: false,
+        };
+        let bundle = (
+            debris,
+            UnitPosition(origin.0),
+            Transform::from_xyz(0., 0., FX_DEPTH),
+        );
+
+        // Recycle the oldest piece if we are at the cap.
+        let recycled = if r_pool.active.len() >= MAX_DEBRIS {
+            r_pool.active.pop_front()
+        } else {
+            None
+        };
+        if let Some(oldest) = recycled {
+            commands.entity(oldest).insert(bundle);
+            r_pool.active.push_back(oldest);
+            continue;
+        }
+        let entity = commands
+            .spawn((
+                bundle,
+                Mesh3d(r_pool.mesh.clone()),
+                MeshMaterial3d(r_pool.material.clone()),
+                DespawnOnExit(GameState::Playing),
+            ))
+            .id();
+        r_pool.active.push_back(entity);
+    }
+}
+
+/// Debris falls and tumbles until it hits the ground, where it lies flat. Each piece only touches
+/// its own components, so pieces are updated in parallel.
+pub(crate) fn update_debris(
+    mut q_debris: Query<(&mut Debris, &mut UnitPosition, &mut Transform)>,
+    r_terrain: Res<Terrain>,
+    r_playfield: Res<Playfield>,
+    r_wave: Res<WaveConfig>,
+    r_time: Res<Time>,
+) {
+    let dt = r_time.delta_secs();
+    let gravity = DEBRIS_GRAVITY * r_wave.shrapnel_gravity();
+    q_debris
+        .par_iter_mut()
+        .for_each(|(mut debris, mut position, mut transform)| {
+            if debris.settled {
+                return;
+            }
+            debris.velocity.y -= gravity * dt;
+            position.0 += debris.velocity * dt;
+            position.0.x = r_playfield.wrap(position.0.x);
+            transform.rotate_local_x(debris.spin * dt);
+            transform.rotate_local_z(debris.spin * 0.7 * dt);
+
+            let ground = r_terrain.height_at(position.0.x);
+            if position.0.y <= ground {
+                position.0.y = ground;
+                debris.settled = true;
+                // Lie flat, keeping a random-looking heading.
+                let (_, _, heading) = transform.rotation.to_euler(EulerRot::XYZ);
+                transform.rotation = Quat::from_rotation_x(-1.4) * Quat::from_rotation_z(heading);
+            }
+        });
+}
+
+/// Forget about all debris at the end of the wave. The entities themselves are despawned by
+/// `DespawnOnExit`.
+pub(crate) fn clear_debris(mut r_pool: ResMut<DebrisPool>) {
+    r_pool.active.clear();
+}
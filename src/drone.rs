@@ -0,0 +1,145 @@
+//! Companion drone which orbits the player ship and shoots at nearby enemies.
+use avian2d::prelude::{Collider, CollidingEntities, CollisionLayers, RigidBody};
+use bevy::prelude::*;
+
+use crate::{
+    ENEMY_LAYER, Enemy, FX_DEPTH, PLAYER_LAYER, Playfield, SeamMirrored, UnitPosition,
+    explosion::FlareEffect,
+    game_state::{DespawnOnExit, GameState},
+    laser::{ShotMesh, spawn_laser},
+    pickup::{PickupCollected, PickupKind},
+    ship::{Facing, PlayerShip},
+    spatial::SpatialHash,
+    tuning::Tuning,
+};
+
+/// A companion drone. It is destroyed by the first enemy that touches it, shielding the ship.
+#[derive(Component, Default, Debug)]
+pub struct Drone {
+    /// Current angle around the ship, in radians
+    angle: f32,
+
+    /// Time until the drone can fire again
+    cooldown: f32,
+}
+
+/// Distance from the ship at which the drone orbits
+const ORBIT_RADIUS: f32 = 0.09;
+
+/// Orbit angular speed, in radians per second
+const ORBIT_SPEED: f32 = 3.0;
+
+/// Enemies within this horizontal distance are targeted.
+const DRONE_RANGE: f32 = 0.8;
+
+/// Enemies must be within this vertical distance to be hit by the drone's horizontal shots.
+const DRONE_AIM_BAND: f32 = 0.05;
+
+/// Time between drone shots, in seconds
+const DRONE_FIRE_INTERVAL: f32 = 0.8;
+
+/// Damage dealt by each drone shot
+const DRONE_DAMAGE: f32 = 0.5;
+
+/// Spawn a drone when the drone pickup is collected. The ship can only have one at a time.
+pub(crate) fn on_drone_pickup(
+    trigger: Trigger<PickupCollected>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    q_drones: Query<(), With<Drone>>,
+    q_player: Query<&UnitPosition, With<PlayerShip>>,
+) {
+    if trigger.event().kind != PickupKind::Drone || !q_drones.is_empty() {
+        return;
+    }
+    let Ok(player_pos) = q_player.single() else {
+        return;
+    };
+    commands.spawn((
+        Drone::default(),
+        UnitPosition(player_pos.0 + Vec2::X * ORBIT_RADIUS),
+        Mesh3d(meshes.add(Sphere::new(0.01).mesh().ico(1).unwrap())),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.5, 0.5, 0.6),
+            emissive: LinearRgba::new(0.4, 1.0, 0.4, 1.0),
+            ..default()
+        })),
+        Transform::from_xyz(0., 0., FX_DEPTH),
+        RigidBody::Kinematic,
+        Collider::circle(0.012),
+        CollisionLayers::from_bits(PLAYER_LAYER, ENEMY_LAYER),
+        CollidingEntities::default(),
+        SeamMirrored,
+        DespawnOnExit(GameState::Playing),
+    ));
+}
+
+/// Orbit the ship, fire at the nearest enemy in range, and absorb enemy contact.
+pub(crate) fn update_drones(
+    mut commands: Commands,
+    mut q_drones: Query<
+        (Entity, &mut Drone, &mut UnitPosition, &CollidingEntities),
+        Without<PlayerShip>,
+    >,
+    q_player: Query<&UnitPosition, With<PlayerShip>>,
+    q_enemies: Query<&UnitPosition, (With<Enemy>, Without<Drone>, Without<PlayerShip>)>,
+    r_playfield: Res<Playfield>,
+    r_hash: Res<SpatialHash>,
+    r_time: Res<Time>,
+    shot_mesh: Res<ShotMesh>,
+    r_tuning: Res<Tuning>,
+) {
+    let dt = r_time.delta_secs();
+    let Ok(player_pos) = q_player.single() else {
+        return;
+    };
+    for (entity, mut drone, mut position, collisions) in q_drones.iter_mut() {
+        if !collisions.is_empty() {
+            commands.entity(entity).despawn();
+            commands.spawn((
+                FlareEffect {
+                    size: 0.01,
+                    velocity: Vec2::default(),
+                },
+                UnitPosition(position.0),
+            ));
+            continue;
+        }
+
+        drone.angle = (drone.angle + ORBIT_SPEED * dt) % std::f32::consts::TAU;
+        let orbit = Vec2::from_angle(drone.angle) * ORBIT_RADIUS;
+        position.0 = player_pos.0 + orbit;
+        position.0.x = r_playfield.wrap(position.0.x);
+
+        drone.cooldown -= dt;
+        if drone.cooldown > 0.0 {
+            continue;
+        }
+
+        let target = r_hash
+            .within(position.0, DRONE_RANGE + DRONE_AIM_BAND)
+            .filter_map(|(enemy, _)| q_enemies.get(enemy).ok())
+            .map(|enemy| r_playfield.delta(position.0, enemy.0))
+            .filter(|delta| delta.x.abs() < DRONE_RANGE && delta.y.abs() < DRONE_AIM_BAND)
+            .min_by(|a, b| a.x.abs().total_cmp(&b.x.abs()));
+        if let Some(delta) = target {
+            let facing = if delta.x >= 0.0 {
+                Facing::Right
+            } else {
+                Facing::Left
+            };
+            spawn_laser(
+                &mut commands,
+                position.0,
+                Vec2::ZERO,
+                facing,
+                0,
+                DRONE_DAMAGE,
+                &shot_mesh,
+                &r_tuning,
+            );
+            drone.cooldown = DRONE_FIRE_INTERVAL;
+        }
+    }
+}
@@ -0,0 +1,29 @@
+//! Fully embedded assets, for distributing the game as a single executable. With the `embedded`
+//! feature enabled, every file in the assets folder is compiled into the binary, and served in
+//! place of the assets folder. Asset paths are unchanged, so nothing else needs to know whether
+//! assets are embedded.
+use std::path::Path;
+
+use bevy::{
+    asset::io::{
+        AssetSource, AssetSourceId,
+        memory::{Dir, MemoryAssetReader},
+    },
+    prelude::*,
+};
+
+// The list of asset files, generated by the build script.
+include!(concat!(env!("OUT_DIR"), "/asset_files.rs"));
+
+/// Replace the default asset source with the embedded assets. This must be called before the
+/// `AssetPlugin` is added.
+pub fn register_embedded_assets(app: &mut App) {
+    let dir = Dir::default();
+    for (path, bytes) in ASSET_FILES {
+        dir.insert_asset(Path::new(path), *bytes);
+    }
+    app.register_asset_source(
+        AssetSourceId::Default,
+        AssetSource::build().with_reader(move || Box::new(MemoryAssetReader { root: dir.clone() })),
+    );
+}
@@ -0,0 +1,260 @@
+//! Enemy shots: queened saucers fire slow plasma bolts at the player. A skilled player can flip
+//! the ship around just as a bolt arrives to deflect it, turning it into a player shot aimed
+//! back the other way.
+use avian2d::prelude::{Collider, CollidingEntities, CollisionLayers, RigidBody};
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+};
+
+use crate::{
+    ENEMY_SHOT_LAYER, FX_DEPTH, PLAYER_LAYER, Playfield, UnitPosition,
+    ai::{AiTransition, StateMachine},
+    explosion::FlareEffect,
+    game_state::{DespawnOnExit, GameState},
+    laser::{ShotMesh, spawn_laser},
+    leader::{INSPIRED_FIRE_RATE, Inspired},
+    saucer::{Saucer, SaucerState},
+    score::ScoreEvent,
+    ship::{Facing, PlayerShip},
+    tuning::Tuning,
+};
+
+/// A plasma bolt fired by an enemy
+#[derive(Component, Debug)]
+pub struct EnemyShot {
+    velocity: Vec2,
+
+    /// Time until the bolt fizzles out
+    lifetime: f32,
+}
+
+/// An enemy which fires at the player
+#[derive(Component, Debug)]
+pub struct Gunner {
+    /// Time until the next shot
+    cooldown: f32,
+}
+
+/// Shared mesh and material for enemy shots
+#[derive(Resource, Default, Debug)]
+pub struct EnemyShotAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+/// Time since the player ship last changed facing. Enemy shots which reach the ship while this is
+/// within the deflection window are sent back.
+#[derive(Resource, Debug)]
+pub struct DeflectWindow {
+    /// Facing of the ship as of the last frame
+    facing: Option<Facing>,
+
+    /// Time since the ship flipped, in seconds
+    since_flip: f32,
+}
+
+impl Default for DeflectWindow {
+    fn default() -> Self {
+        Self {
+            facing: None,
+            since_flip: f32::INFINITY,
+        }
+    }
+}
+
+/// Time between shots from each gunner, in seconds
+const GUNNER_INTERVAL: f32 = 2.5;
+
+/// Gunners only fire at a player within this horizontal distance.
+const GUNNER_RANGE: f32 = 1.2;
+
+/// Speed of enemy shots
+const ENEMY_SHOT_SPEED: f32 = 0.5;
+
+/// How long an enemy shot lasts, in seconds
+const ENEMY_SHOT_LIFETIME: f32 = 4.0;
+
+/// Radius of an enemy shot's collider
+const ENEMY_SHOT_RADIUS: f32 = 0.008;
+
+/// How soon after flipping the ship an arriving shot is deflected, in seconds
+const DEFLECT_WINDOW: f32 = 0.15;
+
+/// Shots within this distance of the ship can be deflected.
+const DEFLECT_RADIUS: f32 = 0.08;
+
+/// Deflected shots are stronger than regular shots, and pass through one enemy.
+const DEFLECT_DAMAGE: f32 = 1.5;
+const DEFLECT_PIERCE: u32 = 1;
+
+/// Points awarded for a deflection
+const DEFLECT_BONUS: i32 = 100;
+
+pub(crate) fn setup_enemy_shots(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut r_assets: ResMut<EnemyShotAssets>,
+) {
+    r_assets.mesh = meshes.add(Sphere::new(ENEMY_SHOT_RADIUS).mesh().ico(2).unwrap());
+    r_assets.material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.4, 1.0, 0.2),
+        emissive: LinearRgba::new(1.0, 3.0, 0.5, 1.0),
+        ..default()
+    });
+}
+
+/// Saucers which become queens start firing at the player.
+pub(crate) fn arm_queens(trigger: Trigger<AiTransition<SaucerState>>, mut commands: Commands) {
+    if trigger.event().to == SaucerState::Queened {
+        commands.entity(trigger.target()).insert(Gunner {
+            cooldown: GUNNER_INTERVAL,
+        });
+    }
+}
+
+/// Gunners fire a bolt at the player ship every so often, if it is within range.
+pub(crate) fn fire_enemy_shots(
+    mut commands: Commands,
+    mut q_gunners: Query<
+        (
+            &mut Gunner,
+            &UnitPosition,
+            &StateMachine<SaucerState>,
+            Has<Inspired>,
+        ),
+        With<Saucer>,
+    >,
+    q_player: Query<&UnitPosition, With<PlayerShip>>,
+    r_assets: Res<EnemyShotAssets>,
+    r_playfield: Res<Playfield>,
+    r_time: Res<Time>,
+) {
+    let Ok(target) = q_player.single() else {
+        return;
+    };
+    for (mut gunner, position, machine, inspired) in q_gunners.iter_mut() {
+        if machine.state() != SaucerState::Queened {
+            continue;
+        }
+        // Gunners near a formation leader reload faster.
+        let rate = if inspired { INSPIRED_FIRE_RATE } else { 1.0 };
+        gunner.cooldown -= r_time.delta_secs() * rate;
+        if gunner.cooldown > 0.0 {
+            continue;
+        }
+        let delta = r_playfield.delta(position.0, target.0);
+        if delta.x.abs() > GUNNER_RANGE {
+            continue;
+        }
+        gunner.cooldown = GUNNER_INTERVAL;
+        spawn_enemy_shot(&mut commands, position.0, delta, &r_assets);
+    }
+}
+
+/// Fire a bolt from `position`, heading in the direction of `aim`.
+pub fn spawn_enemy_shot(
+    commands: &mut Commands,
+    position: Vec2,
+    aim: Vec2,
+    r_assets: &EnemyShotAssets,
+) {
+    commands.spawn((
+        EnemyShot {
+            velocity: aim.normalize_or_zero() * ENEMY_SHOT_SPEED,
+            lifetime: ENEMY_SHOT_LIFETIME,
+        },
+        RigidBody::Kinematic,
+        Collider::circle(ENEMY_SHOT_RADIUS),
+        CollisionLayers::from_bits(ENEMY_SHOT_LAYER, PLAYER_LAYER),
+        CollidingEntities::default(),
+        UnitPosition(position),
+        Mesh3d(r_assets.mesh.clone()),
+        MeshMaterial3d(r_assets.material.clone()),
+        Transform::from_xyz(0., 0., FX_DEPTH),
+        DespawnOnExit(GameState::Playing),
+    ));
+}
+
+/// Move enemy shots, and fizzle them out when they expire.
+pub(crate) fn update_enemy_shots(
+    mut commands: Commands,
+    mut q_shots: Query<(Entity, &mut EnemyShot, &mut UnitPosition)>,
+    r_playfield: Res<Playfield>,
+    r_time: Res<Time>,
+) {
+    let dt = r_time.delta_secs();
+    for (entity, mut shot, mut position) in q_shots.iter_mut() {
+        shot.lifetime -= dt;
+        if shot.lifetime <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        position.0 += shot.velocity * dt;
+        position.0.x = r_playfield.wrap(position.0.x);
+    }
+}
+
+/// If the ship flips around just as an enemy shot arrives, the shot is deflected: it is replaced
+/// by a player shot heading the way the ship now faces.
+pub(crate) fn deflect_enemy_shots(
+    mut commands: Commands,
+    q_shots: Query<(Entity, &UnitPosition), With<EnemyShot>>,
+    q_player: Query<(&PlayerShip, &UnitPosition)>,
+    mut r_window: ResMut<DeflectWindow>,
+    asset_server: Res<AssetServer>,
+    shot_mesh: Res<ShotMesh>,
+    r_playfield: Res<Playfield>,
+    r_time: Res<Time>,
+    r_tuning: Res<Tuning>,
+) {
+    let Ok((ship, ship_pos)) = q_player.single() else {
+        return;
+    };
+    let facing = ship.facing();
+    if r_window.facing.is_some_and(|previous| previous != facing) {
+        r_window.since_flip = 0.0;
+    } else {
+        r_window.since_flip += r_time.delta_secs();
+    }
+    r_window.facing = Some(facing);
+    if r_window.since_flip > DEFLECT_WINDOW {
+        return;
+    }
+
+    for (entity, position) in q_shots.iter() {
+        if r_playfield.distance(position.0, ship_pos.0) > DEFLECT_RADIUS {
+            continue;
+        }
+        commands.entity(entity).despawn();
+        spawn_laser(
+            &mut commands,
+            position.0,
+            Vec2::ZERO,
+            facing,
+            DEFLECT_PIERCE,
+            DEFLECT_DAMAGE,
+            &shot_mesh,
+            &r_tuning,
+        );
+        commands.spawn((
+            FlareEffect {
+                size: 0.015,
+                velocity: Vec2::default(),
+            },
+            UnitPosition(position.0),
+        ));
+        commands.spawn((
+            AudioPlayer::new(asset_server.load("sounds/kick.ogg")),
+            PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                volume: Volume::Linear(0.8),
+                speed: 1.5,
+                ..default()
+            },
+        ));
+        commands.trigger(ScoreEvent {
+            points: DEFLECT_BONUS,
+        });
+    }
+}
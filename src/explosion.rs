@@ -1,13 +1,32 @@
 //! Shrapnel from explosions
+use std::ops::Range;
+
 use avian2d::math::PI;
 use bevy::{
-    ecs::{relationship::RelatedSpawner, spawn::SpawnWith},
+    asset::RenderAssetUsages,
+    audio::{PlaybackMode, Volume},
+    pbr::{ExtendedMaterial, MaterialExtension, MaterialExtensionKey, MaterialExtensionPipeline},
     prelude::*,
+    render::{
+        mesh::{MeshVertexAttribute, MeshVertexBufferLayoutRef, PrimitiveTopology},
+        render_resource::{
+            AsBindGroup, RenderPipelineDescriptor, ShaderRef, SpecializedMeshPipelineError,
+            VertexFormat,
+        },
+        view::NoFrustumCulling,
+    },
 };
 use rand::Rng;
 use rand_chacha::ChaCha8Rng;
 
-use crate::{FX_DEPTH, RandomGenerator, UnitPosition};
+use crate::{
+    EnemyKind, FX_DEPTH, RandomGenerator, SHIP_DEPTH, UnitPosition,
+    game_state::{DespawnOnExit, GameState},
+    settings::Settings,
+    terrain::Terrain,
+    tuning::Tuning,
+    wave::WaveConfig,
+};
 
 /// Determines the lifetime of the effect
 #[derive(Component, Default, Debug)]
@@ -30,17 +49,16 @@ pub struct ShrapnelEffect {
     pub velocity: Vec2,
 }
 
-/// Explosion effect: one fragment of shrapnel
-#[derive(Component, Default, Debug)]
-pub struct ShrapnelFragment {
-    /// Velocity of fragment
-    pub velocity: Vec2,
+/// Number of pre-generated shrapnel meshes. Each explosion picks one at random.
+const NUM_SHRAPNEL_MESHES: usize = 4;
 
-    /// Rotation of fragment
-    pub spin_axis: Vec3,
-}
+/// Per-vertex fragment motion: x, y velocity.
+const ATTRIBUTE_FRAGMENT_MOTION: MeshVertexAttribute =
+    MeshVertexAttribute::new("FragmentMotion", 417734012, VertexFormat::Float32x2);
 
-const NUM_FRAGMENTS: usize = 64;
+/// Per-vertex fragment spin axis.
+const ATTRIBUTE_FRAGMENT_SPIN: MeshVertexAttribute =
+    MeshVertexAttribute::new("FragmentSpin", 417734013, VertexFormat::Float32x3);
 
 /// Explosion effect: expanding sphere of light
 #[derive(Component, Default, Debug)]
@@ -52,71 +70,312 @@ pub struct FlareEffect {
     pub velocity: Vec2,
 }
 
+/// Small puff of dust or smoke, such as where a shot strikes the ground
+#[derive(Component, Default, Debug)]
+pub struct DustEffect {
+    /// Size of the puff
+    pub size: f32,
+
+    /// Color of the puff
+    pub color: Color,
+}
+
+/// Color of dust kicked up from the ground
+pub const DUST_COLOR: Color = Color::srgb(0.5, 0.42, 0.33);
+
+/// Speed at which dust puffs drift upwards
+const DUST_RISE_SPEED: f32 = 0.04;
+
+/// A single spark, thrown out by damaged machinery
+#[derive(Component, Default, Debug)]
+pub struct SparkEffect {
+    /// Velocity
+    pub velocity: Vec2,
+}
+
+/// Downward acceleration of sparks
+const SPARK_GRAVITY: f32 = 0.8;
+
+/// Point light which briefly illuminates the surroundings of a flare. This is a separate entity
+/// rather than a child, because the flare is scaled and lives at a different depth.
+#[derive(Component, Debug)]
+#[relationship(relationship_target = FlareLights)]
+pub struct FlareLightOf(pub Entity);
+
+#[derive(Component, Debug)]
+#[relationship_target(relationship = FlareLightOf, linked_spawn)]
+pub struct FlareLights(Vec<Entity>);
+
+/// Peak intensity of the flare light, in lumens
+const FLARE_LIGHT_INTENSITY: f32 = 40_000.0;
+
+/// Range of the flare light
+const FLARE_LIGHT_RANGE: f32 = 1.0;
+
+/// Distance of the flare light in front of the ship layer
+const FLARE_LIGHT_OFFSET: f32 = 0.3;
+
+/// A bank of explosion samples, played with random pitch and volume so that chains of kills
+/// don't all sound identical.
+pub struct ExplosionSound {
+    /// Sample paths; one is chosen at random
+    samples: &'static [&'static str],
+    /// Range of linear volume
+    volume: Range<f32>,
+    /// Range of playback speed, which also shifts the pitch
+    speed: Range<f32>,
+}
+
+const SAUCER_EXPLOSION: ExplosionSound = ExplosionSound {
+    samples: &["sounds/softexplode.ogg", "sounds/kick.ogg"],
+    volume: 0.8..1.0,
+    speed: 0.9..1.15,
+};
+
+const QUEEN_EXPLOSION: ExplosionSound = ExplosionSound {
+    samples: &["sounds/softexplode.ogg", "sounds/smash.ogg"],
+    volume: 0.9..1.1,
+    speed: 0.65..0.8,
+};
+
+const TURRET_EXPLOSION: ExplosionSound = ExplosionSound {
+    samples: &["sounds/kick.ogg", "sounds/minein.ogg"],
+    volume: 0.8..1.0,
+    speed: 1.2..1.4,
+};
+
+const BOSS_EXPLOSION: ExplosionSound = ExplosionSound {
+    samples: &["sounds/smash.ogg"],
+    volume: 1.2..1.4,
+    speed: 0.45..0.55,
+};
+
+impl ExplosionSound {
+    /// Explosion sound for a given kind of enemy.
+    pub fn for_enemy(kind: EnemyKind) -> Self {
+        match kind {
+            EnemyKind::Saucer | EnemyKind::Swarmlet => SAUCER_EXPLOSION,
+            EnemyKind::Queen | EnemyKind::Leader => QUEEN_EXPLOSION,
+            EnemyKind::BossTurret => TURRET_EXPLOSION,
+            EnemyKind::Boss => BOSS_EXPLOSION,
+        }
+    }
+
+    /// Pick a random sample and jitter, returning the components needed to play it.
+    pub fn play(
+        &self,
+        asset_server: &AssetServer,
+        rng: &mut ChaCha8Rng,
+    ) -> (AudioPlayer, PlaybackSettings) {
+        let sample = self.samples[rng.random_range(0..self.samples.len())];
+        (
+            AudioPlayer::new(asset_server.load(sample)),
+            PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                volume: Volume::Linear(rng.random_range(self.volume.clone())),
+                speed: rng.random_range(self.speed.clone()),
+                ..default()
+            },
+        )
+    }
+}
+
+/// Materials which are animated per effect, so can't be shared between live effects. Rather than
+/// allocating a new asset for each effect, materials are taken from the pool when an effect
+/// starts and returned when it is removed.
+#[derive(Debug)]
+pub struct MaterialPool<M: Asset> {
+    free: Vec<Handle<M>>,
+}
+
+impl<M: Asset> Default for MaterialPool<M> {
+    fn default() -> Self {
+        Self { free: Vec::new() }
+    }
+}
+
+impl<M: Asset> MaterialPool<M> {
+    /// Take a material from the pool, resetting it to `material`. If the pool is empty, a new
+    /// material is added.
+    fn take(&mut self, assets: &mut Assets<M>, material: M) -> Handle<M> {
+        match self.free.pop() {
+            Some(handle) => {
+                assets.insert(&handle, material);
+                handle
+            }
+            None => assets.add(material),
+        }
+    }
+
+    /// Return a material to the pool.
+    fn release(&mut self, handle: &Handle<M>) {
+        self.free.push(handle.clone());
+    }
+}
+
 /// Stores shared materials and meshes used by effects.
 #[derive(Resource, Default, Debug)]
 pub struct ExplosionHandles {
-    shrapnel_mesh: Handle<Mesh>,
+    shrapnel_meshes: Vec<Handle<Mesh>>,
     flare_mesh: Handle<Mesh>,
+    spark_mesh: Handle<Mesh>,
+    spark_material: Handle<StandardMaterial>,
+    glow_texture: Handle<Image>,
+    shrapnel_materials: MaterialPool<ShrapnelMaterial>,
+    flare_materials: MaterialPool<StandardMaterial>,
+    dust_materials: MaterialPool<StandardMaterial>,
+    /// Number of fragments the shrapnel meshes were built with
+    fragments: usize,
 }
 
+/// Number of materials of each kind created up front, enough for a busy fight.
+const MATERIAL_POOL_SIZE: usize = 16;
+
+/// Build the shared effect meshes and fill the material pools. This is re-run when the settings
+/// change, since the number of shrapnel fragments depends on the graphics quality.
 pub(crate) fn setup_explosions(
     mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut shrapnel_materials: ResMut<Assets<ShrapnelMaterial>>,
     mut handles: ResMut<ExplosionHandles>,
+    mut random: ResMut<RandomGenerator>,
+    asset_server: Res<AssetServer>,
+    r_settings: Res<Settings>,
 ) {
-    // Vec2::Y * 0.5, Vec2::new(-0.5, -0.5), Vec2::new(0.5, -0.5)
-    handles.shrapnel_mesh = meshes.add(Triangle2d::new(
-        Vec2::new(0.0, 0.01),
-        Vec2::new(-0.01, -0.01),
-        Vec2::new(0.01, -0.007),
-    ));
+    if handles.glow_texture == Handle::default() {
+        handles.glow_texture = asset_server.load("textures/glowspark.png");
+        for _ in 0..MATERIAL_POOL_SIZE {
+            let handles = &mut *handles;
+            let shrapnel = shrapnel_materials.add(shrapnel_material(0.0));
+            handles.shrapnel_materials.release(&shrapnel);
+            let flare = materials.add(flare_material(&handles.glow_texture));
+            handles.flare_materials.release(&flare);
+            let dust = materials.add(dust_material(&handles.glow_texture, DUST_COLOR));
+            handles.dust_materials.release(&dust);
+        }
+    }
+
+    let fragments = r_settings.graphics.quality.shrapnel_fragments();
+    if handles.fragments == fragments {
+        return;
+    }
+    handles.fragments = fragments;
+    let _span = info_span!("generate_shrapnel_meshes", fragments).entered();
+    handles.shrapnel_meshes = (0..NUM_SHRAPNEL_MESHES)
+        .map(|_| meshes.add(create_shrapnel_mesh(&mut random.0, fragments)))
+        .collect();
     handles.flare_mesh = meshes.add(Rectangle::default());
+    handles.spark_mesh = meshes.add(Cuboid::from_length(0.003));
+    handles.spark_material = materials.add(StandardMaterial {
+        base_color: Color::BLACK,
+        emissive: LinearRgba::new(4.0, 2.5, 0.8, 1.0),
+        ..default()
+    });
+}
+
+/// Build a mesh containing all of the fragments of one explosion. Each fragment is a triangle
+/// with a random initial orientation; its velocity and spin axis are stored as vertex attributes
+/// so that the motion can be computed in the vertex shader.
+fn create_shrapnel_mesh(rng: &mut ChaCha8Rng, fragments: usize) -> Mesh {
+    let triangle = [
+        Vec3::new(0.0, 0.01, 0.0),
+        Vec3::new(-0.01, -0.01, 0.0),
+        Vec3::new(0.01, -0.007, 0.0),
+    ];
+
+    let mut v_pos: Vec<[f32; 3]> = Vec::with_capacity(fragments * 3);
+    let mut v_normal: Vec<[f32; 3]> = Vec::with_capacity(fragments * 3);
+    let mut v_motion: Vec<[f32; 2]> = Vec::with_capacity(fragments * 3);
+    let mut v_spin: Vec<[f32; 3]> = Vec::with_capacity(fragments * 3);
+    for _ in 0..fragments {
+        let rot = Quat::from_euler(
+            EulerRot::XYZ,
+            rng.random_range(0.0..PI * 2.0),
+            rng.random_range(0.0..PI),
+            rng.random_range(0.0..PI * 2.0),
+        );
+        let velocity =
+            Vec2::from_angle(rng.random_range(0.0..PI * 2.0)) * rng.random_range(0.2..0.6);
+        let spin_axis = random_unit_vector(rng);
+        let normal = rot * Vec3::Z;
+        for vertex in triangle {
+            v_pos.push((rot * vertex).to_array());
+            v_normal.push(normal.to_array());
+            v_motion.push(velocity.to_array());
+            v_spin.push(spin_axis.to_array());
+        }
+    }
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, v_pos);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, v_normal);
+    mesh.insert_attribute(ATTRIBUTE_FRAGMENT_MOTION, v_motion);
+    mesh.insert_attribute(ATTRIBUTE_FRAGMENT_SPIN, v_spin);
+    mesh
+}
+
+fn shrapnel_material(rotation: f32) -> ShrapnelMaterial {
+    ShrapnelMaterial {
+        base: StandardMaterial {
+            base_color: Color::srgb(0.8, 0.8, 0.8),
+            alpha_mode: AlphaMode::Blend,
+            perceptual_roughness: 0.05,
+            metallic: 0.7,
+            ..default()
+        },
+        extension: ShrapnelMaterialExt {
+            params: Vec4::new(0.0, f32::MIN, rotation, 0.0),
+        },
+    }
+}
+
+fn flare_material(texture: &Handle<Image>) -> StandardMaterial {
+    StandardMaterial {
+        base_color_texture: Some(texture.clone()),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    }
+}
+
+fn dust_material(texture: &Handle<Image>, color: Color) -> StandardMaterial {
+    StandardMaterial {
+        base_color: color.with_alpha(0.8),
+        base_color_texture: Some(texture.clone()),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        ..default()
+    }
 }
 
 pub(crate) fn on_add_shrapnel(
     trigger: Trigger<OnAdd, ShrapnelEffect>,
     mut commands: Commands,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    handles: Res<ExplosionHandles>,
-    random: ResMut<RandomGenerator>,
+    mut materials: ResMut<Assets<ShrapnelMaterial>>,
+    mut handles: ResMut<ExplosionHandles>,
+    mut random: ResMut<RandomGenerator>,
+    r_tuning: Res<Tuning>,
 ) {
-    let mesh = handles.shrapnel_mesh.clone();
-    let material = materials.add(StandardMaterial {
-        base_color: Color::srgb(0.8, 0.8, 0.8),
-        alpha_mode: AlphaMode::Blend,
-        perceptual_roughness: 0.05,
-        metallic: 0.7,
-        ..default()
-    });
+    let rotation = random.0.random_range(0.0..PI * 2.0);
+    let mesh_index = random.0.random_range(0..handles.shrapnel_meshes.len());
+    let material = handles
+        .shrapnel_materials
+        .take(&mut materials, shrapnel_material(rotation));
 
-    let mut rng = random.0.clone();
     commands.entity(trigger.target()).insert((
         EffectTimer {
-            total: 0.8,
+            total: r_tuning.effects.shrapnel_time,
             elapsed: 0.,
         },
-        Transform::from_xyz(0., 0., FX_DEPTH),
-        Visibility::Visible,
-        Children::spawn(SpawnWith(move |parent: &mut RelatedSpawner<ChildOf>| {
-            for _ in 0..NUM_FRAGMENTS {
-                let rot = Quat::from_euler(
-                    EulerRot::XYZ,
-                    rng.random_range(0.0..PI * 2.0),
-                    rng.random_range(0.0..PI),
-                    rng.random_range(0.0..PI * 2.0),
-                );
-                let velocity =
-                    Vec2::from_angle(rng.random_range(0.0..PI * 2.0)) * rng.random_range(0.2..0.6);
-                parent.spawn((
-                    ShrapnelFragment {
-                        velocity,
-                        spin_axis: random_unit_vector(&mut rng),
-                    },
-                    Mesh3d(mesh.clone()),
-                    MeshMaterial3d(material.clone()),
-                    Transform::from_rotation(rot),
-                ));
-            }
-        })),
+        Mesh3d(handles.shrapnel_meshes[mesh_index].clone()),
+        MeshMaterial3d(material),
+        Transform::from_xyz(0., 0., FX_DEPTH).with_rotation(Quat::from_rotation_z(rotation)),
+        // Fragment positions are computed in the shader, so the mesh bounds are meaningless.
+        NoFrustumCulling,
+        DespawnOnExit(GameState::Playing),
     ));
 }
 
@@ -125,51 +384,130 @@ pub(crate) fn on_add_flare(
     trigger: Trigger<OnAdd, FlareEffect>,
     mut commands: Commands,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    handles: Res<ExplosionHandles>,
-    asset_server: Res<AssetServer>,
+    mut handles: ResMut<ExplosionHandles>,
+    r_tuning: Res<Tuning>,
 ) {
+    let handles = &mut *handles;
+    let material = handles
+        .flare_materials
+        .take(&mut materials, flare_material(&handles.glow_texture));
     commands.entity(trigger.target()).insert((
         EffectTimer {
-            total: 0.2,
+            total: r_tuning.effects.flare_time,
             elapsed: 0.,
         },
         Mesh3d(handles.flare_mesh.clone()),
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color_texture: Some(asset_server.load("textures/glowspark.png")),
-            alpha_mode: AlphaMode::Blend,
-            unlit: true,
+        MeshMaterial3d(material),
+        Transform::from_xyz(0., 0., FX_DEPTH),
+        DespawnOnExit(GameState::Playing),
+    ));
+    commands.spawn((
+        FlareLightOf(trigger.target()),
+        PointLight {
+            color: Color::srgb(1.0, 0.8, 0.5),
+            intensity: FLARE_LIGHT_INTENSITY,
+            range: FLARE_LIGHT_RANGE,
             ..default()
-        })),
+        },
+        UnitPosition::default(),
+        Transform::from_xyz(0., 0., SHIP_DEPTH + FLARE_LIGHT_OFFSET),
+    ));
+}
+
+pub(crate) fn on_add_dust(
+    trigger: Trigger<OnAdd, DustEffect>,
+    mut commands: Commands,
+    q_dust: Query<&DustEffect>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut handles: ResMut<ExplosionHandles>,
+    r_tuning: Res<Tuning>,
+) {
+    let Ok(effect) = q_dust.get(trigger.target()) else {
+        return;
+    };
+    let handles = &mut *handles;
+    let material = handles.dust_materials.take(
+        &mut materials,
+        dust_material(&handles.glow_texture, effect.color),
+    );
+    commands.entity(trigger.target()).insert((
+        EffectTimer {
+            total: r_tuning.effects.dust_time,
+            elapsed: 0.,
+        },
+        Mesh3d(handles.flare_mesh.clone()),
+        MeshMaterial3d(material),
+        Transform::from_xyz(0., 0., FX_DEPTH),
+        DespawnOnExit(GameState::Playing),
+    ));
+}
+
+pub(crate) fn on_add_spark(
+    trigger: Trigger<OnAdd, SparkEffect>,
+    mut commands: Commands,
+    handles: Res<ExplosionHandles>,
+    r_tuning: Res<Tuning>,
+) {
+    commands.entity(trigger.target()).insert((
+        EffectTimer {
+            total: r_tuning.effects.spark_time,
+            elapsed: 0.,
+        },
+        Mesh3d(handles.spark_mesh.clone()),
+        MeshMaterial3d(handles.spark_material.clone()),
         Transform::from_xyz(0., 0., FX_DEPTH),
+        DespawnOnExit(GameState::Playing),
     ));
 }
 
-/// Animate the shrapnel
+/// Return the materials of finished effects to their pools. This covers effects which are
+/// despawned at the end of the wave as well as those which run their course.
+pub(crate) fn on_remove_shrapnel(
+    trigger: Trigger<OnRemove, ShrapnelEffect>,
+    q_materials: Query<&MeshMaterial3d<ShrapnelMaterial>>,
+    mut handles: ResMut<ExplosionHandles>,
+) {
+    if let Ok(material) = q_materials.get(trigger.target()) {
+        handles.shrapnel_materials.release(&material.0);
+    }
+}
+
+pub(crate) fn on_remove_flare(
+    trigger: Trigger<OnRemove, FlareEffect>,
+    q_materials: Query<&MeshMaterial3d<StandardMaterial>>,
+    mut handles: ResMut<ExplosionHandles>,
+) {
+    if let Ok(material) = q_materials.get(trigger.target()) {
+        handles.flare_materials.release(&material.0);
+    }
+}
+
+pub(crate) fn on_remove_dust(
+    trigger: Trigger<OnRemove, DustEffect>,
+    q_materials: Query<&MeshMaterial3d<StandardMaterial>>,
+    mut handles: ResMut<ExplosionHandles>,
+) {
+    if let Ok(material) = q_materials.get(trigger.target()) {
+        handles.dust_materials.release(&material.0);
+    }
+}
+
+/// Animate the shrapnel. The fragments themselves are moved by the vertex shader; here we only
+/// need to pass it the elapsed time and the height of the ground, and fade the material.
 pub(crate) fn update_shrapnel(
     mut commands: Commands,
-    mut q_shots: Query<
-        (
-            Entity,
-            &mut ShrapnelEffect,
-            &mut EffectTimer,
-            &mut UnitPosition,
-            &mut Transform,
-            &Children,
-        ),
-        With<ShrapnelEffect>,
-    >,
-    mut q_fragments: Query<
-        (
-            &mut ShrapnelFragment,
-            &mut Transform,
-            &mut MeshMaterial3d<StandardMaterial>,
-        ),
-        Without<ShrapnelEffect>,
-    >,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut q_shots: Query<(
+        Entity,
+        &ShrapnelEffect,
+        &mut EffectTimer,
+        &mut UnitPosition,
+        &MeshMaterial3d<ShrapnelMaterial>,
+    )>,
+    mut materials: ResMut<Assets<ShrapnelMaterial>>,
+    r_terrain: Res<Terrain>,
     r_time: Res<Time>,
 ) {
-    for (ent, effect, mut timer, mut position, _transform, children) in q_shots.iter_mut() {
+    for (ent, effect, mut timer, mut position, material) in q_shots.iter_mut() {
         timer.elapsed += r_time.delta_secs();
         if timer.elapsed >= timer.total {
             commands.entity(ent).despawn();
@@ -179,30 +517,11 @@ pub(crate) fn update_shrapnel(
         }
 
         let fade = 1.0 - timer.t();
-        let mut material_updated = false;
-        for child_id in children.iter() {
-            if let Ok((fragment, mut frag_xform, material)) = q_fragments.get_mut(child_id) {
-                // Since all the children share the same material handle, we only need to do this
-                // once
-                if !material_updated {
-                    if let Some(material) = materials.get_mut(material.id()) {
-                        material.emissive =
-                            LinearRgba::new(fade.powf(3.0), fade.powf(4.0), 0.0, 1.0);
-                        material.base_color.set_alpha((fade * 4.0).min(1.0));
-                    }
-                    material_updated = true;
-                }
-
-                // Spin
-                frag_xform.rotate_axis(
-                    Dir3::new(fragment.spin_axis).unwrap(),
-                    16.0 * r_time.delta_secs(),
-                );
-
-                // Radiate outward
-                frag_xform.translation.x += fragment.velocity.x * r_time.delta_secs();
-                frag_xform.translation.y += fragment.velocity.y * r_time.delta_secs();
-            }
+        if let Some(material) = materials.get_mut(material.id()) {
+            material.base.emissive = LinearRgba::new(fade.powf(3.0), fade.powf(4.0), 0.0, 1.0);
+            material.base.base_color.set_alpha((fade * 4.0).min(1.0));
+            material.extension.params.x = timer.elapsed;
+            material.extension.params.y = r_terrain.height_at(position.0.x) - position.0.y;
         }
     }
 }
@@ -242,6 +561,82 @@ pub(crate) fn update_flare(
     }
 }
 
+/// Dust puffs billow outwards and drift upwards as they fade.
+pub(crate) fn update_dust(
+    mut commands: Commands,
+    mut q_dust: Query<(
+        Entity,
+        &mut DustEffect,
+        &mut EffectTimer,
+        &MeshMaterial3d<StandardMaterial>,
+        &mut UnitPosition,
+        &mut Transform,
+    )>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    r_time: Res<Time>,
+) {
+    for (ent, mut effect, mut timer, material, mut position, mut transform) in q_dust.iter_mut() {
+        timer.elapsed += r_time.delta_secs();
+        if timer.elapsed >= timer.total {
+            commands.entity(ent).despawn();
+            continue;
+        }
+        position.0.y += DUST_RISE_SPEED * r_time.delta_secs();
+        if let Some(material) = materials.get_mut(material.id()) {
+            material.base_color.set_alpha(0.8 * (1.0 - timer.t()));
+        }
+        effect.size += r_time.delta_secs() * 0.1;
+        transform.scale.x = effect.size;
+        transform.scale.y = effect.size;
+    }
+}
+
+/// Sparks arc downwards and shrink away. There can be a great many sparks during heavy combat,
+/// and they only touch their own components, so they are updated in parallel.
+pub(crate) fn update_sparks(
+    par_commands: ParallelCommands,
+    mut q_sparks: Query<(
+        Entity,
+        &mut SparkEffect,
+        &mut EffectTimer,
+        &mut UnitPosition,
+        &mut Transform,
+    )>,
+    r_wave: Res<WaveConfig>,
+    r_time: Res<Time>,
+) {
+    let dt = r_time.delta_secs();
+    let gravity = SPARK_GRAVITY * r_wave.shrapnel_gravity();
+    q_sparks.par_iter_mut().for_each(
+        |(ent, mut effect, mut timer, mut position, mut transform)| {
+            timer.elapsed += dt;
+            if timer.elapsed >= timer.total {
+                par_commands.command_scope(|mut commands| {
+                    commands.entity(ent).despawn();
+                });
+                return;
+            }
+            effect.velocity.y -= gravity * dt;
+            position.0 += effect.velocity * dt;
+            transform.scale = Vec3::splat(1.0 - timer.t());
+        },
+    );
+}
+
+/// Flare lights follow their flare, and fade out over the lifetime of the effect.
+pub(crate) fn update_flare_lights(
+    mut q_lights: Query<(&FlareLightOf, &mut PointLight, &mut UnitPosition), Without<EffectTimer>>,
+    q_flares: Query<(&EffectTimer, &UnitPosition), With<FlareEffect>>,
+) {
+    for (light_of, mut light, mut position) in q_lights.iter_mut() {
+        let Ok((timer, flare_pos)) = q_flares.get(light_of.0) else {
+            continue;
+        };
+        position.0 = flare_pos.0;
+        light.intensity = FLARE_LIGHT_INTENSITY * (1.0 - timer.t()).max(0.0).powi(2);
+    }
+}
+
 fn random_unit_vector(rng: &mut ChaCha8Rng) -> Vec3 {
     let theta = rng.random_range(0.0..(2.0 * PI));
     let phi = rng.random_range(0.0..PI);
@@ -250,3 +645,35 @@ fn random_unit_vector(rng: &mut ChaCha8Rng) -> Vec3 {
     let z = phi.cos();
     Vec3::new(x, y, z)
 }
+
+#[derive(AsBindGroup, Asset, TypePath, Debug, Clone)]
+pub(crate) struct ShrapnelMaterialExt {
+    /// x: elapsed time of the effect, y: ground height relative to the effect origin,
+    /// z: rotation of the effect around the Z axis
+    #[uniform(100)]
+    pub(crate) params: Vec4,
+}
+
+impl MaterialExtension for ShrapnelMaterialExt {
+    fn vertex_shader() -> ShaderRef {
+        "embedded://guardian/assets/shaders/shrapnel.wgsl".into()
+    }
+
+    fn specialize(
+        _pipeline: &MaterialExtensionPipeline,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialExtensionKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let vertex_layout = layout.0.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            Mesh::ATTRIBUTE_NORMAL.at_shader_location(1),
+            ATTRIBUTE_FRAGMENT_MOTION.at_shader_location(2),
+            ATTRIBUTE_FRAGMENT_SPIN.at_shader_location(3),
+        ])?;
+        descriptor.vertex.buffers = vec![vertex_layout];
+        Ok(())
+    }
+}
+
+pub(crate) type ShrapnelMaterial = ExtendedMaterial<StandardMaterial, ShrapnelMaterialExt>;
@@ -0,0 +1,56 @@
+//! Frame limiter: either waits for vsync, or caps the frame rate by sleeping at the end of each
+//! frame, so that the game doesn't burn through laptop batteries rendering frames nobody sees.
+use std::time::Duration;
+
+use bevy::{
+    platform::time::Instant,
+    prelude::*,
+    window::{PresentMode, PrimaryWindow},
+};
+
+use crate::{
+    game_state::PauseState,
+    low_power::IDLE_FPS,
+    settings::{FrameLimit, Settings},
+};
+
+/// Switch vsync on or off to match the frame limit setting.
+pub(crate) fn apply_present_mode(
+    mut q_window: Query<&mut Window, With<PrimaryWindow>>,
+    r_settings: Res<Settings>,
+) {
+    let present_mode = match r_settings.graphics.frame_limit {
+        FrameLimit::Vsync => PresentMode::AutoVsync,
+        _ => PresentMode::AutoNoVsync,
+    };
+    for mut window in q_window.iter_mut() {
+        if window.present_mode != present_mode {
+            window.present_mode = present_mode;
+        }
+    }
+}
+
+/// If the frame rate is capped, sleep until it is time for the next frame. In low-power mode,
+/// the frame rate is also capped while the game is paused or the window is unfocused.
+pub(crate) fn limit_frame_rate(
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    r_pause: Option<Res<State<PauseState>>>,
+    r_settings: Res<Settings>,
+    mut last_frame: Local<Option<Instant>>,
+) {
+    let idle = r_pause
+        .is_some_and(|state| matches!(state.get(), PauseState::Paused | PauseState::GameOver))
+        || q_window.iter().any(|window| !window.focused);
+    let mut max_fps = r_settings.graphics.frame_limit.max_fps();
+    if idle && r_settings.graphics.low_power {
+        max_fps = Some(max_fps.map_or(IDLE_FPS, |fps| fps.min(IDLE_FPS)));
+    }
+    if let (Some(max_fps), Some(last)) = (max_fps, *last_frame) {
+        let frame_time = Duration::from_secs_f32(1.0 / max_fps);
+        let elapsed = last.elapsed();
+        if elapsed < frame_time {
+            std::thread::sleep(frame_time - elapsed);
+        }
+    }
+    *last_frame = Some(Instant::now());
+}
@@ -0,0 +1,164 @@
+//! Developer tools for reproducing bugs deterministically, only built with the `debug` feature.
+//!
+//! * F5 toggles frame-step mode, which freezes the game clock without pausing the game.
+//! * F6, in frame-step mode, advances the game by exactly one fixed timestep.
+//! * F7 plays back the scripted input macro in `macros/playback.macro.ron`.
+use bevy::{
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput, NativeKey},
+    },
+    prelude::*,
+    time::TimeSystem,
+    window::PrimaryWindow,
+};
+use serde::Deserialize;
+
+use crate::{pause::ClockHold, ron_asset::RonAssetLoader};
+
+/// Frame-step state.
+#[derive(Resource, Default, Debug)]
+pub struct FrameStep {
+    /// Whether the game clock is frozen, advancing only on request
+    enabled: bool,
+
+    /// Number of steps requested but not yet taken
+    pending: u32,
+}
+
+/// A single scripted key press or release.
+#[derive(Deserialize, Debug, Clone)]
+pub struct MacroEvent {
+    /// Game frame on which the event happens, counting from the start of playback. Frames on
+    /// which the game clock is stopped aren't counted.
+    pub frame: u32,
+    pub key: KeyCode,
+    pub pressed: bool,
+}
+
+/// A script of key presses, played back as if the player had typed them.
+#[derive(Asset, TypePath, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct InputMacro {
+    pub events: Vec<MacroEvent>,
+}
+
+/// Input macro being played back
+#[derive(Resource, Default, Debug)]
+pub struct MacroPlayback {
+    script: Option<Handle<InputMacro>>,
+
+    /// Game frames since playback started
+    frame: u32,
+
+    /// Index of the next event to play
+    next: usize,
+}
+
+pub struct FrameStepPlugin;
+
+impl Plugin for FrameStepPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<InputMacro>()
+            .register_asset_loader(RonAssetLoader::<InputMacro>::new(&["macro.ron"]))
+            .init_resource::<FrameStep>()
+            .init_resource::<MacroPlayback>()
+            .add_systems(First, (step_frame, play_macro).chain().after(TimeSystem))
+            .add_systems(Update, frame_step_keys);
+    }
+}
+
+fn frame_step_keys(
+    r_keys: Res<ButtonInput<KeyCode>>,
+    mut r_step: ResMut<FrameStep>,
+    mut r_playback: ResMut<MacroPlayback>,
+    mut r_hold: ResMut<ClockHold>,
+    asset_server: Res<AssetServer>,
+) {
+    if r_keys.just_pressed(KeyCode::F5) {
+        r_step.enabled = !r_step.enabled;
+        r_step.pending = 0;
+        // The clock itself is stopped and started by `sync_game_clock`, which would otherwise
+        // restart it straight away.
+        r_hold.0 = r_step.enabled;
+    }
+    if r_keys.just_pressed(KeyCode::F6) && r_step.enabled {
+        r_step.pending += 1;
+    }
+    if r_keys.just_pressed(KeyCode::F7) {
+        *r_playback = MacroPlayback {
+            script: Some(asset_server.load("macros/playback.macro.ron")),
+            frame: 0,
+            next: 0,
+        };
+    }
+}
+
+/// Advance the frozen game clock by one fixed timestep. This runs just after the clocks are
+/// updated, so the rest of the frame, including the fixed-timestep loop, sees the step.
+fn step_frame(
+    mut r_step: ResMut<FrameStep>,
+    mut r_virtual: ResMut<Time<Virtual>>,
+    mut r_time: ResMut<Time>,
+    r_fixed: Res<Time<Fixed>>,
+) {
+    if !r_step.enabled || r_step.pending == 0 {
+        return;
+    }
+    r_step.pending -= 1;
+    r_virtual.advance_by(r_fixed.timestep());
+    *r_time = r_virtual.as_generic();
+}
+
+/// Feed scripted key events into the input system, as if they came from the keyboard.
+fn play_macro(
+    mut r_playback: ResMut<MacroPlayback>,
+    mut w_keyboard: EventWriter<KeyboardInput>,
+    q_window: Query<Entity, With<PrimaryWindow>>,
+    macros: Res<Assets<InputMacro>>,
+    r_time: Res<Time>,
+) {
+    let Some(script) = r_playback
+        .script
+        .as_ref()
+        .and_then(|handle| macros.get(handle))
+    else {
+        return;
+    };
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+
+    // Only count frames on which the game actually moves, so that playback lines up with
+    // frame-step mode.
+    if r_time.delta_secs() <= 0.0 {
+        return;
+    }
+    let frame = r_playback.frame;
+    let start = r_playback.next;
+    let due = script.events[start..]
+        .iter()
+        .take_while(|event| event.frame <= frame);
+    let mut played = 0;
+    for event in due {
+        w_keyboard.write(KeyboardInput {
+            key_code: event.key,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state: if event.pressed {
+                ButtonState::Pressed
+            } else {
+                ButtonState::Released
+            },
+            text: None,
+            repeat: false,
+            window,
+        });
+        played += 1;
+    }
+    let done = start + played >= script.events.len();
+    r_playback.next += played;
+    r_playback.frame += 1;
+    if done {
+        r_playback.script = None;
+    }
+}
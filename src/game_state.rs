@@ -1,10 +1,13 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, state::state::StateTransitionEvent};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{RandomGenerator, ship::ShipVariant};
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, States)]
 pub enum GameState {
-    Intro,
-    // TODO: Switch default to intro later.
     #[default]
+    Intro,
     Playing,
     LevelComplete,
 }
@@ -15,5 +18,140 @@ pub enum PauseState {
     #[default]
     Running,
     Paused,
+    PhotoMode,
     GameOver,
 }
+
+/// Marks an entity to be despawned when the given state is exited, so that modules don't need
+/// their own teardown systems.
+#[derive(Component, Debug, Clone)]
+pub struct DespawnOnExit<S: States>(pub S);
+
+/// Despawn entities scoped to a state which has just been exited.
+pub(crate) fn despawn_on_exit<S: States>(
+    mut commands: Commands,
+    mut transitions: EventReader<StateTransitionEvent<S>>,
+    q_scoped: Query<(Entity, &DespawnOnExit<S>)>,
+) {
+    for transition in transitions.read() {
+        // Ignore transitions which re-enter the same state
+        let Some(exited) = transition
+            .exited
+            .as_ref()
+            .filter(|_| transition.exited != transition.entered)
+        else {
+            continue;
+        };
+        for (entity, scope) in q_scoped.iter() {
+            if scope.0 == *exited {
+                commands.entity(entity).try_despawn();
+            }
+        }
+    }
+}
+
+/// Choices made by the player which persist for the duration of a run.
+#[derive(Resource, Default, Debug)]
+pub struct RunState {
+    /// Which ship the player selected in the intro menu
+    pub ship: ShipVariant,
+
+    /// Number of additional enemies each laser shot can pass through (piercing upgrade)
+    pub pierce: u32,
+
+    /// Whether the ship has a rear-facing gun (pickup)
+    pub rear_gun: bool,
+
+    /// Whether shots fired while climbing or diving ricochet off the playfield bounds (pickup)
+    pub ricochet: bool,
+
+    /// Extra attraction radius added to the ship's magnet (pickup)
+    pub magnet_bonus: f32,
+
+    /// Whether any assist option has been enabled at some point during this run
+    pub assisted: bool,
+
+    /// Starting wave if this is a practice run. Practice runs have unlimited lives.
+    pub practice: Option<u32>,
+
+    /// Seed entered on the intro screen. If not set, each run gets a random seed.
+    pub seed: Option<u64>,
+}
+
+/// The current level. Procedural scenery such as the starfield and mountains is generated from
+/// the level seed, so each level has its own skyline.
+#[derive(Resource, Debug)]
+pub struct Level {
+    /// Level number, starting from zero
+    pub number: u32,
+
+    /// Seed chosen at the start of the run
+    pub run_seed: u64,
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        Self {
+            number: 0,
+            run_seed: 19878367467712,
+        }
+    }
+}
+
+/// Number of waves in a loop. After the last one, play goes back to the first wave with tougher
+/// enemies and a shifted palette, like the loops of a classic arcade game.
+pub const WAVES_PER_LOOP: u32 = 8;
+
+impl Level {
+    /// Wave number within the current loop, starting from zero.
+    pub fn wave(&self) -> u32 {
+        self.number % WAVES_PER_LOOP
+    }
+
+    /// Number of loops completed.
+    pub fn loop_count(&self) -> u32 {
+        self.number / WAVES_PER_LOOP
+    }
+
+    /// Whether this is the last wave of a loop.
+    pub fn is_final_wave(&self) -> bool {
+        self.wave() == WAVES_PER_LOOP - 1
+    }
+
+    /// Name of the level for display, such as "Wave 3", or "Loop 2, wave 3" once play has looped.
+    pub fn name(&self) -> String {
+        match self.loop_count() {
+            0 => format!("Wave {}", self.wave() + 1),
+            n => format!("Loop {}, wave {}", n + 1, self.wave() + 1),
+        }
+    }
+
+    /// Seed for generating the current level.
+    pub fn seed(&self) -> u64 {
+        self.run_seed
+            .wrapping_add((self.number as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15))
+    }
+}
+
+/// Start a new run from the first level, or the chosen practice wave. The run uses the seed
+/// entered by the player, or a fresh random one. The shared random generator is reseeded from it
+/// too, so that runs with the same seed play out the same way.
+pub(crate) fn start_run_level(
+    mut r_level: ResMut<Level>,
+    mut r_run: ResMut<RunState>,
+    mut r_rng: ResMut<RandomGenerator>,
+) {
+    r_run.assisted = false;
+    r_level.number = r_run.practice.unwrap_or(0);
+    r_level.run_seed = r_run.seed.unwrap_or_else(|| r_rng.0.random());
+    r_rng.0 = ChaCha8Rng::seed_from_u64(r_level.run_seed);
+}
+
+/// Move on to the next level.
+pub(crate) fn advance_level(mut r_level: ResMut<Level>) {
+    r_level.number += 1;
+}
+
+/// Difficulty of the current wave. 0.0 is the easiest; later waves raise this above 1.0.
+#[derive(Resource, Default, Debug)]
+pub struct Difficulty(pub f32);
@@ -0,0 +1,63 @@
+//! Heat haze shimmering behind the thrust cones
+use bevy::{
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderRef},
+};
+
+use crate::ship::PlayerShip;
+
+/// Marker for the heat haze quad, a child of the player ship
+#[derive(Component, Default, Debug)]
+pub struct HeatHaze;
+
+/// Maximum opacity of the haze, at full thrust
+const HAZE_STRENGTH: f32 = 1.0;
+
+/// Attach a heat haze quad behind the thrusters when the ship is spawned. Positions are in model
+/// units, like the thrust cones.
+pub(crate) fn on_add_player_ship(
+    trigger: Trigger<OnAdd, PlayerShip>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<HeatHazeMaterial>>,
+) {
+    commands.entity(trigger.target()).with_child((
+        Mesh3d(meshes.add(Rectangle::new(6.0, 3.0))),
+        MeshMaterial3d(materials.add(HeatHazeMaterial { params: Vec4::ZERO })),
+        Transform::from_xyz(-6.6, 0.1, 0.0),
+        HeatHaze,
+    ));
+}
+
+/// Haze strength follows the ship's thrust.
+pub(crate) fn update_heat_haze(
+    q_ship: Query<&PlayerShip>,
+    q_haze: Query<&MeshMaterial3d<HeatHazeMaterial>, With<HeatHaze>>,
+    mut materials: ResMut<Assets<HeatHazeMaterial>>,
+) {
+    let Ok(ship) = q_ship.single() else {
+        return;
+    };
+    for material in q_haze.iter() {
+        if let Some(material) = materials.get_mut(material.id()) {
+            material.params.x = ship.thrust() * HAZE_STRENGTH;
+        }
+    }
+}
+
+#[derive(AsBindGroup, Asset, Reflect, Debug, Clone)]
+pub(crate) struct HeatHazeMaterial {
+    /// x: strength
+    #[uniform(0)]
+    pub(crate) params: Vec4,
+}
+
+impl Material for HeatHazeMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "embedded://guardian/assets/shaders/heat_haze.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+}
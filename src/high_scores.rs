@@ -0,0 +1,86 @@
+//! High score table and personal best wave splits, stored alongside the profile.
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game_state::{Level, RunState},
+    profile::{load_data, save_data},
+    settings::Settings,
+    stats::RunStats,
+};
+
+/// A single entry in the high score table.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct HighScoreEntry {
+    pub score: u32,
+
+    /// Wave on which the run ended, counting from zero
+    pub wave: u32,
+
+    /// Number of loops completed
+    pub loops: u32,
+
+    /// Whether any assist option was used during the run
+    pub assisted: bool,
+}
+
+/// Best scores and times, persisted between sessions.
+#[derive(Resource, Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct HighScores {
+    /// Best scores, highest first
+    pub entries: Vec<HighScoreEntry>,
+
+    /// Best time from the start of a run to the end of each wave, in seconds, indexed by wave
+    pub best_splits: Vec<Option<f32>>,
+}
+
+/// Number of entries kept in the high score table
+const MAX_ENTRIES: usize = 10;
+
+impl HighScores {
+    /// Add an entry to the table, keeping it sorted and trimmed.
+    pub fn insert(&mut self, entry: HighScoreEntry) {
+        let index = self.entries.partition_point(|e| e.score >= entry.score);
+        if index < MAX_ENTRIES {
+            self.entries.insert(index, entry);
+            self.entries.truncate(MAX_ENTRIES);
+        }
+    }
+}
+
+pub(crate) fn load_high_scores() -> HighScores {
+    load_data("scores.ron")
+}
+
+pub(crate) fn save_high_scores(scores: &HighScores) {
+    save_data("scores.ron", scores);
+}
+
+/// Note when an assist is in use. Assists can be toggled from the pause menu, so a run counts as
+/// assisted if one was enabled at any point, not just at the end.
+pub(crate) fn track_assists(mut r_run: ResMut<RunState>, r_settings: Res<Settings>) {
+    if r_settings.assist.active() && !r_run.assisted {
+        r_run.assisted = true;
+    }
+}
+
+/// Enter the final score in the table when the game is over. Practice runs don't count, and
+/// runs played with assists are flagged. The table is written to disk by the autosave.
+pub(crate) fn record_high_score(
+    r_stats: Res<RunStats>,
+    r_level: Res<Level>,
+    r_run: Res<RunState>,
+    mut r_scores: ResMut<HighScores>,
+) {
+    if r_run.practice.is_some() {
+        return;
+    }
+    r_scores.insert(HighScoreEntry {
+        score: r_stats.score,
+        wave: r_level.number,
+        loops: r_stats.loops,
+        assisted: r_run.assisted,
+    });
+}
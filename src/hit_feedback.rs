@@ -0,0 +1,155 @@
+//! Visual feedback when the player ship is hit: a red vignette flash, and an indicator on the
+//! side of the screen the damage came from.
+use bevy::prelude::*;
+
+use crate::{Playfield, UnitPosition, damage::PlayerHit, ship::PlayerShip};
+
+/// Which edge of the screen a hit indicator is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenSide {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl ScreenSide {
+    const ALL: [ScreenSide; 4] = [
+        ScreenSide::Left,
+        ScreenSide::Right,
+        ScreenSide::Top,
+        ScreenSide::Bottom,
+    ];
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Marker for the full-screen damage vignette
+#[derive(Component, Default, Debug)]
+pub struct DamageVignette;
+
+/// Bar along one edge of the screen, lit when damage comes from that side
+#[derive(Component, Debug)]
+pub struct HitIndicator(ScreenSide);
+
+/// Current intensity of the hit feedback effects, fading to zero.
+#[derive(Resource, Default, Debug)]
+pub struct HitFlash {
+    vignette: f32,
+    sides: [f32; 4],
+}
+
+/// Rate at which the effects fade, per second
+const HIT_FLASH_FADE: f32 = 2.0;
+
+const HIT_COLOR: Color = Color::srgb(1.0, 0.1, 0.05);
+
+pub(crate) fn spawn_hit_feedback(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            right: Val::Px(0.0),
+            top: Val::Px(0.0),
+            bottom: Val::Px(0.0),
+            border: UiRect::all(Val::Px(48.0)),
+            ..default()
+        },
+        BorderColor(Color::NONE),
+        BackgroundColor(Color::NONE),
+        DamageVignette,
+    ));
+
+    for side in ScreenSide::ALL {
+        let node = match side {
+            ScreenSide::Left | ScreenSide::Right => Node {
+                width: Val::Px(10.0),
+                top: Val::Percent(30.0),
+                bottom: Val::Percent(30.0),
+                ..default()
+            },
+            ScreenSide::Top | ScreenSide::Bottom => Node {
+                height: Val::Px(10.0),
+                left: Val::Percent(30.0),
+                right: Val::Percent(30.0),
+                ..default()
+            },
+        };
+        let node = match side {
+            ScreenSide::Left => Node {
+                left: Val::Px(0.0),
+                ..node
+            },
+            ScreenSide::Right => Node {
+                right: Val::Px(0.0),
+                ..node
+            },
+            ScreenSide::Top => Node {
+                top: Val::Px(0.0),
+                ..node
+            },
+            ScreenSide::Bottom => Node {
+                bottom: Val::Px(0.0),
+                ..node
+            },
+        };
+        commands.spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                ..node
+            },
+            BackgroundColor(Color::NONE),
+            HitIndicator(side),
+        ));
+    }
+}
+
+/// Flash the vignette, and light the indicator on the side nearest the source of the damage.
+/// Horizontal direction is measured the short way around the wraparound world.
+pub(crate) fn on_player_hit_flash(
+    trigger: Trigger<PlayerHit>,
+    q_player: Query<&UnitPosition, With<PlayerShip>>,
+    r_playfield: Res<Playfield>,
+    mut r_flash: ResMut<HitFlash>,
+) {
+    r_flash.vignette = 1.0;
+    let Ok(player_pos) = q_player.single() else {
+        return;
+    };
+    let delta = r_playfield.delta(player_pos.0, trigger.event().source);
+    let side = if delta.x.abs() >= delta.y.abs() {
+        if delta.x < 0.0 {
+            ScreenSide::Left
+        } else {
+            ScreenSide::Right
+        }
+    } else if delta.y < 0.0 {
+        ScreenSide::Bottom
+    } else {
+        ScreenSide::Top
+    };
+    r_flash.sides[side.index()] = 1.0;
+}
+
+pub(crate) fn update_hit_feedback(
+    mut r_flash: ResMut<HitFlash>,
+    mut q_vignette: Query<(&mut BorderColor, &mut BackgroundColor), With<DamageVignette>>,
+    mut q_indicators: Query<(&HitIndicator, &mut BackgroundColor), Without<DamageVignette>>,
+    r_time: Res<Time<Real>>,
+) {
+    let fade = HIT_FLASH_FADE * r_time.delta_secs();
+    r_flash.vignette = (r_flash.vignette - fade).max(0.0);
+    for side in r_flash.sides.iter_mut() {
+        *side = (*side - fade).max(0.0);
+    }
+
+    for (mut border, mut background) in q_vignette.iter_mut() {
+        border.0 = HIT_COLOR.with_alpha(r_flash.vignette * 0.5);
+        background.0 = HIT_COLOR.with_alpha(r_flash.vignette * 0.15);
+    }
+    for (indicator, mut background) in q_indicators.iter_mut() {
+        background.0 = HIT_COLOR.with_alpha(r_flash.sides[indicator.0.index()]);
+    }
+}
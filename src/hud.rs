@@ -0,0 +1,144 @@
+//! HUD layout: applies the player's HUD settings to the UI tree built in `setup()`.
+use bevy::prelude::*;
+
+use crate::{
+    Hud, HudHeader,
+    alerts::ShieldAlert,
+    hit_feedback::HitIndicator,
+    settings::{MinimapPosition, MinimapStyle, Settings},
+    speedrun::RunTimerDisplay,
+    ticker::EventTicker,
+    wave::{WaveConfig, WaveMutator},
+};
+
+/// Smallest and largest allowed HUD scale
+pub(crate) const MIN_HUD_SCALE: f32 = 0.5;
+pub(crate) const MAX_HUD_SCALE: f32 = 2.0;
+
+/// Apply HUD settings. Runs whenever the settings or the wave's mutators change.
+pub(crate) fn apply_hud_settings(
+    r_settings: Res<Settings>,
+    r_wave: Res<WaveConfig>,
+    mut r_ui_scale: ResMut<UiScale>,
+    mut q_root: Query<&mut Node, (With<Hud>, Without<HudHeader>)>,
+    mut q_header: Query<&mut Node, (With<HudHeader>, Without<Hud>)>,
+    mut q_indicators: Query<
+        &mut Visibility,
+        (
+            With<HitIndicator>,
+            Without<ShieldAlert>,
+            Without<RunTimerDisplay>,
+            Without<EventTicker>,
+        ),
+    >,
+    mut q_shield_alert: Query<
+        &mut Visibility,
+        (
+            With<ShieldAlert>,
+            Without<HitIndicator>,
+            Without<RunTimerDisplay>,
+            Without<EventTicker>,
+        ),
+    >,
+    mut q_timer: Query<
+        &mut Visibility,
+        (
+            With<RunTimerDisplay>,
+            Without<HitIndicator>,
+            Without<ShieldAlert>,
+            Without<EventTicker>,
+        ),
+    >,
+    mut q_ticker: Query<
+        &mut Visibility,
+        (
+            With<EventTicker>,
+            Without<HitIndicator>,
+            Without<ShieldAlert>,
+            Without<RunTimerDisplay>,
+        ),
+    >,
+) {
+    let hud = &r_settings.hud;
+    r_ui_scale.0 = hud.scale.clamp(MIN_HUD_SCALE, MAX_HUD_SCALE);
+
+    for mut node in q_root.iter_mut() {
+        node.flex_direction = match hud.minimap_position {
+            MinimapPosition::Top => FlexDirection::Column,
+            MinimapPosition::Bottom => FlexDirection::ColumnReverse,
+        };
+    }
+    for mut node in q_header.iter_mut() {
+        node.display = if hud.show_minimap && !r_wave.has(WaveMutator::NoMinimap) {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+    for mut visibility in q_indicators.iter_mut() {
+        *visibility = shown(hud.show_hit_indicators);
+    }
+    for mut visibility in q_shield_alert.iter_mut() {
+        *visibility = shown(hud.show_shield_alert);
+    }
+    for mut visibility in q_timer.iter_mut() {
+        *visibility = shown(hud.show_timer);
+    }
+    for mut visibility in q_ticker.iter_mut() {
+        *visibility = shown(hud.show_ticker);
+    }
+}
+
+/// Adjust HUD settings from the pause menu: - / = to change the scale, M to move the minimap,
+/// H to hide or show the minimap, I to toggle hit indicators, K to toggle the shield alert, T to
+/// toggle the run timer, U to toggle damage numbers, J to toggle the event ticker, Y to switch
+/// the minimap style.
+pub(crate) fn adjust_hud_settings(
+    r_keys: Res<ButtonInput<KeyCode>>,
+    mut r_settings: ResMut<Settings>,
+) {
+    if r_keys.just_pressed(KeyCode::Minus) {
+        r_settings.hud.scale = (r_settings.hud.scale - 0.1).max(MIN_HUD_SCALE);
+    }
+    if r_keys.just_pressed(KeyCode::Equal) {
+        r_settings.hud.scale = (r_settings.hud.scale + 0.1).min(MAX_HUD_SCALE);
+    }
+    if r_keys.just_pressed(KeyCode::KeyM) {
+        r_settings.hud.minimap_position = match r_settings.hud.minimap_position {
+            MinimapPosition::Top => MinimapPosition::Bottom,
+            MinimapPosition::Bottom => MinimapPosition::Top,
+        };
+    }
+    if r_keys.just_pressed(KeyCode::KeyH) {
+        r_settings.hud.show_minimap = !r_settings.hud.show_minimap;
+    }
+    if r_keys.just_pressed(KeyCode::KeyI) {
+        r_settings.hud.show_hit_indicators = !r_settings.hud.show_hit_indicators;
+    }
+    if r_keys.just_pressed(KeyCode::KeyK) {
+        r_settings.hud.show_shield_alert = !r_settings.hud.show_shield_alert;
+    }
+    if r_keys.just_pressed(KeyCode::KeyT) {
+        r_settings.hud.show_timer = !r_settings.hud.show_timer;
+    }
+    if r_keys.just_pressed(KeyCode::KeyU) {
+        r_settings.hud.show_damage_numbers = !r_settings.hud.show_damage_numbers;
+    }
+    if r_keys.just_pressed(KeyCode::KeyJ) {
+        r_settings.hud.show_ticker = !r_settings.hud.show_ticker;
+    }
+    if r_keys.just_pressed(KeyCode::KeyY) {
+        r_settings.hud.minimap_style = match r_settings.hud.minimap_style {
+            MinimapStyle::Blips => MinimapStyle::Scanner,
+            MinimapStyle::Scanner => MinimapStyle::Blips,
+        };
+    }
+}
+
+fn shown(visible: bool) -> Visibility {
+    if visible {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    }
+}
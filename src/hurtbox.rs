@@ -0,0 +1,95 @@
+//! The ship's hurtbox: a collider, smaller than the ship model, which enemies must touch to do
+//! damage. Keeping it inside the visible hull means that near-misses which look like misses are
+//! misses. The size is scaled by the player's skill level setting.
+use avian2d::prelude::{Collider, CollidingEntities, CollisionLayers};
+use bevy::prelude::*;
+
+use crate::{
+    ENEMY_LAYER, ENEMY_SHOT_LAYER, PLAYER_LAYER,
+    settings::Settings,
+    ship::{PlayerShip, ShipStats},
+};
+
+/// Marker for the child entity carrying the ship's hurtbox collider
+#[derive(Component, Default, Debug)]
+pub struct Hurtbox;
+
+/// Whether hurtboxes are outlined for debugging
+#[derive(Resource, Default, Debug)]
+pub struct HurtboxDebug(pub bool);
+
+/// Size of the hurtbox relative to the ship's hull, at normal skill level
+const HURTBOX_SCALE: f32 = 0.7;
+
+const HURTBOX_DEBUG_COLOR: Color = Color::srgb(1.0, 0.2, 0.8);
+
+/// Components for the hurtbox child of a ship. The collider has the same shape as the hull
+/// capsule, and is shrunk using its transform.
+pub fn hurtbox(stats: &ShipStats, settings: &Settings) -> impl Bundle {
+    (
+        Hurtbox,
+        Collider::capsule_endpoints(
+            stats.hitbox_radius,
+            stats.hitbox_endpoints.0,
+            stats.hitbox_endpoints.1,
+        ),
+        CollisionLayers::from_bits(PLAYER_LAYER, ENEMY_LAYER | ENEMY_SHOT_LAYER),
+        CollidingEntities::default(),
+        Transform::from_scale(Vec3::splat(hurtbox_scale(settings))),
+    )
+}
+
+fn hurtbox_scale(settings: &Settings) -> f32 {
+    HURTBOX_SCALE * settings.gameplay.skill.hurtbox_scale()
+}
+
+/// Resize hurtboxes when the skill level changes.
+pub(crate) fn apply_hurtbox_scale(
+    mut q_hurtboxes: Query<&mut Transform, With<Hurtbox>>,
+    r_settings: Res<Settings>,
+) {
+    let scale = hurtbox_scale(&r_settings);
+    for mut transform in q_hurtboxes.iter_mut() {
+        transform.scale = Vec3::splat(scale);
+    }
+}
+
+pub(crate) fn toggle_hurtbox_debug(
+    r_keys: Res<ButtonInput<KeyCode>>,
+    mut r_debug: ResMut<HurtboxDebug>,
+) {
+    if r_keys.just_pressed(KeyCode::F8) {
+        r_debug.0 = !r_debug.0;
+    }
+}
+
+/// Outline the ship's hurtbox.
+pub(crate) fn draw_hurtbox(
+    mut gizmos: Gizmos,
+    q_player: Query<&PlayerShip>,
+    q_hurtboxes: Query<(&ChildOf, &GlobalTransform), With<Hurtbox>>,
+) {
+    for (child_of, transform) in q_hurtboxes.iter() {
+        let Ok(ship) = q_player.get(child_of.parent()) else {
+            continue;
+        };
+        let stats = ship.variant.stats();
+        let (scale, _, _) = transform.to_scale_rotation_translation();
+        let radius = stats.hitbox_radius * scale.x;
+        let start = transform.transform_point(stats.hitbox_endpoints.0.extend(0.0));
+        let end = transform.transform_point(stats.hitbox_endpoints.1.extend(0.0));
+        let side = (end - start).cross(Vec3::Z).normalize_or_zero() * radius;
+        gizmos.line(start + side, end + side, HURTBOX_DEBUG_COLOR);
+        gizmos.line(start - side, end - side, HURTBOX_DEBUG_COLOR);
+        gizmos.circle(
+            Isometry3d::from_translation(start),
+            radius,
+            HURTBOX_DEBUG_COLOR,
+        );
+        gizmos.circle(
+            Isometry3d::from_translation(end),
+            radius,
+            HURTBOX_DEBUG_COLOR,
+        );
+    }
+}
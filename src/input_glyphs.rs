@@ -0,0 +1,144 @@
+//! Button glyphs for on-screen prompts. The game tracks whether the player last used the
+//! keyboard or a gamepad, and which brand of gamepad, so that prompts name the buttons the
+//! player is actually holding. Prompts update as soon as the player switches devices.
+use bevy::prelude::*;
+
+/// Gamepad families with distinct face button labels. The button positions are the same for
+/// all of them; only the names differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadBrand {
+    Xbox,
+    PlayStation,
+    Nintendo,
+    Generic,
+}
+
+impl GamepadBrand {
+    /// USB vendor ids of the major controller makers
+    const MICROSOFT: u16 = 0x045e;
+    const SONY: u16 = 0x054c;
+    const NINTENDO: u16 = 0x057e;
+
+    /// Identify a gamepad from its vendor id, falling back to its name for controllers which
+    /// don't report one (such as on the web).
+    fn detect(gamepad: &Gamepad, name: Option<&Name>) -> Self {
+        match gamepad.vendor_id() {
+            Some(Self::MICROSOFT) => return GamepadBrand::Xbox,
+            Some(Self::SONY) => return GamepadBrand::PlayStation,
+            Some(Self::NINTENDO) => return GamepadBrand::Nintendo,
+            _ => {}
+        }
+        let name = name
+            .map(|name| name.as_str().to_lowercase())
+            .unwrap_or_default();
+        if name.contains("xbox") || name.contains("xinput") {
+            GamepadBrand::Xbox
+        } else if ["playstation", "dualshock", "dualsense", "ps4", "ps5"]
+            .iter()
+            .any(|brand| name.contains(brand))
+        {
+            GamepadBrand::PlayStation
+        } else if name.contains("nintendo") || name.contains("switch") {
+            GamepadBrand::Nintendo
+        } else {
+            GamepadBrand::Generic
+        }
+    }
+}
+
+/// The device the player used most recently.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputDevice {
+    #[default]
+    Keyboard,
+    Gamepad(GamepadBrand),
+}
+
+/// Buttons which can be named in a prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptButton {
+    /// Menu selection: arrow keys or d-pad
+    Navigate,
+    /// Accept: Enter or the south face button
+    Confirm,
+    /// Cancel or clear: Backspace or the east face button
+    Back,
+    /// Pause: P or the start button
+    Pause,
+    /// Fire the lasers: Space or the south face button
+    Fire,
+}
+
+impl InputDevice {
+    /// Label for a button on this device
+    pub fn glyph(self, button: PromptButton) -> &'static str {
+        match (self, button) {
+            (InputDevice::Keyboard, PromptButton::Navigate) => "Arrow keys",
+            (InputDevice::Keyboard, PromptButton::Confirm) => "Enter",
+            (InputDevice::Keyboard, PromptButton::Back) => "Backspace",
+            (InputDevice::Keyboard, PromptButton::Pause) => "P",
+            (InputDevice::Keyboard, PromptButton::Fire) => "Space",
+            (InputDevice::Gamepad(_), PromptButton::Navigate) => "D-pad",
+            (InputDevice::Gamepad(brand), PromptButton::Pause) => match brand {
+                GamepadBrand::Xbox => "Menu",
+                GamepadBrand::PlayStation => "Options",
+                GamepadBrand::Nintendo => "+",
+                GamepadBrand::Generic => "Start",
+            },
+            (InputDevice::Gamepad(brand), PromptButton::Back) => match brand {
+                GamepadBrand::Xbox | GamepadBrand::Generic => "B",
+                GamepadBrand::PlayStation => "Circle",
+                GamepadBrand::Nintendo => "A",
+            },
+            // Confirm and Fire are both the south face button.
+            (InputDevice::Gamepad(brand), _) => match brand {
+                GamepadBrand::Xbox | GamepadBrand::Generic => "A",
+                GamepadBrand::PlayStation => "Cross",
+                GamepadBrand::Nintendo => "B",
+            },
+        }
+    }
+
+    /// Whether the player is using a gamepad
+    pub fn is_gamepad(self) -> bool {
+        matches!(self, InputDevice::Gamepad(_))
+    }
+}
+
+/// Text which names buttons, and is rebuilt whenever the input device changes.
+#[derive(Component, Debug)]
+pub struct ButtonPrompt(pub fn(InputDevice) -> String);
+
+/// The left stick must be pushed this far to count as gamepad input.
+const STICK_THRESHOLD: f32 = 0.5;
+
+/// Switch to whichever device was used this frame.
+pub(crate) fn detect_input_device(
+    q_gamepads: Query<(&Gamepad, Option<&Name>)>,
+    r_keys: Res<ButtonInput<KeyCode>>,
+    mut r_device: ResMut<InputDevice>,
+) {
+    let device = if r_keys.get_just_pressed().next().is_some() {
+        InputDevice::Keyboard
+    } else if let Some((gamepad, name)) = q_gamepads.iter().find(|(gamepad, _)| {
+        gamepad.get_just_pressed().next().is_some()
+            || gamepad.left_stick().length() > STICK_THRESHOLD
+    }) {
+        InputDevice::Gamepad(GamepadBrand::detect(gamepad, name))
+    } else {
+        return;
+    };
+    r_device.set_if_neq(device);
+}
+
+/// Rebuild prompts when the device changes, or when they are spawned.
+pub(crate) fn update_button_prompts(
+    mut q_prompts: Query<(Ref<ButtonPrompt>, &mut Text)>,
+    r_device: Res<InputDevice>,
+) {
+    for (prompt, mut text) in q_prompts.iter_mut() {
+        if prompt.is_added() || r_device.is_changed() {
+            text.0 = prompt.0(*r_device);
+        }
+    }
+}
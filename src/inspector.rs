@@ -0,0 +1,50 @@
+//! Developer world inspector, only built with the `debug` feature. Toggle with F3.
+use bevy::prelude::*;
+use bevy_inspector_egui::{
+    bevy_egui::EguiPlugin,
+    quick::{AssetInspectorPlugin, ResourceInspectorPlugin, WorldInspectorPlugin},
+};
+
+use crate::{
+    Viewpoint,
+    laser::LaserMaterial,
+    mountains::MountainMaterial,
+    saucer::Saucer,
+    ship::{Facing, PlayerShip, ShipVariant},
+    stars::StarMaterial,
+};
+
+/// Whether the inspector windows are shown.
+#[derive(Resource, Default, Debug)]
+pub struct InspectorVisible(pub bool);
+
+pub struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        let visible = |r_visible: Res<InspectorVisible>| r_visible.0;
+        app.init_resource::<InspectorVisible>()
+            .register_type::<PlayerShip>()
+            .register_type::<ShipVariant>()
+            .register_type::<Facing>()
+            .register_type::<Saucer>()
+            .register_type::<Viewpoint>()
+            .add_plugins((
+                EguiPlugin {
+                    enable_multipass_for_primary_context: true,
+                },
+                WorldInspectorPlugin::new().run_if(visible),
+                ResourceInspectorPlugin::<Viewpoint>::new().run_if(visible),
+                AssetInspectorPlugin::<LaserMaterial>::new().run_if(visible),
+                AssetInspectorPlugin::<StarMaterial>::new().run_if(visible),
+                AssetInspectorPlugin::<MountainMaterial>::new().run_if(visible),
+            ))
+            .add_systems(Update, toggle_inspector);
+    }
+}
+
+fn toggle_inspector(r_keys: Res<ButtonInput<KeyCode>>, mut r_visible: ResMut<InspectorVisible>) {
+    if r_keys.just_pressed(KeyCode::F3) {
+        r_visible.0 = !r_visible.0;
+    }
+}
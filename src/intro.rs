@@ -0,0 +1,231 @@
+//! Intro screen and ship selection menu
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+
+use crate::{
+    game_state::{DespawnOnExit, GameState, RunState},
+    input_glyphs::{ButtonPrompt, InputDevice, PromptButton},
+    menu_input::MenuInput,
+    profile::Profile,
+    ship::ShipVariant,
+    ui_audio::UiSound,
+};
+
+/// Marker component for the root of the intro menu
+#[derive(Component, Default, Debug)]
+pub struct IntroMenu;
+
+/// Menu entry for a selectable ship
+#[derive(Component, Debug)]
+pub struct ShipOption(ShipVariant);
+
+/// Menu entry showing the practice wave selection
+#[derive(Component, Default, Debug)]
+pub struct PracticeOption;
+
+/// Menu entry showing the seed typed by the player
+#[derive(Component, Default, Debug)]
+pub struct SeedOption;
+
+const DIGIT_KEYS: [KeyCode; 10] = [
+    KeyCode::Digit0,
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+];
+
+const TITLE_COLOR: Color = Color::srgb(0.3, 0.6, 1.0);
+const OPTION_COLOR: Color = Color::srgb(0.4, 0.4, 0.5);
+const SELECTED_COLOR: Color = Color::srgb(0.2, 1.0, 0.2);
+
+/// Spawn the intro menu. This is drawn by the overlay camera so that it appears on top of the
+/// playfield.
+pub(crate) fn spawn_intro_menu(mut commands: Commands, r_run: Res<RunState>) {
+    commands.spawn((
+        DespawnOnExit(GameState::Intro),
+        Node {
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(12.0),
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            right: Val::Px(0.0),
+            top: Val::Px(0.0),
+            bottom: Val::Px(0.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        IntroMenu,
+        Children::spawn((
+            Spawn((
+                Text::new("GUARDIAN 2"),
+                TextFont {
+                    font_size: 64.0,
+                    ..default()
+                },
+                TextColor(TITLE_COLOR),
+            )),
+            SpawnIter(ShipVariant::ALL.into_iter().map(|variant| {
+                (
+                    Text::new(variant.stats().name),
+                    TextFont {
+                        font_size: 28.0,
+                        ..default()
+                    },
+                    TextColor(OPTION_COLOR),
+                    ShipOption(variant),
+                )
+            })),
+            Spawn((
+                Text::new(practice_text(r_run.practice)),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(OPTION_COLOR),
+                PracticeOption,
+            )),
+            Spawn((
+                Text::new(seed_text(r_run.seed)),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(OPTION_COLOR),
+                SeedOption,
+            )),
+            Spawn((
+                Text::default(),
+                ButtonPrompt(menu_prompt),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(OPTION_COLOR),
+            )),
+        )),
+    ));
+}
+
+/// Handle ship and practice wave selection, and highlight the chosen ship. The menu can be
+/// driven with either the keyboard or a gamepad, except for typing a seed.
+pub(crate) fn update_intro_menu(
+    mut commands: Commands,
+    mut q_options: Query<(&ShipOption, &mut Text, &mut TextColor)>,
+    mut q_practice: Query<&mut Text, (With<PracticeOption>, Without<SeedOption>)>,
+    mut q_seed: Query<&mut Text, (With<SeedOption>, Without<PracticeOption>)>,
+    r_keys: Res<ButtonInput<KeyCode>>,
+    r_menu: Res<MenuInput>,
+    r_profile: Res<Profile>,
+    mut r_run: ResMut<RunState>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let count = ShipVariant::ALL.len();
+    let index = ShipVariant::ALL
+        .iter()
+        .position(|variant| *variant == r_run.ship)
+        .unwrap_or(0);
+    if r_menu.up || r_keys.just_pressed(KeyCode::KeyW) {
+        r_run.ship = ShipVariant::ALL[(index + count - 1) % count];
+        commands.trigger(UiSound::Hover);
+    } else if r_menu.down || r_keys.just_pressed(KeyCode::KeyS) {
+        r_run.ship = ShipVariant::ALL[(index + 1) % count];
+        commands.trigger(UiSound::Hover);
+    }
+
+    // Practice waves run from "off" through the furthest wave reached.
+    if r_menu.left || r_keys.just_pressed(KeyCode::KeyA) {
+        r_run.practice = match r_run.practice {
+            None | Some(0) => None,
+            Some(wave) => Some(wave - 1),
+        };
+        commands.trigger(UiSound::Hover);
+    } else if r_menu.right || r_keys.just_pressed(KeyCode::KeyD) {
+        r_run.practice = match r_run.practice {
+            None => Some(0),
+            Some(wave) => Some((wave + 1).min(r_profile.furthest_wave)),
+        };
+        commands.trigger(UiSound::Hover);
+    }
+    for mut text in q_practice.iter_mut() {
+        text.0 = practice_text(r_run.practice);
+    }
+
+    // Seed entry: digits are appended, Backspace removes the last one.
+    for (digit, key) in DIGIT_KEYS.iter().enumerate() {
+        if r_keys.just_pressed(*key) {
+            let seed = r_run.seed.unwrap_or(0);
+            if let Some(seed) = seed
+                .checked_mul(10)
+                .and_then(|s| s.checked_add(digit as u64))
+            {
+                r_run.seed = Some(seed);
+                commands.trigger(UiSound::Click);
+            }
+        }
+    }
+    if r_menu.back {
+        r_run.seed = r_run.seed.map(|seed| seed / 10).filter(|seed| *seed > 0);
+        commands.trigger(UiSound::Back);
+    }
+    for mut text in q_seed.iter_mut() {
+        text.0 = seed_text(r_run.seed);
+    }
+
+    if r_menu.confirm {
+        next_state.set(GameState::Playing);
+        commands.trigger(UiSound::Confirm);
+    }
+
+    // The chosen ship is highlighted and marked with arrows.
+    for (option, mut text, mut color) in q_options.iter_mut() {
+        let name = option.0.stats().name;
+        let (label, option_color) = if option.0 == r_run.ship {
+            (format!("> {name} <"), SELECTED_COLOR)
+        } else {
+            (name.to_string(), OPTION_COLOR)
+        };
+        if text.0 != label {
+            text.0 = label;
+        }
+        color.set_if_neq(TextColor(option_color));
+    }
+}
+
+fn menu_prompt(device: InputDevice) -> String {
+    let (back, confirm) = (
+        device.glyph(PromptButton::Back),
+        device.glyph(PromptButton::Confirm),
+    );
+    if device.is_gamepad() {
+        format!(
+            "D-pad up / down to choose a ship, left / right to choose a practice wave, \
+             {back} to clear the seed, {confirm} to start"
+        )
+    } else {
+        format!(
+            "Up / Down to choose a ship, Left / Right to choose a practice wave, \
+             type a seed or {back} to clear it, {confirm} to start"
+        )
+    }
+}
+
+fn practice_text(practice: Option<u32>) -> String {
+    match practice {
+        None => "Practice: off".to_string(),
+        Some(wave) => format!("Practice: wave {}", wave + 1),
+    }
+}
+
+fn seed_text(seed: Option<u64>) -> String {
+    match seed {
+        None => "Seed: random".to_string(),
+        Some(seed) => format!("Seed: {}", seed),
+    }
+}
@@ -1,12 +1,26 @@
 //! Shots from player ship
-use avian2d::prelude::{Collider, CollidingEntities, CollisionLayers, RigidBody};
+use avian2d::prelude::{
+    Collider, CollidingEntities, CollisionLayers, RigidBody, ShapeCastConfig, SpatialQuery,
+    SpatialQueryFilter,
+};
 use bevy::{
     pbr::{ExtendedMaterial, MaterialExtension},
+    platform::collections::HashSet,
     prelude::*,
     render::render_resource::{AsBindGroup, ShaderRef},
 };
+use rand::Rng;
 
-use crate::{ENEMY_LAYER, EnemyHit, FX_DEPTH, PLAYER_SHOT_LAYER, UnitPosition, ship::Facing};
+use crate::{
+    ENEMY_LAYER, EnemyHit, FX_DEPTH, PLAYER_SHOT_LAYER, Playfield, RandomGenerator, SeamGhostOf,
+    SeamMirrored, ShotLanded, UnitPosition, Viewpoint,
+    explosion::{DUST_COLOR, DustEffect, SparkEffect},
+    game_state::{DespawnOnExit, GameState},
+    seam_source,
+    ship::Facing,
+    terrain::Terrain,
+    tuning::Tuning,
+};
 
 /// * Abductor is destroyed, and treasure is rescued (absorbed) by player ship.
 #[derive(Component, Default, Debug)]
@@ -19,115 +33,383 @@ pub struct LaserShot {
 
     /// Horizontal velocity
     speed: f32,
+
+    /// Horizontal distance moved during the most recent frame
+    travel: f32,
+
+    /// Number of additional enemies this shot can pass through
+    pierce: u32,
+
+    /// Damage dealt to the next enemy hit
+    damage: f32,
+
+    /// Enemies already hit, so that we don't hit them again while passing through
+    hits: HashSet<Entity>,
+
+    /// Time until the next spark, while skimming the ground
+    spark_timer: f32,
+}
+
+impl LaserShot {
+    /// Horizontal velocity of the shot.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+}
+
+/// Marker for shots fired from the ship's own guns. Only these count towards accuracy; shots from
+/// drones and deflected enemy fire are not reported as fired, so they don't report landing either.
+#[derive(Component, Default, Debug)]
+pub struct GunShot;
+
+/// A shot fired at an angle, which bounces once off the top or bottom of the playfield.
+#[derive(Component, Default, Debug)]
+pub struct Ricochet {
+    /// Vertical velocity
+    climb: f32,
+
+    /// Whether the shot has already bounced
+    bounced: bool,
+}
+
+impl Ricochet {
+    pub fn new(climb: f32) -> Self {
+        Self {
+            climb,
+            bounced: false,
+        }
+    }
 }
 
+/// Laser beam mesh, and materials for each facing. The beam is brightest at its leading end,
+/// so right- and left-going shots need separate materials.
 #[derive(Resource, Default, Debug)]
 pub struct ShotMesh {
     mesh: Handle<Mesh>,
-    material: Handle<LaserMaterial>,
-    hue: f32,
+    material: [Handle<LaserMaterial>; 2],
+
+    /// Brighter, faster-cycling beam used for piercing shots
+    pierce_material: [Handle<LaserMaterial>; 2],
+}
+
+impl ShotMesh {
+    fn material(&self, facing: Facing, pierce: bool) -> Handle<LaserMaterial> {
+        let materials = if pierce {
+            &self.pierce_material
+        } else {
+            &self.material
+        };
+        materials[facing_index(facing)].clone()
+    }
 }
 
+fn facing_index(facing: Facing) -> usize {
+    match facing {
+        Facing::Right => 0,
+        Facing::Left => 1,
+    }
+}
+
+fn facing_sign(facing: Facing) -> f32 {
+    match facing {
+        Facing::Right => 1.0,
+        Facing::Left => -1.0,
+    }
+}
+
+/// Rate at which the hue of laser shots cycles, in degrees per second.
+const LASER_HUE_RATE: f32 = 360.0;
+
+/// Fraction of damage retained each time a piercing shot passes through an enemy.
+const PIERCE_FALLOFF: f32 = 0.7;
+
+/// Shots flying lower than this above the ground throw up sparks beneath them.
+const SKIM_HEIGHT: f32 = 0.06;
+
+/// Time between sparks from a shot skimming right along the ground, in seconds. Higher shots
+/// spark less often.
+const SKIM_SPARK_INTERVAL: f32 = 0.02;
+
 pub(crate) fn setup_laser(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<LaserMaterial>>,
     mut shot_mesh: ResMut<ShotMesh>,
 ) {
     shot_mesh.mesh = meshes.add(Rectangle::from_size(Vec2::new(1.0, 0.007)));
-    shot_mesh.material = materials.add(LaserMaterial {
-        base: StandardMaterial {
-            unlit: true,
-            alpha_mode: AlphaMode::Blend,
-            ..default()
-        },
-        extension: LaserMaterialExt {
-            color: LinearRgba::from(Color::srgb(0.0, 1.0, 0.0)).to_vec4(),
-        },
-    });
+    for facing in [Facing::Right, Facing::Left] {
+        let index = facing_index(facing);
+        let direction = facing_sign(facing);
+        shot_mesh.material[index] = materials.add(LaserMaterial {
+            base: StandardMaterial {
+                unlit: true,
+                alpha_mode: AlphaMode::Blend,
+                ..default()
+            },
+            extension: LaserMaterialExt {
+                color: LinearRgba::from(Hsla::new(0.0, 1.0, 0.5, 1.0)).to_vec4(),
+                params: Vec4::new(LASER_HUE_RATE, direction, 0.0, 0.0),
+            },
+        });
+        shot_mesh.pierce_material[index] = materials.add(LaserMaterial {
+            base: StandardMaterial {
+                unlit: true,
+                alpha_mode: AlphaMode::Add,
+                ..default()
+            },
+            extension: LaserMaterialExt {
+                color: LinearRgba::from(Hsla::new(0.0, 1.0, 0.8, 1.0)).to_vec4() * 2.0,
+                params: Vec4::new(LASER_HUE_RATE * 3.0, direction, 0.0, 0.0),
+            },
+        });
+    }
 }
 
+/// Spawn a laser shot. The `mount` offset is relative to the ship position when facing right,
+/// and is mirrored when facing left. Shots with a nonzero `pierce` pass through that many
+/// enemies before being destroyed. A full-strength shot deals 1.0 `damage`.
+///
+/// Returns the shot entity.
 pub(crate) fn spawn_laser(
     commands: &mut Commands,
     position: Vec2,
+    mount: Vec2,
     facing: Facing,
-    shot_mesh: Res<ShotMesh>,
-) {
-    commands.spawn((
-        LaserShot {
-            expiration: 0.3,
-            speed: match facing {
-                Facing::Right => 3.0,
-                Facing::Left => -3.0,
-            },
-            size: 0.2,
-        },
-        RigidBody::Kinematic,
-        Collider::capsule_endpoints(0.003, Vec2::new(-0.5, 0.), Vec2::new(0.5, 0.)),
-        CollisionLayers::from_bits(PLAYER_SHOT_LAYER, ENEMY_LAYER),
-        CollidingEntities::default(),
-        UnitPosition(Vec2::new(
-            match facing {
-                Facing::Right => position.x + 0.18,
-                Facing::Left => position.x - 0.18,
+    pierce: u32,
+    damage: f32,
+    shot_mesh: &ShotMesh,
+    tuning: &Tuning,
+) -> Entity {
+    commands
+        .spawn((
+            LaserShot {
+                expiration: tuning.laser.lifetime,
+                speed: match facing {
+                    Facing::Right => tuning.laser.speed,
+                    Facing::Left => -tuning.laser.speed,
+                },
+                size: 0.2,
+                travel: 0.0,
+                pierce,
+                damage,
+                hits: HashSet::new(),
+                spark_timer: 0.0,
             },
-            position.y,
-        )),
-        Mesh3d(shot_mesh.mesh.clone()),
-        MeshMaterial3d(shot_mesh.material.clone()),
-        Transform::from_xyz(0., 0., FX_DEPTH).with_scale(Vec3::new(0.2, 1.0, 1.0)),
-    ));
+            RigidBody::Kinematic,
+            Collider::capsule_endpoints(0.003, Vec2::new(-0.5, 0.), Vec2::new(0.5, 0.)),
+            CollisionLayers::from_bits(PLAYER_SHOT_LAYER, ENEMY_LAYER),
+            CollidingEntities::default(),
+            UnitPosition(Vec2::new(
+                match facing {
+                    Facing::Right => position.x + mount.x,
+                    Facing::Left => position.x - mount.x,
+                },
+                position.y + mount.y,
+            )),
+            Mesh3d(shot_mesh.mesh.clone()),
+            MeshMaterial3d(shot_mesh.material(facing, pierce > 0)),
+            Transform::from_xyz(0., 0., FX_DEPTH).with_scale(Vec3::new(0.2, 1.0, 1.0)),
+            SeamMirrored,
+            DespawnOnExit(GameState::Playing),
+        ))
+        .id()
 }
 
 /// Laser animations:
 /// * Overall velocity
 /// * Expansion
-/// * Color rotation
 /// * Expiration
+///
+/// Color rotation is done in the shader.
 pub(crate) fn update_laser(
     mut commands: Commands,
     mut q_shots: Query<(Entity, &mut LaserShot, &mut UnitPosition, &mut Transform)>,
-    mut materials: ResMut<Assets<LaserMaterial>>,
     r_time: Res<Time>,
-    mut shot_mesh: ResMut<ShotMesh>,
 ) {
-    // Rotate shot color
-    if let Some(material) = materials.get_mut(shot_mesh.material.id()) {
-        shot_mesh.hue = (shot_mesh.hue + r_time.delta_secs() * 360.0).rem_euclid(360.0);
-        material.extension.color =
-            LinearRgba::from(Hsla::new(shot_mesh.hue, 1.0, 0.5, 1.0)).to_vec4()
-    }
-
     for (ent, mut shot, mut position, mut transform) in q_shots.iter_mut() {
         shot.expiration -= r_time.delta_secs();
         if shot.expiration <= 0. {
             commands.entity(ent).despawn();
         } else {
-            position.0.x += shot.speed * r_time.delta_secs();
-            // Update
+            shot.travel = shot.speed * r_time.delta_secs();
+            position.0.x += shot.travel;
         }
         shot.size += r_time.delta_secs();
         transform.scale.x = shot.size;
     }
 }
 
+/// Ricochet shots climb or dive, bouncing once off the ceiling or floor of the playfield. The
+/// beam is tilted to match its direction of travel, without flipping it end for end.
+pub(crate) fn update_ricochet(
+    mut q_shots: Query<(&LaserShot, &mut Ricochet, &mut UnitPosition, &mut Transform)>,
+    r_playfield: Res<Playfield>,
+    r_time: Res<Time>,
+) {
+    for (shot, mut ricochet, mut position, mut transform) in q_shots.iter_mut() {
+        position.0.y += ricochet.climb * r_time.delta_secs();
+        let out_of_bounds = (ricochet.climb > 0.0 && position.0.y > r_playfield.ceiling)
+            || (ricochet.climb < 0.0 && position.0.y < r_playfield.floor);
+        if out_of_bounds && !ricochet.bounced {
+            position.0.y = position.0.y.clamp(r_playfield.floor, r_playfield.ceiling);
+            ricochet.climb = -ricochet.climb;
+            ricochet.bounced = true;
+        }
+        transform.rotation = Quat::from_rotation_z((ricochet.climb / shot.speed).atan());
+    }
+}
+
+/// Maximum number of enemies a single shot can sweep through in one frame.
+const MAX_SWEEP_HITS: u32 = 8;
+
+/// Apply shot damage to enemies. A shot is normally destroyed by the first enemy it hits;
+/// piercing shots continue on, dealing less damage to each successive enemy.
+///
+/// Shots are fast and thin, so at low frame rates they can jump clean over an enemy between
+/// physics steps. In addition to the contacts reported by the physics engine, the shot's shape
+/// is swept back along the distance it moved this frame, and anything it passed through is hit.
 pub(crate) fn detect_enemy_kills(
     mut commands: Commands,
-    q_enemies: Query<(Entity, &CollidingEntities), With<LaserShot>>,
+    mut q_shots: Query<(
+        Entity,
+        &mut LaserShot,
+        &UnitPosition,
+        &CollidingEntities,
+        Has<GunShot>,
+    )>,
+    q_ghosts: Query<&SeamGhostOf>,
+    spatial_query: SpatialQuery,
+    r_viewpoint: Res<Viewpoint>,
+    r_playfield: Res<Playfield>,
 ) {
-    for (entity, collisions) in q_enemies {
-        if !collisions.is_empty() {
-            commands.entity(entity).despawn();
+    let filter = SpatialQueryFilter::from_mask(ENEMY_LAYER);
+    for (entity, mut shot, position, collisions, gun_shot) in q_shots.iter_mut() {
+        let mut new_hits: Vec<Entity> = collisions.iter().copied().collect();
+        if let Ok(direction) = Dir2::new(Vec2::X * shot.travel) {
+            // Sweep from where the shot was at the start of the frame, in the same
+            // viewpoint-relative coordinates that the colliders use.
+            let origin = Vec2::new(
+                r_playfield.relative(position.0.x - shot.travel, r_viewpoint.position),
+                position.0.y,
+            );
+            let half_length = shot.size * 0.5;
+            let shape = Collider::capsule_endpoints(
+                0.003,
+                Vec2::new(-half_length, 0.),
+                Vec2::new(half_length, 0.),
+            );
+            let sweep = spatial_query.shape_hits(
+                &shape,
+                origin,
+                0.0,
+                direction,
+                MAX_SWEEP_HITS,
+                &ShapeCastConfig::from_max_distance(shot.travel.abs()),
+                &filter,
+            );
+            new_hits.extend(sweep.iter().map(|hit| hit.entity));
         }
-        collisions.iter().for_each(|enemy| {
-            commands.entity(*enemy).trigger(EnemyHit);
-        });
+        // Enemies near the wrap seam may be hit via their ghost copy on the other side.
+        for enemy in new_hits.iter_mut() {
+            *enemy = seam_source(&q_ghosts, *enemy);
+        }
+        new_hits.retain(|enemy| !shot.hits.contains(enemy));
+        if new_hits.is_empty() {
+            continue;
+        }
+        if gun_shot && shot.hits.is_empty() {
+            commands.trigger(ShotLanded);
+        }
+
+        // Sort so that hits are applied in a consistent order when several happen at once.
+        new_hits.sort();
+        new_hits.dedup();
+        for enemy in new_hits {
+            commands.entity(enemy).trigger(EnemyHit {
+                damage: shot.damage,
+            });
+            shot.hits.insert(enemy);
+            if shot.pierce == 0 {
+                commands.entity(entity).despawn();
+                break;
+            }
+            shot.pierce -= 1;
+            shot.damage *= PIERCE_FALLOFF;
+        }
+    }
+}
+
+/// Shots which fly into the ground are stopped there, kicking up a puff of dust. Only the leading
+/// tip of the shot is tested, since shots travel horizontally.
+pub(crate) fn detect_ground_impacts(
+    mut commands: Commands,
+    q_shots: Query<(Entity, &LaserShot, &UnitPosition)>,
+    r_terrain: Res<Terrain>,
+    r_playfield: Res<Playfield>,
+) {
+    for (entity, shot, position) in q_shots.iter() {
+        let tip = Vec2::new(
+            r_playfield.wrap(position.0.x + shot.speed.signum() * shot.size * 0.5),
+            position.0.y,
+        );
+        if !r_terrain.is_below(tip) {
+            continue;
+        }
+        commands.entity(entity).despawn();
+        commands.spawn((
+            DustEffect {
+                size: 0.02,
+                color: DUST_COLOR,
+            },
+            UnitPosition(Vec2::new(tip.x, r_terrain.height_at(tip.x))),
+        ));
+    }
+}
+
+/// Shots which skim low over the ground throw up sparks from the ground beneath them. This is
+/// purely cosmetic.
+pub(crate) fn emit_ground_sparks(
+    mut commands: Commands,
+    mut q_shots: Query<(&mut LaserShot, &UnitPosition)>,
+    mut r_rng: ResMut<RandomGenerator>,
+    r_terrain: Res<Terrain>,
+    r_playfield: Res<Playfield>,
+    r_time: Res<Time>,
+) {
+    for (mut shot, position) in q_shots.iter_mut() {
+        let height = position.0.y - r_terrain.height_at(position.0.x);
+        if height > SKIM_HEIGHT {
+            continue;
+        }
+        shot.spark_timer -= r_time.delta_secs();
+        if shot.spark_timer > 0.0 {
+            continue;
+        }
+        let closeness = 1.0 - height / SKIM_HEIGHT;
+        shot.spark_timer = SKIM_SPARK_INTERVAL / closeness.max(0.1);
+
+        // Sparks come off the ground somewhere under the shot, and are flung along with it.
+        let x = r_playfield.wrap(position.0.x + r_rng.0.random_range(-0.5..0.5) * shot.size);
+        commands.spawn((
+            SparkEffect {
+                velocity: Vec2::new(
+                    shot.speed * r_rng.0.random_range(0.02..0.08),
+                    r_rng.0.random_range(0.05..0.25) * closeness,
+                ),
+            },
+            UnitPosition(Vec2::new(x, r_terrain.height_at(x))),
+        ));
     }
 }
 
-#[derive(AsBindGroup, Asset, TypePath, Debug, Clone)]
+#[derive(AsBindGroup, Asset, Reflect, Debug, Clone)]
 pub(crate) struct LaserMaterialExt {
+    /// Base color of the beam
     #[uniform(100)]
     pub(crate) color: Vec4,
+    /// x: hue cycle rate, in degrees per second; y: direction of travel, 1 for right or -1 for
+    /// left
+    #[uniform(101)]
+    pub(crate) params: Vec4,
 }
 
 impl MaterialExtension for LaserMaterialExt {
@@ -0,0 +1,156 @@
+//! Laser heat: with auto-fire enabled, every shot heats the guns. If they overheat, the ship
+//! can't fire until they have vented and cooled down. This keeps auto-fire from trivializing
+//! waves, and can be turned off in the assist options.
+use bevy::prelude::*;
+
+use crate::{
+    UnitPosition,
+    explosion::DustEffect,
+    game_state::{DespawnOnExit, GameState},
+    settings::Settings,
+    ship::PlayerShip,
+};
+
+/// Heat built up in the player ship's guns.
+#[derive(Component, Default, Debug)]
+pub struct LaserHeat {
+    /// Gun heat, from 0 (cold) to 1 (overheated)
+    heat: f32,
+
+    /// Set when the guns overheat, and cleared once they have cooled down
+    overheated: bool,
+
+    /// Time until the next puff of vented steam
+    vent_timer: f32,
+}
+
+impl LaserHeat {
+    /// Check whether the guns can fire, and if so, add the heat of a volley. Always succeeds
+    /// when laser heat is disabled.
+    pub fn try_fire(&mut self, settings: &Settings) -> bool {
+        if !settings.assist.laser_heat_enabled() {
+            return true;
+        }
+        if self.overheated {
+            return false;
+        }
+        self.heat = (self.heat + HEAT_PER_VOLLEY).min(1.0);
+        if self.heat >= 1.0 {
+            self.overheated = true;
+        }
+        true
+    }
+}
+
+/// Marker for the fill bar of the laser heat gauge
+#[derive(Component, Default, Debug)]
+pub struct LaserHeatGauge;
+
+/// Marker for the frame of the laser heat gauge, which is hidden when laser heat is disabled
+#[derive(Component, Default, Debug)]
+pub struct LaserHeatGaugeFrame;
+
+/// Heat added by each volley
+const HEAT_PER_VOLLEY: f32 = 0.06;
+
+/// Heat lost per second
+const COOL_RATE: f32 = 0.3;
+
+/// Overheated guns can fire again once they have cooled below this level.
+const RECOVERED_HEAT: f32 = 0.3;
+
+/// Time between puffs of steam while venting, in seconds
+const VENT_INTERVAL: f32 = 0.08;
+
+const VENT_COLOR: Color = Color::srgb(0.8, 0.85, 0.9);
+const GAUGE_COLOR: Color = Color::srgb(0.2, 0.8, 1.0);
+const OVERHEAT_COLOR: Color = Color::srgb(1.0, 0.1, 0.1);
+
+/// Cool the guns down. While overheated, the ship vents steam.
+pub(crate) fn update_laser_heat(
+    mut commands: Commands,
+    mut q_player: Query<(&mut LaserHeat, &UnitPosition), With<PlayerShip>>,
+    r_time: Res<Time>,
+) {
+    let dt = r_time.delta_secs();
+    for (mut laser_heat, position) in q_player.iter_mut() {
+        laser_heat.heat = (laser_heat.heat - COOL_RATE * dt).max(0.0);
+        if laser_heat.heat < RECOVERED_HEAT {
+            laser_heat.overheated = false;
+        }
+        if !laser_heat.overheated {
+            continue;
+        }
+        laser_heat.vent_timer -= dt;
+        if laser_heat.vent_timer <= 0.0 {
+            laser_heat.vent_timer = VENT_INTERVAL;
+            commands.spawn((
+                DustEffect {
+                    size: 0.015,
+                    color: VENT_COLOR,
+                },
+                UnitPosition(position.0),
+            ));
+        }
+    }
+}
+
+pub(crate) fn spawn_laser_heat_gauge(mut commands: Commands, r_settings: Res<Settings>) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(12.0),
+            bottom: Val::Px(42.0),
+            width: Val::Px(120.0),
+            height: Val::Px(6.0),
+            border: UiRect::all(Val::Px(1.0)),
+            ..default()
+        },
+        BorderColor(Color::srgb(0.5, 0.5, 0.6)),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+        if r_settings.assist.laser_heat_enabled() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        },
+        LaserHeatGaugeFrame,
+        DespawnOnExit(GameState::Playing),
+        children![(
+            Node {
+                width: Val::Percent(0.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(GAUGE_COLOR),
+            LaserHeatGauge,
+        )],
+    ));
+}
+
+/// Fill the gauge according to the gun heat. It turns red while the guns are overheated, and is
+/// only shown while laser heat is enabled.
+pub(crate) fn update_laser_heat_gauge(
+    q_player: Query<&LaserHeat, With<PlayerShip>>,
+    mut q_gauge: Query<(&mut Node, &mut BackgroundColor), With<LaserHeatGauge>>,
+    mut q_frame: Query<&mut Visibility, With<LaserHeatGaugeFrame>>,
+    r_settings: Res<Settings>,
+) {
+    for mut visibility in q_frame.iter_mut() {
+        visibility.set_if_neq(if r_settings.assist.laser_heat_enabled() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+    let Ok(laser_heat) = q_player.single() else {
+        return;
+    };
+    for (mut node, mut color) in q_gauge.iter_mut() {
+        node.width = Val::Percent(laser_heat.heat * 100.0);
+        color.0 = if laser_heat.overheated {
+            OVERHEAT_COLOR
+        } else {
+            GAUGE_COLOR
+        };
+    }
+}
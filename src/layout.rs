@@ -0,0 +1,63 @@
+//! Level layouts: optional hand-crafted placement of treasures for specific levels, such as the
+//! early waves and the tutorial. Levels without a layout use random placement. A layout can
+//! also list mutators which change the rules for that wave.
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{game_state::Level, treasure::TreasureKind, wave::WaveMutator};
+
+/// Layout files, indexed by level number.
+const LAYOUTS: &[&str] = &["levels/level1.layout.ron", "levels/level2.layout.ron"];
+
+/// Hand-crafted layout for a level.
+#[derive(Asset, TypePath, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct LevelLayout {
+    /// Treasures placed at fixed positions
+    pub treasures: Vec<TreasurePlacement>,
+
+    /// Number of additional treasures placed at random
+    pub random_treasures: usize,
+
+    /// Rule changes for this wave
+    pub mutators: Vec<WaveMutator>,
+}
+
+/// Placement of a single treasure.
+#[derive(Deserialize, Debug, Clone)]
+pub struct TreasurePlacement {
+    /// Horizontal position, as a fraction of the playfield width
+    pub x: f32,
+
+    /// Type of treasure; chosen at random if omitted
+    #[serde(default)]
+    pub kind: Option<TreasureKind>,
+}
+
+/// Handles to the level layouts.
+#[derive(Resource, Default, Debug)]
+pub struct LevelLayouts(Vec<Handle<LevelLayout>>);
+
+impl LevelLayouts {
+    /// Return the layout for the current level, if it has one and it has loaded. Layouts are
+    /// reused for the same wave in later loops.
+    pub fn current<'a>(
+        &self,
+        level: &Level,
+        layouts: &'a Assets<LevelLayout>,
+    ) -> Option<&'a LevelLayout> {
+        self.0
+            .get(level.wave() as usize)
+            .and_then(|handle| layouts.get(handle))
+    }
+}
+
+pub(crate) fn load_level_layouts(
+    asset_server: Res<AssetServer>,
+    mut r_layouts: ResMut<LevelLayouts>,
+) {
+    r_layouts.0 = LAYOUTS
+        .iter()
+        .map(|path| asset_server.load(*path))
+        .collect();
+}
@@ -0,0 +1,130 @@
+//! Formation leaders: elite saucers which inspire nearby saucers to fly faster and fire more
+//! often while they live. Each leader is surrounded by a pulsing aura ring showing its reach,
+//! and stands out on the minimap, so the player can decide whether to take it out first.
+use bevy::prelude::*;
+
+use crate::{
+    FX_DEPTH, Playfield, SeamMirrored, UnitPosition,
+    game_state::{DespawnOnExit, GameState},
+    saucer::Saucer,
+};
+
+/// An elite saucer which buffs the saucers around it
+#[derive(Component, Default, Debug)]
+pub struct FormationLeader;
+
+/// A saucer within the aura of a formation leader
+#[derive(Component, Default, Debug)]
+pub struct Inspired;
+
+/// Aura ring drawn around a formation leader
+#[derive(Component, Debug)]
+pub struct LeaderAura {
+    leader: Entity,
+}
+
+/// Shared mesh and material for aura rings
+#[derive(Resource, Default, Debug)]
+pub struct LeaderAuraAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+/// Number of saucers in each wave which are formation leaders
+pub const FORMATION_LEADERS: usize = 2;
+
+/// Hit points of a formation leader; regular saucers have one.
+pub const LEADER_HEALTH: f32 = 3.0;
+
+/// Saucers within this distance of a leader are inspired.
+const AURA_RADIUS: f32 = 0.3;
+
+/// Flying speed multiplier for inspired saucers
+pub const INSPIRED_SPEED: f32 = 1.4;
+
+/// Fire rate multiplier for inspired saucers
+pub const INSPIRED_FIRE_RATE: f32 = 1.75;
+
+/// Pulse rate of the aura ring, in cycles per second
+const AURA_PULSE_RATE: f32 = 1.5;
+
+pub(crate) fn setup_leader_auras(
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut r_assets: ResMut<LeaderAuraAssets>,
+) {
+    r_assets.mesh = meshes.add(Rectangle::from_length(AURA_RADIUS * 2.0));
+    r_assets.material = materials.add(StandardMaterial {
+        base_color: Color::srgba(1.0, 0.7, 0.2, 0.5),
+        base_color_texture: Some(asset_server.load("textures/ring.png")),
+        alpha_mode: AlphaMode::Add,
+        unlit: true,
+        ..default()
+    });
+}
+
+/// Give each new leader its aura ring.
+pub(crate) fn on_add_formation_leader(
+    trigger: Trigger<OnAdd, FormationLeader>,
+    mut commands: Commands,
+    q_positions: Query<&UnitPosition>,
+    r_assets: Res<LeaderAuraAssets>,
+) {
+    let position = q_positions
+        .get(trigger.target())
+        .map_or(Vec2::ZERO, |position| position.0);
+    commands.spawn((
+        LeaderAura {
+            leader: trigger.target(),
+        },
+        UnitPosition(position),
+        Mesh3d(r_assets.mesh.clone()),
+        MeshMaterial3d(r_assets.material.clone()),
+        Transform::from_xyz(0., 0., FX_DEPTH),
+        SeamMirrored,
+        DespawnOnExit(GameState::Playing),
+    ));
+}
+
+/// Inspire the saucers within reach of a leader. Leaders don't inspire each other.
+pub(crate) fn update_leader_auras(
+    mut commands: Commands,
+    q_leaders: Query<&UnitPosition, With<FormationLeader>>,
+    q_saucers: Query<
+        (Entity, &UnitPosition, Has<Inspired>),
+        (With<Saucer>, Without<FormationLeader>),
+    >,
+    r_playfield: Res<Playfield>,
+) {
+    for (entity, position, inspired) in q_saucers.iter() {
+        let in_range = q_leaders
+            .iter()
+            .any(|leader| r_playfield.distance(leader.0, position.0) < AURA_RADIUS);
+        if in_range && !inspired {
+            commands.entity(entity).insert(Inspired);
+        } else if !in_range && inspired {
+            commands.entity(entity).remove::<Inspired>();
+        }
+    }
+}
+
+/// Keep each aura ring centered on its leader, gently pulsing. The ring goes when the leader
+/// does.
+pub(crate) fn update_aura_rings(
+    mut commands: Commands,
+    mut q_auras: Query<(Entity, &LeaderAura, &mut UnitPosition, &mut Transform)>,
+    q_leaders: Query<&UnitPosition, (With<FormationLeader>, Without<LeaderAura>)>,
+    r_time: Res<Time>,
+) {
+    let pulse =
+        1.0 + 0.08 * (r_time.elapsed_secs() * AURA_PULSE_RATE * std::f32::consts::TAU).sin();
+    for (entity, aura, mut position, mut transform) in q_auras.iter_mut() {
+        let Ok(leader) = q_leaders.get(aura.leader) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        position.0 = leader.0;
+        transform.scale = Vec3::splat(pulse);
+    }
+}
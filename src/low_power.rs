@@ -0,0 +1,80 @@
+//! Low-power mode, for laptop and handheld play. Background effects and idle model animations
+//! are updated less often, and the frame rate is capped while the game is paused or the window
+//! is unfocused.
+use bevy::prelude::*;
+
+use crate::settings::Settings;
+
+/// Clock for background effects. In low-power mode it only ticks a few times a second, and
+/// reports the time accumulated since the last tick; otherwise it ticks every frame.
+#[derive(Resource, Default, Debug)]
+pub struct BackgroundClock {
+    /// Time accumulated since the last tick
+    pending: f32,
+
+    /// Time covered by the current tick, or zero if the clock didn't tick this frame
+    delta: f32,
+}
+
+impl BackgroundClock {
+    /// Time since the previous tick, in seconds
+    pub fn delta_secs(&self) -> f32 {
+        self.delta
+    }
+}
+
+/// Update rate of background effects in low-power mode, in updates per second
+const BACKGROUND_RATE: f32 = 15.0;
+
+/// Frame rate cap while paused or unfocused in low-power mode
+pub const IDLE_FPS: f32 = 20.0;
+
+pub(crate) fn tick_background_clock(
+    mut r_clock: ResMut<BackgroundClock>,
+    r_settings: Res<Settings>,
+    r_time: Res<Time>,
+) {
+    r_clock.pending += r_time.delta_secs();
+    if r_settings.graphics.low_power && r_clock.pending < 1.0 / BACKGROUND_RATE {
+        r_clock.delta = 0.0;
+    } else {
+        r_clock.delta = r_clock.pending;
+        r_clock.pending = 0.0;
+    }
+}
+
+/// Run condition for background effect systems, which should use [`BackgroundClock`] for their
+/// timing.
+pub(crate) fn background_tick(r_clock: Res<BackgroundClock>) -> bool {
+    r_clock.delta > 0.0
+}
+
+/// Advance model animations, such as the spinning saucer rings, only when the background clock
+/// ticks. In between they are paused, and on a tick they catch up on the time they skipped.
+pub(crate) fn throttle_animations(
+    mut q_players: Query<&mut AnimationPlayer>,
+    r_clock: Res<BackgroundClock>,
+    r_time: Res<Time>,
+) {
+    let tick = r_clock.delta > 0.0;
+    let skipped = (r_clock.delta - r_time.delta_secs()).max(0.0);
+    for mut player in q_players.iter_mut() {
+        let up_to_date = if tick {
+            skipped == 0.0 && !player.all_paused()
+        } else {
+            player.all_paused()
+        };
+        if up_to_date {
+            continue;
+        }
+        for (_, animation) in player.playing_animations_mut() {
+            if !tick {
+                animation.pause();
+                continue;
+            }
+            animation.resume();
+            let seek_time = animation.seek_time() + skipped * animation.speed();
+            animation.set_seek_time(seek_time);
+        }
+    }
+}
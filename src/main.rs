@@ -1,35 +1,250 @@
-use avian2d::{PhysicsPlugins, prelude::Gravity};
-use bevy::{asset::embedded_asset, prelude::*};
+use avian2d::{
+    PhysicsPlugins,
+    prelude::{
+        Collider, ColliderDisabled, CollisionLayers, Gravity, PhysicsDebugPlugin, RigidBody, Sensor,
+    },
+};
+use bevy::{
+    asset::embedded_asset, gltf::Gltf, input::InputSystem, prelude::*,
+    state::state::StateTransitionSteps,
+};
 use bevy_enhanced_input::prelude::*;
-use game_state::{GameState, PauseState};
+use game_state::{
+    Difficulty, GameState, Level, PauseState, RunState, advance_level, despawn_on_exit,
+    start_run_level,
+};
 use mountains::spawn_mountains;
 use rand::SeedableRng;
 use rand_chacha::ChaCha8Rng;
-use stars::{spawn_stars, update_stars};
+use serde::{Deserialize, Serialize};
+use stars::{StarMaterial, spawn_stars, update_stars};
 
 use crate::{
+    adaptive::{
+        AdaptiveDifficulty, adaptive_on_ship_lost, adaptive_on_shot_fired, adaptive_on_shot_landed,
+        start_run_adaptive, update_adaptive_difficulty,
+    },
+    afterburner::{spawn_heat_gauge, update_afterburner, update_heat_gauge},
+    ai::{AiDebug, apply_ai_transitions, draw_ai_debug, toggle_ai_debug},
+    alerts::{
+        on_abduction_started, spawn_shield_alert, update_abduction_alert, update_distress_calls,
+        update_shield_alert,
+    },
+    announcer::{KillStreak, announce_streak, on_enemy_destroyed_streak, update_kill_streak},
+    asset_watchdog::{
+        PlaceholderAssets, remove_failed_sounds, report_failed_assets, setup_placeholders,
+        substitute_failed_images, substitute_failed_scenes,
+    },
+    autosave::{Autosave, autosave},
+    barrel_roll::{spawn_roll_gauge, update_barrel_roll, update_roll_gauge},
+    bomb::{BombMesh, setup_bombs, update_bombs},
+    boss::{BossAssets, fire_boss_turrets, setup_boss, spawn_boss, update_boss},
+    continues::{Continues, spawn_continue_prompt, start_run_continues, update_continue_prompt},
+    damage::{
+        detect_player_hits, on_add_invulnerable, on_remove_invulnerable, update_invulnerability,
+    },
+    debris::{DebrisPool, clear_debris, on_add_shrapnel_debris, setup_debris, update_debris},
+    drone::{on_drone_pickup, update_drones},
+    enemy_shot::{
+        DeflectWindow, EnemyShotAssets, arm_queens, deflect_enemy_shots, fire_enemy_shots,
+        setup_enemy_shots, update_enemy_shots,
+    },
     explosion::{
-        ExplosionHandles, on_add_flare, on_add_shrapnel, setup_explosions, update_flare,
-        update_shrapnel,
+        ExplosionHandles, ShrapnelMaterial, on_add_dust, on_add_flare, on_add_shrapnel,
+        on_add_spark, on_remove_dust, on_remove_flare, on_remove_shrapnel, setup_explosions,
+        update_dust, update_flare, update_flare_lights, update_shrapnel, update_sparks,
+    },
+    frame_limiter::{apply_present_mode, limit_frame_rate},
+    heat_haze::{HeatHazeMaterial, on_add_player_ship, update_heat_haze},
+    high_scores::{load_high_scores, record_high_score, track_assists},
+    hit_feedback::{HitFlash, on_player_hit_flash, spawn_hit_feedback, update_hit_feedback},
+    hud::{adjust_hud_settings, apply_hud_settings},
+    hurtbox::{HurtboxDebug, apply_hurtbox_scale, draw_hurtbox, toggle_hurtbox_debug},
+    input_glyphs::{InputDevice, detect_input_device, update_button_prompts},
+    intro::{spawn_intro_menu, update_intro_menu},
+    laser::{
+        LaserMaterial, ShotMesh, detect_enemy_kills, detect_ground_impacts, emit_ground_sparks,
+        setup_laser, update_laser, update_ricochet,
+    },
+    laser_heat::{spawn_laser_heat_gauge, update_laser_heat, update_laser_heat_gauge},
+    layout::{LevelLayout, LevelLayouts, load_level_layouts},
+    leader::{
+        LeaderAuraAssets, on_add_formation_leader, setup_leader_auras, update_aura_rings,
+        update_leader_auras,
+    },
+    low_power::{BackgroundClock, background_tick, throttle_animations, tick_background_clock},
+    menu_input::{MenuInput, read_menu_input},
+    minimap::{
+        on_add_enemy_blip, on_add_ship_blip, on_add_treasure_blip, spawn_minimap_window,
+        update_abduction_progress, update_minimap,
     },
-    laser::{LaserMaterial, ShotMesh, detect_enemy_kills, setup_laser, update_laser},
     mountains::{MountainMaterial, update_mountains},
-    saucer::{animate_saucers, spawn_saucer},
-    ship::{move_ship, spawn_ship},
-    treasure::spawn_treasure,
+    muzzle_flash::{
+        MuzzleFlashAssets, on_add_muzzle_flash, setup_muzzle_flash, update_muzzle_flash,
+    },
+    nebula::{NebulaLightning, spawn_nebula_lightning, update_nebula_lightning},
+    parachute::{ParachuteMesh, on_remove_parachute, setup_parachutes, update_parachutes},
+    pause::{
+        ClockHold, PauseFocus, spawn_pause_menu, sync_game_clock, toggle_pause, update_pause_menu,
+    },
+    photo_mode::{enter_photo_mode, exit_photo_mode, toggle_photo_mode, update_photo_camera},
+    pickup::{
+        PickupMeshes, apply_magnet, on_enemy_destroyed_drop_pickup, on_pickup_collected,
+        setup_pickups, update_pickups,
+    },
+    popup::{on_enemy_destroyed_popup, on_enemy_hit_damage_number, update_popups},
+    practice::{apply_practice_hitboxes, record_furthest_wave},
+    profile::load_profile,
+    ron_asset::RonAssetLoader,
+    run_log::{
+        RunLog, log_enemy_destroyed, log_saucer_transition, log_ship_lost, log_wave_start,
+        save_run_log, start_run_log,
+    },
+    saucer::{
+        AbductionDirector, SaucerState, animate_saucers, dodge_shots, queen_saucers_on_planet_lost,
+        separate_saucers, spawn_saucer, switch_saucer_animation,
+    },
+    saucer_damage::{mark_damaged_saucers, update_saucer_damage},
+    scanner::{
+        ScannerMaterial, ScannerMesh, apply_scanner_settings, on_add_scanner_marker, setup_scanner,
+        update_scanner,
+    },
+    scene_anim::{SceneAnimationGraphs, on_scene_ready_play_animation, on_switch_scene_animation},
+    score::{
+        Milestones, Reserves, award_loop_bonus, award_wave_smart_bomb, on_milestone_flash,
+        on_score, spawn_reserves_display, start_run_reserves, update_milestone_flash,
+        update_reserves_display,
+    },
+    searchlight::{SearchlightMesh, on_add_saucer, setup_searchlights, update_searchlights},
+    settings::{
+        Settings, adjust_assist_settings, adjust_audio_settings, adjust_gameplay_settings,
+        adjust_graphics_settings, load_settings,
+    },
+    shield_bubble::{
+        ShieldMaterial, on_add_ship_shield_bubble, on_player_hit_ripple, update_shield_bubble,
+    },
+    ship::{auto_fire, move_ship, spawn_ship},
+    spatial::{SpatialHash, update_spatial_hash},
+    speedrun::{
+        RunTimer, record_split, spawn_run_timer, start_run_timer, tick_run_timer,
+        update_run_timer_display,
+    },
+    stats::{
+        RunStats, on_enemy_destroyed, on_game_over, on_level_complete, on_shot_fired,
+        on_shot_landed, on_treasure_lost, on_treasure_rescued, start_run_stats, track_distance,
+    },
+    structures::{
+        BombingRun, StructureAssets, award_structure_bonus, drop_enemy_bombs,
+        on_structure_destroyed, setup_structures, spawn_structures, update_enemy_bombs,
+    },
+    swarmlet::{SwarmletAssets, emit_swarmlets, nest_queens, setup_swarmlets, update_swarmlets},
+    terrain::Terrain,
+    theme::{CurrentTheme, LevelTheme, load_theme, select_level_theme},
+    ticker::{
+        on_ticker_message, spawn_event_ticker, ticker_all_treasures_lost, ticker_saucer_transition,
+        ticker_structure_destroyed, ticker_treasure_lost, ticker_treasure_rescued,
+        ticker_treasures_critical, update_event_ticker,
+    },
+    time_scale::{
+        TimeScale, on_slow_motion, slow_motion_on_enemy_destroyed, slow_motion_on_ship_lost,
+        update_time_scale,
+    },
+    treasure::{
+        TreasureTable, TreasureTableHandle, award_treasure_points, load_treasure_table,
+        spawn_treasure, update_treasure,
+    },
+    treasure_count::{
+        TreasureCount, count_lost_treasure, spawn_treasure_count_display, update_treasure_count,
+        update_treasure_count_display,
+    },
+    tuning::{Tuning, TuningHandle, apply_tuning, load_tuning},
+    ui_audio::{UiAudioBus, on_ui_sound, play_wave_complete, update_ui_audio_bus},
+    wave::{
+        WaveConfig, check_wave_complete, configure_wave, continue_to_next_level, spawn_wave_banner,
+        update_wave_banner,
+    },
 };
 
+mod adaptive;
+mod afterburner;
+mod ai;
+mod alerts;
+mod announcer;
+mod asset_watchdog;
+mod autosave;
+mod barrel_roll;
+mod bomb;
+mod boss;
+mod continues;
+mod damage;
+mod debris;
+mod drone;
+#[cfg(feature = "embedded")]
+mod embedded_assets;
+mod enemy_shot;
 mod explosion;
+mod frame_limiter;
+#[cfg(feature = "debug")]
+mod frame_step;
 mod game_state;
+mod heat_haze;
+mod high_scores;
+mod hit_feedback;
+mod hud;
+mod hurtbox;
+mod input_glyphs;
+#[cfg(feature = "debug")]
+mod inspector;
+mod intro;
 mod laser;
+mod laser_heat;
+mod layout;
+mod leader;
+mod low_power;
+mod menu_input;
+mod minimap;
 mod mountains;
+mod muzzle_flash;
+mod nebula;
+mod parachute;
+mod pause;
+#[cfg(feature = "debug")]
+mod perf_hud;
+mod photo_mode;
+mod pickup;
+mod popup;
+mod practice;
+mod profile;
+mod ron_asset;
+mod run_log;
 mod saucer;
+mod saucer_damage;
+mod scanner;
+mod scene_anim;
+mod score;
+mod searchlight;
+mod settings;
+mod shield_bubble;
 mod ship;
+mod spatial;
+mod speedrun;
 mod stars;
+mod stats;
+mod steering;
+mod structures;
+mod swarmlet;
+mod terrain;
+mod theme;
+mod ticker;
+mod time_scale;
 mod treasure;
+mod treasure_count;
+mod tuning;
+mod ui_audio;
+mod wave;
 
-/// Virtual width of playfield.
-pub const PLAYFIELD_WIDTH: f32 = 8.0;
+/// Default virtual width of playfield.
+pub const DEFAULT_PLAYFIELD_WIDTH: f32 = 8.0;
 
 pub const NEBULA_DEPTH: f32 = -100.0;
 pub const STARS_DEPTH: f32 = -80.0;
@@ -41,13 +256,63 @@ pub const FX_DEPTH: f32 = 0.0;
 pub const PLAYER_LAYER: u32 = 1 << 0;
 pub const ENEMY_LAYER: u32 = 1 << 1;
 pub const PLAYER_SHOT_LAYER: u32 = 1 << 2;
+pub const PICKUP_LAYER: u32 = 1 << 3;
+pub const ENEMY_SHOT_LAYER: u32 = 1 << 4;
+
+/// Dimensions of the wraparound world. This can be changed between levels; the stars, mountains
+/// and minimap are regenerated whenever it changes.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct Playfield {
+    /// Virtual width of playfield.
+    pub width: f32,
+
+    /// Lowest altitude the player ship can fly at
+    pub floor: f32,
+
+    /// Highest altitude the player ship can fly at
+    pub ceiling: f32,
+}
+
+impl Default for Playfield {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_PLAYFIELD_WIDTH,
+            floor: -0.4,
+            ceiling: 0.45,
+        }
+    }
+}
+
+impl Playfield {
+    /// Wrap a horizontal coordinate into the range 0..width.
+    pub fn wrap(&self, x: f32) -> f32 {
+        x.rem_euclid(self.width)
+    }
+
+    /// Return the horizontal position of `x` relative to `origin`, taking the shortest path
+    /// around the world. Result is in the range -width/2..width/2.
+    pub fn relative(&self, x: f32, origin: f32) -> f32 {
+        (x - origin + self.width * 0.5).rem_euclid(self.width) - self.width * 0.5
+    }
+
+    /// Return the shortest displacement from `from` to `to`, wrapping horizontally.
+    pub fn delta(&self, from: Vec2, to: Vec2) -> Vec2 {
+        Vec2::new(self.relative(to.x, from.x), to.y - from.y)
+    }
+
+    /// Return the shortest distance between two points, wrapping horizontally.
+    pub fn distance(&self, a: Vec2, b: Vec2) -> f32 {
+        self.delta(a, b).length()
+    }
+}
 
 /// Represents the current camera scroll position. Note that because this is a multi-planar parallax
 /// scrolling game with a wrap-around world, we don't use the normal perspective transform or even
 /// move thd camera. Instead, we move all the individual objects relative to the virtual viewpoint.
-#[derive(Resource, Debug, Default)]
+#[derive(Resource, Debug, Default, Reflect)]
+#[reflect(Resource)]
 pub struct Viewpoint {
-    /// Range is 0..PLAYFIELD_WIDTH
+    /// Range is 0..Playfield::width
     position: f32,
 }
 
@@ -55,13 +320,106 @@ pub struct Viewpoint {
 #[derive(Component, Default, Debug)]
 pub struct UnitPosition(pub Vec2);
 
+/// Marker for entities that should be drawn on both edges of the screen when they are close to
+/// the wrap seam. A ghost copy is spawned which is positioned one playfield width away. If the
+/// entity has a collider, the ghost gets one too, so that collisions work across the seam.
+#[derive(Component, Default, Debug)]
+pub struct SeamMirrored;
+
+/// Copy of a [`SeamMirrored`] entity, used to render it and collide with it on the far side of
+/// the seam. Collisions with a ghost should be treated as collisions with its source; see
+/// [`seam_source`].
+#[derive(Component, Debug)]
+#[relationship(relationship_target = SeamGhosts)]
+pub struct SeamGhostOf(pub Entity);
+
+/// List of seam ghosts belonging to an entity; ghosts are despawned along with their source.
+#[derive(Component, Debug, Default)]
+#[relationship_target(relationship = SeamGhostOf, linked_spawn)]
+pub struct SeamGhosts(Vec<Entity>);
+
 /// Marker component to tag enemy units
 #[derive(Component, Default, Debug)]
 pub struct Enemy;
 
 /// Event sent to enemy when hit by shot.
+#[derive(Event, Debug)]
+pub struct EnemyHit {
+    /// Amount of damage dealt; a full-strength shot deals 1.0.
+    pub damage: f32,
+}
+
+/// Types of enemy, used for scoring and statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum EnemyKind {
+    Saucer,
+    Queen,
+    Leader,
+    Swarmlet,
+    BossTurret,
+    Boss,
+}
+
+impl EnemyKind {
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            EnemyKind::Saucer => "Saucer",
+            EnemyKind::Queen => "Queen",
+            EnemyKind::Leader => "Formation leader",
+            EnemyKind::Swarmlet => "Swarmlet",
+            EnemyKind::BossTurret => "Mothership turret",
+            EnemyKind::Boss => "Mothership",
+        }
+    }
+
+    /// Points awarded for destroying this kind of enemy
+    pub fn points(&self) -> i32 {
+        match self {
+            EnemyKind::Saucer => 150,
+            EnemyKind::Queen => 500,
+            EnemyKind::Leader => 400,
+            EnemyKind::Swarmlet => 0,
+            EnemyKind::BossTurret => 250,
+            EnemyKind::Boss => 5000,
+        }
+    }
+
+    /// Whether destroying this kind of enemy earns rewards: points, pickup drops and kill
+    /// streaks. Swarmlets are released endlessly by queens, so they earn nothing.
+    pub fn rewarded(&self) -> bool {
+        *self != EnemyKind::Swarmlet
+    }
+}
+
+/// Event sent when an enemy is destroyed by the player.
+#[derive(Event, Debug)]
+pub struct EnemyDestroyed {
+    pub kind: EnemyKind,
+    pub position: Vec2,
+}
+
+/// Event sent when the player fires a laser shot.
+#[derive(Event, Default, Debug)]
+pub struct ShotFired;
+
+/// Event sent when a player shot hits at least one enemy.
 #[derive(Event, Default, Debug)]
-pub struct EnemyHit;
+pub struct ShotLanded;
+
+/// Event sent when the player catches a falling treasure.
+#[derive(Event, Debug)]
+pub struct TreasureRescued {
+    pub position: Vec2,
+    /// Points awarded for the rescue
+    pub bonus: u32,
+}
+
+/// Event sent when a treasure is destroyed or carried off by a saucer.
+#[derive(Event, Debug)]
+pub struct TreasureLost {
+    pub position: Vec2,
+}
 
 /// Used as a source of random numbers for effects. Non-deterministic.
 #[derive(Resource)]
@@ -78,7 +436,19 @@ impl Default for UiCamera {
 
 /// Marker component for game camera
 #[derive(Component, Default, Debug)]
-struct PlayfieldCamera;
+pub(crate) struct PlayfieldCamera;
+
+/// Marker component for the HUD root, hidden in photo mode
+#[derive(Component, Default, Debug)]
+pub(crate) struct Hud;
+
+/// Marker component for the HUD header which contains the minimap
+#[derive(Component, Default, Debug)]
+pub(crate) struct HudHeader;
+
+/// Marker component for the minimap frame in the header
+#[derive(Component, Default, Debug)]
+pub(crate) struct Minimap;
 
 /// Marker component for main content area
 #[derive(Component, Default, Debug)]
@@ -98,6 +468,36 @@ pub struct Move;
 #[input_action(output = bool)]
 pub struct Fire;
 
+/// Drop bomb action
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+pub struct DropBomb;
+
+/// Smart bomb action
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+pub struct SmartBomb;
+
+/// Afterburner action, active while held
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+pub struct Boost;
+
+/// Air brake action, active while held
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+pub struct Brake;
+
+/// Instantly turn the ship around
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+pub struct Reverse;
+
+/// Barrel roll dodge action
+#[derive(Debug, InputAction)]
+#[input_action(output = bool)]
+pub struct Dodge;
+
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 struct PlayerSet;
 
@@ -122,6 +522,8 @@ fn main() {
     // load_window_settings(&mut prefs, &mut window);
 
     let mut app = App::new();
+    #[cfg(feature = "embedded")]
+    embedded_assets::register_embedded_assets(&mut app);
     app.add_plugins((
         DefaultPlugins
             .set(WindowPlugin {
@@ -132,63 +534,443 @@ fn main() {
         EnhancedInputPlugin,
         MaterialPlugin::<MountainMaterial>::default(),
         MaterialPlugin::<LaserMaterial>::default(),
+        MaterialPlugin::<StarMaterial>::default(),
+        MaterialPlugin::<ShrapnelMaterial>::default(),
+        MaterialPlugin::<HeatHazeMaterial>::default(),
+        MaterialPlugin::<ShieldMaterial>::default(),
+        UiMaterialPlugin::<ScannerMaterial>::default(),
         PhysicsPlugins::default(),
-        // PhysicsDebugPlugin::default(),
+        // Hitboxes are drawn in practice runs; otherwise the debug gizmos are disabled, and the
+        // plugin's systems are skipped.
+        PhysicsDebugPlugin::default(),
     ))
+    .init_asset::<LevelTheme>()
+    .register_asset_loader(RonAssetLoader::<LevelTheme>::new(&["theme.ron"]))
+    .init_asset::<TreasureTable>()
+    .register_asset_loader(RonAssetLoader::<TreasureTable>::new(&["treasures.ron"]))
+    .init_asset::<LevelLayout>()
+    .register_asset_loader(RonAssetLoader::<LevelLayout>::new(&["layout.ron"]))
+    .init_asset::<Tuning>()
+    .register_asset_loader(RonAssetLoader::<Tuning>::new(&["tuning.ron"]))
     .init_state::<GameState>()
     .init_state::<PauseState>()
+    .init_resource::<Playfield>()
     .init_resource::<UiCamera>()
+    .init_resource::<RunState>()
+    .init_resource::<Difficulty>()
+    .init_resource::<Level>()
+    .init_resource::<WaveConfig>()
+    .init_resource::<AbductionDirector>()
+    .init_resource::<SpatialHash>()
+    .init_resource::<RunStats>()
+    .init_resource::<Reserves>()
+    .init_resource::<Milestones>()
+    .init_resource::<Continues>()
+    .insert_resource(load_profile())
+    .insert_resource(load_high_scores())
+    .init_resource::<RunTimer>()
+    .init_resource::<Autosave>()
+    .insert_resource(load_settings())
     .init_resource::<Viewpoint>()
     .init_resource::<ShotMesh>()
+    .init_resource::<MuzzleFlashAssets>()
+    .init_resource::<PlaceholderAssets>()
+    .init_resource::<EnemyShotAssets>()
+    .init_resource::<DeflectWindow>()
+    .init_resource::<BombMesh>()
+    .init_resource::<ParachuteMesh>()
+    .init_resource::<LeaderAuraAssets>()
+    .init_resource::<SwarmletAssets>()
+    .init_resource::<BossAssets>()
+    .init_resource::<TreasureCount>()
+    .init_resource::<PickupMeshes>()
+    .init_resource::<UiAudioBus>()
+    .init_resource::<MenuInput>()
+    .init_resource::<InputDevice>()
+    .init_resource::<PauseFocus>()
+    .init_resource::<ClockHold>()
+    .init_resource::<HitFlash>()
+    .init_resource::<NebulaLightning>()
+    .init_resource::<BackgroundClock>()
+    .init_resource::<SearchlightMesh>()
+    .init_resource::<DebrisPool>()
+    .init_resource::<Terrain>()
     .init_resource::<ExplosionHandles>()
+    .init_resource::<TimeScale>()
+    .init_resource::<AiDebug>()
+    .init_resource::<HurtboxDebug>()
+    .init_resource::<KillStreak>()
+    .init_resource::<SceneAnimationGraphs>()
+    .init_resource::<ScannerMesh>()
+    .init_resource::<AdaptiveDifficulty>()
+    .init_resource::<RunLog>()
+    .init_resource::<CurrentTheme>()
+    .init_resource::<StructureAssets>()
+    .init_resource::<BombingRun>()
+    .init_resource::<TreasureTableHandle>()
+    .init_resource::<Tuning>()
+    .init_resource::<TuningHandle>()
+    .init_resource::<LevelLayouts>()
     .insert_resource(Gravity(Vec2::splat(0.0)))
     .insert_resource(RandomGenerator(ChaCha8Rng::seed_from_u64(19878367467712)))
     .add_input_context::<MainInput>()
     .add_observer(binding)
+    .add_observer(on_add_seam_mirrored)
+    .add_observer(on_slow_motion)
+    .add_observer(slow_motion_on_enemy_destroyed)
+    .add_observer(slow_motion_on_ship_lost)
+    .add_observer(on_ui_sound)
+    .add_observer(on_shot_fired)
+    .add_observer(on_shot_landed)
+    .add_observer(on_enemy_destroyed)
+    .add_observer(on_treasure_rescued)
+    .add_observer(on_treasure_lost)
+    .add_observer(on_enemy_destroyed_drop_pickup)
+    .add_observer(on_pickup_collected)
+    .add_observer(on_drone_pickup)
+    .add_observer(on_abduction_started)
+    .add_observer(switch_saucer_animation)
+    .add_observer(queen_saucers_on_planet_lost)
+    .add_observer(on_ticker_message)
+    .add_observer(on_add_ship_blip)
+    .add_observer(on_add_enemy_blip)
+    .add_observer(on_add_treasure_blip)
+    .add_observer(on_add_scanner_marker)
+    .add_observer(ticker_saucer_transition)
+    .add_observer(ticker_treasure_lost)
+    .add_observer(ticker_treasure_rescued)
+    .add_observer(ticker_structure_destroyed)
+    .add_observer(ticker_treasures_critical)
+    .add_observer(ticker_all_treasures_lost)
+    .add_observer(count_lost_treasure)
+    .add_observer(on_player_hit_flash)
+    .add_observer(on_add_player_ship)
+    .add_observer(on_add_ship_shield_bubble)
+    .add_observer(on_player_hit_ripple)
+    .add_observer(on_add_saucer)
     .add_observer(on_add_flare)
+    .add_observer(on_add_dust)
+    .add_observer(on_add_spark)
+    .add_observer(on_remove_shrapnel)
+    .add_observer(on_remove_flare)
+    .add_observer(on_remove_dust)
+    .add_observer(on_add_muzzle_flash)
+    .add_observer(arm_queens)
+    .add_observer(nest_queens)
+    .add_observer(on_scene_ready_play_animation)
+    .add_observer(on_switch_scene_animation)
+    .add_observer(on_add_invulnerable)
+    .add_observer(on_remove_invulnerable)
+    .add_observer(on_remove_parachute)
+    .add_observer(on_add_formation_leader)
     .add_observer(on_add_shrapnel)
+    .add_observer(on_add_shrapnel_debris)
+    .add_observer(on_structure_destroyed)
+    .add_observer(award_structure_bonus)
+    .add_observer(award_treasure_points)
+    .add_observer(on_score)
+    .add_observer(award_wave_smart_bomb)
+    .add_observer(award_loop_bonus)
+    .add_observer(adaptive_on_shot_fired)
+    .add_observer(adaptive_on_shot_landed)
+    .add_observer(adaptive_on_ship_lost)
+    .add_observer(log_enemy_destroyed)
+    .add_observer(log_ship_lost)
+    .add_observer(log_saucer_transition)
+    .add_observer(on_enemy_destroyed_streak)
+    .add_observer(announce_streak)
+    .add_observer(on_enemy_destroyed_popup)
+    .add_observer(on_enemy_hit_damage_number)
+    .add_observer(on_milestone_flash)
+    .add_observer(record_split)
     .add_systems(
         Startup,
         (
             setup,
+            load_theme,
+            load_treasure_table,
+            load_tuning,
+            load_level_layouts,
+            spawn_shield_alert,
+            spawn_hit_feedback,
             setup_laser,
+            setup_muzzle_flash,
+            setup_enemy_shots,
+            setup_bombs,
+            (
+                setup_parachutes,
+                setup_leader_auras,
+                setup_swarmlets,
+                setup_boss,
+            ),
+            setup_pickups,
+            setup_searchlights,
+            setup_debris,
             setup_explosions,
-            spawn_stars,
-            spawn_mountains,
+            setup_structures,
+            setup_placeholders,
+            apply_practice_hitboxes,
+            setup_scanner.after(setup),
+        ),
+    )
+    .add_systems(
+        StateTransition,
+        (despawn_on_exit::<GameState>, despawn_on_exit::<PauseState>)
+            .in_set(StateTransitionSteps::ExitSchedules),
+    )
+    .add_systems(
+        StateTransition,
+        sync_game_clock.after(StateTransitionSteps::EnterSchedules),
+    )
+    .add_systems(OnEnter(GameState::Intro), (spawn_intro_menu, save_run_log))
+    .add_systems(
+        OnExit(GameState::Intro),
+        (
+            start_run_stats,
+            start_run_reserves,
+            start_run_continues,
+            start_run_adaptive,
+            start_run_log,
+            start_run_timer,
+            start_run_level,
+            apply_practice_hitboxes,
+        ),
+    )
+    .add_systems(OnEnter(PauseState::Paused), spawn_pause_menu)
+    .add_systems(OnEnter(PauseState::PhotoMode), enter_photo_mode)
+    .add_systems(OnExit(PauseState::PhotoMode), exit_photo_mode)
+    .add_systems(
+        OnEnter(PauseState::GameOver),
+        (
+            on_game_over,
+            record_high_score,
+            spawn_continue_prompt,
+            save_run_log,
+        ),
+    )
+    .add_systems(
+        OnEnter(GameState::LevelComplete),
+        (on_level_complete, play_wave_complete),
+    )
+    .add_systems(OnExit(GameState::LevelComplete), advance_level)
+    .add_systems(
+        OnEnter(GameState::Playing),
+        (
             spawn_ship,
             spawn_treasure,
             spawn_saucer,
+            spawn_boss,
+            spawn_structures,
+            spawn_reserves_display,
+            spawn_heat_gauge,
+            spawn_laser_heat_gauge,
+            spawn_roll_gauge,
+            spawn_treasure_count_display,
+            record_furthest_wave,
+            log_wave_start,
+            spawn_run_timer,
+            spawn_event_ticker,
+            spawn_minimap_window,
+            (configure_wave, spawn_wave_banner).chain(),
+            (select_level_theme, (spawn_stars, spawn_mountains)).chain(),
         ),
     )
+    .add_systems(OnExit(GameState::Playing), clear_debris)
     .configure_sets(
         Update,
         (
-            PlayerSet,
-            EnemySet.after(PlayerSet),
+            PlayerSet
+                .after(update_spatial_hash)
+                .run_if(in_state(PauseState::Running)),
+            EnemySet
+                .after(PlayerSet)
+                .run_if(in_state(PauseState::Running)),
             EffectSet.after(EnemySet),
         ),
     )
     .add_systems(
         Update,
         (
-            update_viewport_rect,
-            move_ship.in_set(PlayerSet),
-            animate_saucers.in_set(EnemySet),
+            (spawn_stars, spawn_mountains, update_minimap_aspect)
+                .run_if(resource_changed::<Playfield>),
+            (
+                spawn_stars
+                    .run_if(on_event::<AssetEvent<LevelTheme>>.or(resource_changed::<Settings>)),
+                spawn_mountains.run_if(on_event::<AssetEvent<LevelTheme>>),
+                (
+                    setup_explosions,
+                    apply_present_mode,
+                    apply_hurtbox_scale,
+                    apply_scanner_settings,
+                )
+                    .run_if(resource_changed::<Settings>),
+                apply_hud_settings
+                    .run_if(resource_changed::<Settings>.or(resource_changed::<WaveConfig>)),
+            ),
+            update_viewport_rect.run_if(not(in_state(PauseState::PhotoMode))),
+            (
+                update_time_scale,
+                apply_tuning.run_if(on_event::<AssetEvent<Tuning>>),
+                tick_background_clock.before(EffectSet),
+            ),
+            update_spatial_hash.run_if(in_state(PauseState::Running)),
+            update_ui_audio_bus.run_if(resource_changed::<UiAudioBus>),
+            (toggle_pause, toggle_photo_mode).run_if(in_state(GameState::Playing)),
+            update_photo_camera.run_if(in_state(PauseState::PhotoMode)),
+            (
+                adjust_hud_settings,
+                adjust_graphics_settings,
+                adjust_gameplay_settings,
+                adjust_audio_settings,
+                adjust_assist_settings,
+            )
+                .run_if(in_state(PauseState::Paused)),
+            (
+                update_intro_menu.run_if(in_state(GameState::Intro)),
+                update_continue_prompt.run_if(in_state(PauseState::GameOver)),
+                update_pause_menu.run_if(in_state(PauseState::Paused)),
+                continue_to_next_level.run_if(in_state(GameState::LevelComplete)),
+                update_button_prompts,
+            ),
+            (
+                (
+                    update_afterburner,
+                    update_barrel_roll,
+                    move_ship,
+                    update_heat_gauge,
+                    update_roll_gauge,
+                )
+                    .chain(),
+                (update_laser_heat, auto_fire, update_laser_heat_gauge).chain(),
+                update_bombs,
+                (apply_magnet, update_pickups).chain(),
+                update_drones,
+                track_distance,
+                update_invulnerability,
+                update_kill_streak,
+                update_adaptive_difficulty,
+            )
+                .in_set(PlayerSet),
+            (
+                dodge_shots,
+                animate_saucers,
+                separate_saucers,
+                apply_ai_transitions::<SaucerState>,
+            )
+                .chain()
+                .in_set(EnemySet),
+            (
+                (update_treasure, update_parachutes).chain(),
+                (update_leader_auras, update_aura_rings),
+                (emit_swarmlets, update_swarmlets),
+                (update_boss, fire_boss_turrets),
+                drop_enemy_bombs,
+                update_enemy_bombs,
+                fire_enemy_shots,
+                (mark_damaged_saucers, update_saucer_damage).chain(),
+            )
+                .in_set(EnemySet),
+            (toggle_ai_debug, toggle_hurtbox_debug),
+            (
+                draw_ai_debug::<SaucerState>.run_if(|r_debug: Res<AiDebug>| r_debug.0),
+                draw_hurtbox.run_if(|r_debug: Res<HurtboxDebug>| r_debug.0),
+            ),
             (
                 update_stars,
                 update_mountains,
-                update_laser,
+                (update_laser, update_ricochet).chain(),
                 update_shrapnel,
-                update_flare,
+                (update_flare, update_flare_lights).chain(),
+                update_dust,
+                update_sparks,
+                update_muzzle_flash,
+                update_heat_haze,
+                update_shield_bubble,
+                (spawn_nebula_lightning, update_nebula_lightning).run_if(background_tick),
+                update_searchlights,
+                update_debris,
+                update_popups,
             )
                 .in_set(EffectSet),
-            detect_enemy_kills,
+            check_wave_complete.run_if(in_state(GameState::Playing)),
+            (
+                (
+                    detect_enemy_kills,
+                    detect_ground_impacts,
+                    emit_ground_sparks,
+                )
+                    .chain()
+                    .after(update_ricochet),
+                (update_enemy_shots, deflect_enemy_shots, detect_player_hits).chain(),
+            )
+                .run_if(in_state(PauseState::Running)),
+            (
+                update_shield_alert,
+                (update_abduction_alert, update_distress_calls),
+                update_hit_feedback,
+                update_reserves_display.run_if(resource_changed::<Reserves>),
+                update_milestone_flash,
+                (
+                    update_treasure_count.run_if(in_state(PauseState::Running)),
+                    update_treasure_count_display,
+                )
+                    .chain(),
+                (
+                    (tick_run_timer, track_assists).run_if(in_state(PauseState::Running)),
+                    update_run_timer_display,
+                )
+                    .chain(),
+                update_event_ticker,
+                update_wave_banner,
+                (update_minimap, update_scanner, update_abduction_progress).chain(),
+            ),
+            (
+                report_failed_assets::<Gltf>,
+                report_failed_assets::<AudioSource>,
+                report_failed_assets::<LevelTheme>,
+                report_failed_assets::<TreasureTable>,
+                report_failed_assets::<LevelLayout>,
+                report_failed_assets::<Tuning>,
+                (report_failed_assets::<Image>, substitute_failed_images).chain(),
+                substitute_failed_scenes,
+                remove_failed_sounds,
+            ),
+        ),
+    )
+    .add_systems(
+        PreUpdate,
+        (read_menu_input, detect_input_device).after(InputSystem),
+    )
+    .add_systems(
+        PostUpdate,
+        (
+            update_unit_translation,
+            throttle_animations.before(bevy::app::Animation),
         ),
     )
-    .add_systems(PostUpdate, update_unit_translation);
+    .add_systems(
+        Last,
+        (
+            autosave,
+            limit_frame_rate,
+            save_run_log.run_if(on_event::<AppExit>.and(not(in_state(GameState::Intro)))),
+        ),
+    );
+
+    #[cfg(feature = "debug")]
+    app.add_plugins((
+        inspector::InspectorPlugin,
+        frame_step::FrameStepPlugin,
+        perf_hud::PerfHudPlugin,
+    ));
 
     embedded_asset!(app, "assets/shaders/mountains.wgsl");
     embedded_asset!(app, "assets/shaders/laser.wgsl");
+    embedded_asset!(app, "assets/shaders/stars.wgsl");
+    embedded_asset!(app, "assets/shaders/shrapnel.wgsl");
+    embedded_asset!(app, "assets/shaders/heat_haze.wgsl");
+    embedded_asset!(app, "assets/shaders/shield_bubble.wgsl");
+    embedded_asset!(app, "assets/shaders/scanner.wgsl");
     app.run();
 }
 
@@ -213,6 +995,18 @@ fn setup(
 
     r_ui_camera.0 = ui_camera;
 
+    // Overlay camera, draws menus and other UI on top of the playfield. This is the default UI
+    // camera, so any UI without a `UiTargetCamera` is rendered here.
+    commands.spawn((
+        Camera2d,
+        Camera {
+            clear_color: ClearColorConfig::None,
+            order: 2,
+            ..default()
+        },
+        IsDefaultUiCamera,
+    ));
+
     // UI root entity
     commands.spawn((
         Node {
@@ -227,6 +1021,7 @@ fn setup(
             ..default()
         },
         UiTargetCamera(ui_camera),
+        Hud,
         children![
             (
                 // Header section with minimap
@@ -239,14 +1034,16 @@ fn setup(
                     ..default()
                 },
                 BackgroundColor(Color::srgb(0.0, 0.0, 0.1)),
+                HudHeader,
                 children![(
                     Node {
                         min_height: Val::Percent(80.0),
-                        aspect_ratio: Some(PLAYFIELD_WIDTH),
+                        aspect_ratio: Some(DEFAULT_PLAYFIELD_WIDTH),
                         border: UiRect::all(Val::Px(2.0)),
                         ..default()
                     },
-                    BorderColor(Color::srgb(0.0, 0.5, 0.0))
+                    BorderColor(Color::srgb(0.0, 0.5, 0.0)),
+                    Minimap
                 ),],
             ),
             // Main content section
@@ -360,6 +1157,16 @@ fn update_viewport_rect(
     };
 }
 
+/// Keep the minimap proportional to the width of the world.
+fn update_minimap_aspect(
+    mut q_minimap: Query<&mut Node, With<Minimap>>,
+    r_playfield: Res<Playfield>,
+) {
+    for mut node in q_minimap.iter_mut() {
+        node.aspect_ratio = Some(r_playfield.width);
+    }
+}
+
 fn binding(trigger: Trigger<Binding<MainInput>>, mut players: Query<&mut Actions<MainInput>>) {
     let mut actions = players.get_mut(trigger.target()).unwrap();
 
@@ -368,6 +1175,7 @@ fn binding(trigger: Trigger<Binding<MainInput>>, mut players: Query<&mut Actions
         .to((
             Cardinal::wasd_keys(),
             Cardinal::arrow_keys(),
+            Cardinal::dpad_buttons(),
             Axial::left_stick(),
         ))
         // .with_modifiers((
@@ -377,18 +1185,98 @@ fn binding(trigger: Trigger<Binding<MainInput>>, mut players: Query<&mut Actions
         // ))
         ;
 
-    actions.bind::<Fire>().to((KeyCode::Space,));
+    actions
+        .bind::<Fire>()
+        .to((KeyCode::Space, GamepadButton::South));
+    actions
+        .bind::<DropBomb>()
+        .to((KeyCode::KeyB, KeyCode::AltLeft, GamepadButton::West));
+    actions
+        .bind::<SmartBomb>()
+        .to((KeyCode::KeyN, KeyCode::ControlLeft, GamepadButton::North));
+    actions.bind::<Boost>().to((
+        KeyCode::ShiftLeft,
+        KeyCode::KeyV,
+        GamepadButton::RightTrigger,
+    ));
+    actions
+        .bind::<Brake>()
+        .to((KeyCode::KeyC, GamepadButton::LeftTrigger));
+    actions
+        .bind::<Reverse>()
+        .to((KeyCode::KeyR, GamepadButton::East));
+    actions
+        .bind::<Dodge>()
+        .to((KeyCode::KeyX, GamepadButton::RightTrigger2));
+}
+
+/// Distance from the wrap seam within which mirrored entities are also drawn on the opposite edge.
+/// This is half the width of the widest viewport we allow.
+const SEAM_MARGIN: f32 = MAX_ASPECT * 0.5;
+
+/// Map a seam ghost to the entity it is a copy of. Other entities are returned unchanged.
+pub(crate) fn seam_source(q_ghosts: &Query<&SeamGhostOf>, entity: Entity) -> Entity {
+    q_ghosts.get(entity).map_or(entity, |ghost_of| ghost_of.0)
+}
+
+/// Spawn a ghost copy of the entity's visual and collision components. The ghost's collider
+/// is disabled while it is hidden.
+fn on_add_seam_mirrored(trigger: Trigger<OnAdd, SeamMirrored>, mut commands: Commands) {
+    let source = trigger.target();
+    commands
+        .entity(source)
+        .clone_and_spawn_with(|builder| {
+            builder.deny_all().allow::<(
+                Transform,
+                Mesh3d,
+                MeshMaterial3d<StandardMaterial>,
+                MeshMaterial3d<LaserMaterial>,
+                SceneRoot,
+                RigidBody,
+                Collider,
+                CollisionLayers,
+                Sensor,
+            )>();
+        })
+        .insert((SeamGhostOf(source), Visibility::Hidden, ColliderDisabled));
 }
 
 /// Convert the unit position into wrap-around coordinates relative to camera.
 pub(crate) fn update_unit_translation(
-    mut q_units: Query<(&UnitPosition, &mut Transform)>,
-    r_viewpoint: ResMut<Viewpoint>,
+    mut commands: Commands,
+    mut q_units: Query<(&UnitPosition, &mut Transform), Without<SeamGhostOf>>,
+    mut q_ghosts: Query<(Entity, &SeamGhostOf, &mut Transform, &mut Visibility)>,
+    r_viewpoint: Res<Viewpoint>,
+    r_playfield: Res<Playfield>,
 ) {
     for (position, mut transform) in q_units.iter_mut() {
-        transform.translation.x = (position.0.x - r_viewpoint.position + PLAYFIELD_WIDTH * 0.5)
-            .rem_euclid(PLAYFIELD_WIDTH)
-            - PLAYFIELD_WIDTH * 0.5;
+        transform.translation.x = r_playfield.relative(position.0.x, r_viewpoint.position);
         transform.translation.y = position.0.y;
     }
+
+    // Ghosts follow their source, offset by one playfield width when the source is near the seam.
+    for (ghost, ghost_of, mut transform, mut visibility) in q_ghosts.iter_mut() {
+        let Ok((_, source)) = q_units.get(ghost_of.0) else {
+            continue;
+        };
+        let x = source.translation.x;
+        let half_width = r_playfield.width * 0.5;
+        let offset = if x > half_width - SEAM_MARGIN {
+            -r_playfield.width
+        } else if x < SEAM_MARGIN - half_width {
+            r_playfield.width
+        } else {
+            if visibility.set_if_neq(Visibility::Hidden) {
+                commands.entity(ghost).insert(ColliderDisabled);
+            }
+            continue;
+        };
+        *transform = Transform {
+            translation: source.translation + Vec3::X * offset,
+            ..*source
+        };
+        if visibility.set_if_neq(Visibility::Inherited) {
+            commands.entity(ghost).remove::<ColliderDisabled>();
+        }
+    }
 }
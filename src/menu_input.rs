@@ -0,0 +1,69 @@
+//! Menu navigation input, combined from the keyboard and every connected gamepad, so that each
+//! menu can be driven with the arrow keys, the d-pad or the left stick, and the face buttons.
+use bevy::prelude::*;
+
+/// Menu actions triggered this frame.
+#[derive(Resource, Default, Debug)]
+pub struct MenuInput {
+    pub up: bool,
+    pub down: bool,
+    pub left: bool,
+    pub right: bool,
+
+    /// Enter, or the south face button
+    pub confirm: bool,
+
+    /// Backspace, or the east face button
+    pub back: bool,
+
+    /// P or Escape, or the start button
+    pub pause: bool,
+}
+
+/// The left stick must be pushed this far to move the menu selection.
+const STICK_THRESHOLD: f32 = 0.6;
+
+/// Gather menu input. The left stick acts like the d-pad: it moves the selection once each time
+/// it is pushed in a direction.
+pub(crate) fn read_menu_input(
+    q_gamepads: Query<&Gamepad>,
+    r_keys: Res<ButtonInput<KeyCode>>,
+    mut r_menu: ResMut<MenuInput>,
+    mut stick_direction: Local<IVec2>,
+) {
+    let pressed = |keys: &[KeyCode], buttons: &[GamepadButton]| {
+        r_keys.any_just_pressed(keys.iter().copied())
+            || q_gamepads
+                .iter()
+                .any(|gamepad| gamepad.any_just_pressed(buttons.iter().copied()))
+    };
+
+    let stick = q_gamepads
+        .iter()
+        .map(|gamepad| gamepad.left_stick())
+        .find(|stick| stick.length() > STICK_THRESHOLD)
+        .unwrap_or_default();
+    let direction = if stick.x.abs() > stick.y.abs() {
+        IVec2::new(stick.x.signum() as i32, 0)
+    } else if stick.y != 0.0 {
+        IVec2::new(0, stick.y.signum() as i32)
+    } else {
+        IVec2::ZERO
+    };
+    let pushed = if direction != *stick_direction {
+        direction
+    } else {
+        IVec2::ZERO
+    };
+    *stick_direction = direction;
+
+    *r_menu = MenuInput {
+        up: pushed.y > 0 || pressed(&[KeyCode::ArrowUp], &[GamepadButton::DPadUp]),
+        down: pushed.y < 0 || pressed(&[KeyCode::ArrowDown], &[GamepadButton::DPadDown]),
+        left: pushed.x < 0 || pressed(&[KeyCode::ArrowLeft], &[GamepadButton::DPadLeft]),
+        right: pushed.x > 0 || pressed(&[KeyCode::ArrowRight], &[GamepadButton::DPadRight]),
+        confirm: pressed(&[KeyCode::Enter], &[GamepadButton::South]),
+        back: pressed(&[KeyCode::Backspace], &[GamepadButton::East]),
+        pause: pressed(&[KeyCode::KeyP, KeyCode::Escape], &[GamepadButton::Start]),
+    };
+}
@@ -0,0 +1,320 @@
+//! Minimap blips, and the bracket showing which part of the world is on screen. Like Defender's
+//! scanner, the world is unrolled around the player ship, which always sits in the middle.
+use bevy::prelude::*;
+
+use crate::{
+    Enemy, Minimap, Playfield, PlayfieldCamera, UnitPosition, Viewpoint,
+    ai::StateMachine,
+    game_state::{DespawnOnExit, GameState},
+    leader::FormationLeader,
+    saucer::{Saucer, SaucerState},
+    settings::{MinimapStyle, Settings},
+    ship::PlayerShip,
+    treasure::Treasure,
+};
+
+/// Type of unit shown by a blip, which determines its size and color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlipKind {
+    Ship,
+    Enemy,
+    Treasure,
+}
+
+/// A dot on the minimap tracking a unit. Despawned along with its unit.
+#[derive(Component, Debug)]
+pub struct MinimapBlip {
+    /// Unit being tracked
+    target: Entity,
+
+    kind: BlipKind,
+
+    /// Position on the minimap, from 0,0 at the top left to 1,1 at the bottom right
+    position: Vec2,
+}
+
+impl MinimapBlip {
+    pub fn kind(&self) -> BlipKind {
+        self.kind
+    }
+
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
+}
+
+/// Gauge beside an enemy blip showing how far it has carried a treasure. Only shown while the
+/// saucer is escaping.
+#[derive(Component, Default, Debug)]
+pub struct AbductionProgress;
+
+/// Filled part of an [`AbductionProgress`] gauge
+#[derive(Component, Default, Debug)]
+pub struct AbductionProgressFill;
+
+/// Bracket on the minimap around the part of the world which is on screen
+#[derive(Component, Default, Debug)]
+pub struct MinimapWindow;
+
+/// Size of the abduction progress gauge, in pixels
+const PROGRESS_WIDTH: f32 = 2.0;
+const PROGRESS_HEIGHT: f32 = 12.0;
+
+/// Range of altitudes shown on the minimap. The top is above the screen, so that arriving
+/// saucers can be seen.
+const MINIMAP_BOTTOM: f32 = -0.5;
+const MINIMAP_TOP: f32 = 0.75;
+
+/// Height of the playfield camera's view
+const SCREEN_HEIGHT: f32 = 1.0;
+
+const SHIP_BLIP_COLOR: Color = Color::WHITE;
+const SAUCER_BLIP_COLOR: Color = Color::srgb(0.2, 1.0, 0.2);
+const QUEEN_BLIP_COLOR: Color = Color::srgb(1.0, 0.2, 1.0);
+const LEADER_BLIP_COLOR: Color = Color::srgb(1.0, 0.7, 0.2);
+const TREASURE_BLIP_COLOR: Color = Color::srgb(1.0, 0.8, 0.2);
+const PROGRESS_TRACK_COLOR: Color = Color::srgba(0.3, 0.1, 0.0, 0.8);
+const PROGRESS_FILL_COLOR: Color = Color::srgb(1.0, 0.5, 0.0);
+const WINDOW_COLOR: Color = Color::srgba(0.6, 0.8, 1.0, 0.7);
+
+impl BlipKind {
+    /// Size of the blip, in pixels
+    pub fn size(self) -> f32 {
+        match self {
+            BlipKind::Ship => 6.0,
+            BlipKind::Enemy => 4.0,
+            BlipKind::Treasure => 3.0,
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            BlipKind::Ship => SHIP_BLIP_COLOR,
+            BlipKind::Enemy => SAUCER_BLIP_COLOR,
+            BlipKind::Treasure => TREASURE_BLIP_COLOR,
+        }
+    }
+}
+
+pub(crate) fn on_add_ship_blip(
+    trigger: Trigger<OnAdd, PlayerShip>,
+    commands: Commands,
+    q_minimap: Query<Entity, With<Minimap>>,
+) {
+    spawn_blip(commands, &q_minimap, trigger.target(), BlipKind::Ship);
+}
+
+pub(crate) fn on_add_enemy_blip(
+    trigger: Trigger<OnAdd, Enemy>,
+    commands: Commands,
+    q_minimap: Query<Entity, With<Minimap>>,
+) {
+    spawn_blip(commands, &q_minimap, trigger.target(), BlipKind::Enemy);
+}
+
+pub(crate) fn on_add_treasure_blip(
+    trigger: Trigger<OnAdd, Treasure>,
+    commands: Commands,
+    q_minimap: Query<Entity, With<Minimap>>,
+) {
+    spawn_blip(commands, &q_minimap, trigger.target(), BlipKind::Treasure);
+}
+
+fn spawn_blip(
+    mut commands: Commands,
+    q_minimap: &Query<Entity, With<Minimap>>,
+    target: Entity,
+    kind: BlipKind,
+) {
+    let Ok(minimap) = q_minimap.single() else {
+        return;
+    };
+    let size = kind.size();
+    let mut blip = commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Px(size),
+            height: Val::Px(size),
+            margin: UiRect::new(
+                Val::Px(-size * 0.5),
+                Val::ZERO,
+                Val::Px(-size * 0.5),
+                Val::ZERO,
+            ),
+            ..default()
+        },
+        BackgroundColor(kind.color()),
+        Visibility::Hidden,
+        MinimapBlip {
+            target,
+            kind,
+            position: Vec2::ZERO,
+        },
+        ChildOf(minimap),
+    ));
+    if kind == BlipKind::Enemy {
+        // Formation leaders are ringed, marking them as priority targets.
+        blip.insert(Outline::new(Val::Px(1.0), Val::Px(1.0), Color::NONE));
+
+        // Gauge to the right of the blip, rising from its bottom edge
+        blip.with_child((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(size + 1.0),
+                bottom: Val::Px(0.0),
+                width: Val::Px(PROGRESS_WIDTH),
+                height: Val::Px(PROGRESS_HEIGHT),
+                flex_direction: FlexDirection::ColumnReverse,
+                ..default()
+            },
+            BackgroundColor(PROGRESS_TRACK_COLOR),
+            Visibility::Hidden,
+            AbductionProgress,
+            children![(
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(0.0),
+                    ..default()
+                },
+                BackgroundColor(PROGRESS_FILL_COLOR),
+                AbductionProgressFill,
+            )],
+        ));
+    }
+}
+
+pub(crate) fn spawn_minimap_window(
+    mut commands: Commands,
+    q_minimap: Query<Entity, With<Minimap>>,
+) {
+    let Ok(minimap) = q_minimap.single() else {
+        return;
+    };
+    let top = (MINIMAP_TOP - SCREEN_HEIGHT * 0.5) / (MINIMAP_TOP - MINIMAP_BOTTOM);
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(top * 100.0),
+            bottom: Val::Percent(0.0),
+            border: UiRect::horizontal(Val::Px(2.0)),
+            ..default()
+        },
+        BorderColor(WINDOW_COLOR),
+        MinimapWindow,
+        DespawnOnExit(GameState::Playing),
+        ChildOf(minimap),
+    ));
+}
+
+/// Place blips and the screen bracket relative to the player ship. Blips whose unit has gone
+/// are removed.
+pub(crate) fn update_minimap(
+    mut commands: Commands,
+    mut q_blips: Query<(
+        Entity,
+        &mut MinimapBlip,
+        &mut Node,
+        &mut BackgroundColor,
+        Option<&mut Outline>,
+        &mut Visibility,
+    )>,
+    mut q_window: Query<&mut Node, (With<MinimapWindow>, Without<MinimapBlip>)>,
+    q_units: Query<(
+        &UnitPosition,
+        Option<&StateMachine<SaucerState>>,
+        Has<FormationLeader>,
+    )>,
+    q_ship: Query<&UnitPosition, With<PlayerShip>>,
+    q_camera: Query<&Projection, With<PlayfieldCamera>>,
+    r_viewpoint: Res<Viewpoint>,
+    r_playfield: Res<Playfield>,
+    r_settings: Res<Settings>,
+) {
+    // In scanner style, blips are drawn by the scanner camera instead.
+    let shown = r_settings.hud.minimap_style == MinimapStyle::Blips;
+    let center = q_ship
+        .single()
+        .map_or(r_viewpoint.position, |position| position.0.x);
+
+    for (blip, mut marker, mut node, mut color, outline, mut visibility) in q_blips.iter_mut() {
+        let Ok((position, machine, leader)) = q_units.get(marker.target) else {
+            commands.entity(blip).despawn();
+            continue;
+        };
+        let x = r_playfield.relative(position.0.x, center) / r_playfield.width + 0.5;
+        let y = (MINIMAP_TOP - position.0.y) / (MINIMAP_TOP - MINIMAP_BOTTOM);
+        marker.position = Vec2::new(x, y.clamp(0.0, 1.0));
+        node.left = Val::Percent(marker.position.x * 100.0);
+        node.top = Val::Percent(marker.position.y * 100.0);
+        *visibility = if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if marker.kind == BlipKind::Enemy {
+            let queen = machine.is_some_and(|machine| machine.state() == SaucerState::Queened);
+            color.0 = if queen {
+                QUEEN_BLIP_COLOR
+            } else if leader {
+                LEADER_BLIP_COLOR
+            } else {
+                SAUCER_BLIP_COLOR
+            };
+        }
+        if let Some(mut outline) = outline {
+            outline.color = if leader {
+                LEADER_BLIP_COLOR
+            } else {
+                Color::NONE
+            };
+        }
+    }
+
+    let screen_width = q_camera
+        .single()
+        .ok()
+        .and_then(|projection| match projection {
+            Projection::Orthographic(ortho) => Some(ortho.area.width()),
+            _ => None,
+        })
+        .unwrap_or(2.0);
+    let offset = r_playfield.relative(r_viewpoint.position, center);
+    let width = (screen_width / r_playfield.width).min(1.0);
+    let left = offset / r_playfield.width + 0.5 - width * 0.5;
+    for mut node in q_window.iter_mut() {
+        node.left = Val::Percent(left * 100.0);
+        node.width = Val::Percent(width * 100.0);
+    }
+}
+
+/// Fill each escaping saucer's gauge according to how close it is to getting away, so the player
+/// can tell which abduction to chase first.
+pub(crate) fn update_abduction_progress(
+    q_blips: Query<(&MinimapBlip, &Children)>,
+    mut q_gauges: Query<(&mut Visibility, &Children), With<AbductionProgress>>,
+    mut q_fills: Query<&mut Node, With<AbductionProgressFill>>,
+    q_saucers: Query<(&UnitPosition, &StateMachine<SaucerState>)>,
+) {
+    for (blip, children) in q_blips.iter() {
+        let progress = q_saucers
+            .get(blip.target)
+            .ok()
+            .filter(|(_, machine)| machine.state() == SaucerState::Escaping)
+            .map(|(position, _)| Saucer::escape_progress(position.0.y));
+        for child in children.iter() {
+            let Ok((mut visibility, fills)) = q_gauges.get_mut(child) else {
+                continue;
+            };
+            *visibility = if progress.is_some() {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+            for fill in fills.iter() {
+                if let Ok(mut node) = q_fills.get_mut(fill) {
+                    node.height = Val::Percent(progress.unwrap_or(0.0) * 100.0);
+                }
+            }
+        }
+    }
+}
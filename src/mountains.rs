@@ -10,7 +10,11 @@ use bevy::{
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 
-use crate::{MOUNTAINS_DEPTH, PLAYFIELD_WIDTH, Viewpoint};
+use crate::{
+    MOUNTAINS_DEPTH, Playfield, Viewpoint,
+    game_state::Level,
+    theme::{CurrentTheme, LevelTheme},
+};
 
 #[derive(Component, Default, Debug)]
 pub struct Mountains {
@@ -18,16 +22,39 @@ pub struct Mountains {
     speed: f32,
 }
 
+/// Spawn the mountain ranges, generated from the current level seed. Any existing mountains are
+/// despawned first, so this can be re-run when the playfield or level theme changes, or a new
+/// level starts.
 pub(crate) fn spawn_mountains(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<MountainMaterial>>,
+    q_mountains: Query<Entity, With<Mountains>>,
+    r_playfield: Res<Playfield>,
+    r_level: Res<Level>,
+    r_theme: Res<CurrentTheme>,
+    themes: Res<Assets<LevelTheme>>,
 ) {
-    // TODO: Seed this with the current level number
-    let mut rng = ChaCha8Rng::seed_from_u64(19878367467712);
+    for mountains in q_mountains.iter() {
+        commands.entity(mountains).despawn();
+    }
+
+    let mut rng = ChaCha8Rng::seed_from_u64(r_level.seed());
+
+    // Shape, snow, ridge highlights and glow are shared by all of the ranges.
+    let theme = r_theme.get(&themes).mountains;
+    let shape = theme.biome.shape();
+    let detail = MountainMaterialExt {
+        color_start: Vec4::ZERO,
+        color_end: Vec4::ZERO,
+        snow_color: theme.snow_color.to_srgba().to_vec4(),
+        ridge_color: theme.ridge_color.to_srgba().to_vec4(),
+        params: Vec4::new(theme.snow_line, SNOW_BLEND, 0.0, 0.0),
+        glow_color: theme.glow_color.to_srgba().to_vec4(),
+    };
 
     // Bottom mountains
-    let mountains = create_mountain_mesh(&mut rng);
+    let mountains = create_mountain_mesh(&mut rng, r_playfield.width, &shape);
     commands.spawn((
         Mesh3d(meshes.add(mountains)),
         MeshMaterial3d(materials.add(MountainMaterial {
@@ -38,6 +65,7 @@ pub(crate) fn spawn_mountains(
             extension: MountainMaterialExt {
                 color_start: Srgba::new(0.1, 0.1, 0.19, 1.0).to_vec4(),
                 color_end: Srgba::new(0.35, 0.35, 0.4, 1.0).to_vec4(),
+                ..detail.clone()
             },
         })),
         Transform::from_translation(Vec3::new(0.0, -0.55, MOUNTAINS_DEPTH + 0.11))
@@ -46,7 +74,7 @@ pub(crate) fn spawn_mountains(
     ));
 
     // Middle mountains
-    let mountains = create_mountain_mesh(&mut rng);
+    let mountains = create_mountain_mesh(&mut rng, r_playfield.width, &shape);
     commands.spawn((
         Mesh3d(meshes.add(mountains)),
         MeshMaterial3d(materials.add(MountainMaterial {
@@ -57,6 +85,7 @@ pub(crate) fn spawn_mountains(
             extension: MountainMaterialExt {
                 color_start: Srgba::new(0.06, 0.07, 0.18, 1.0).to_vec4(),
                 color_end: Srgba::new(0.18, 0.18, 0.25, 1.0).to_vec4(),
+                ..detail.clone()
             },
         })),
         Transform::from_translation(Vec3::new(0.0, -0.37, MOUNTAINS_DEPTH + 0.1))
@@ -65,7 +94,7 @@ pub(crate) fn spawn_mountains(
     ));
 
     // Top mountains
-    let mountains = create_mountain_mesh(&mut rng);
+    let mountains = create_mountain_mesh(&mut rng, r_playfield.width, &shape);
     commands.spawn((
         Mesh3d(meshes.add(mountains)),
         MeshMaterial3d(materials.add(MountainMaterial {
@@ -76,6 +105,7 @@ pub(crate) fn spawn_mountains(
             extension: MountainMaterialExt {
                 color_start: Srgba::new(0.05, 0.05, 0.15, 1.0).to_vec4(),
                 color_end: Srgba::new(0.08, 0.08, 0.2, 1.0).to_vec4(),
+                ..detail
             },
         })),
         Transform::from_translation(Vec3::new(0.0, -0.29, MOUNTAINS_DEPTH))
@@ -86,32 +116,64 @@ pub(crate) fn spawn_mountains(
 
 const NUM_SAMPLES: usize = 128;
 
-fn create_mountain_mesh(rng: &mut ChaCha8Rng) -> Mesh {
+/// Parameters controlling the outline of a mountain range.
+#[derive(Debug, Clone, Copy)]
+pub struct MountainShape {
+    /// Minimum height of the peaks
+    pub base: f32,
+
+    /// Random variation added to the height of the peaks
+    pub amplitude: f32,
+
+    /// Number of samples between peaks; smaller values give more frequent peaks. Rounded up to a
+    /// power of two.
+    pub peak_spacing: usize,
+
+    /// Random displacement of each subdivision between the peaks
+    pub roughness: f32,
+}
+
+impl Default for MountainShape {
+    fn default() -> Self {
+        Self {
+            base: 0.2,
+            amplitude: 0.1,
+            peak_spacing: 4,
+            roughness: 0.02,
+        }
+    }
+}
+
+/// Height over which the snow fades in above the snow line
+const SNOW_BLEND: f32 = 0.015;
+
+fn create_mountain_mesh(rng: &mut ChaCha8Rng, width: f32, shape: &MountainShape) -> Mesh {
     let mut mesh = Mesh::new(
         PrimitiveTopology::TriangleStrip,
         RenderAssetUsages::RENDER_WORLD,
     );
 
+    let spacing = shape.peak_spacing.clamp(1, NUM_SAMPLES).next_power_of_two();
     let mut height: Vec<f32> = Vec::with_capacity(NUM_SAMPLES);
     height.resize(NUM_SAMPLES + 1, 0.);
-    for i in (0..NUM_SAMPLES).step_by(4) {
-        height[i] = rng.random_range(0.2..0.3);
+    for i in (0..NUM_SAMPLES).step_by(spacing) {
+        height[i] = shape.base + rng.random_range(0.0..1.0) * shape.amplitude;
     }
     height[NUM_SAMPLES] = height[0];
 
-    fn gen_fract(height: &mut [f32], i0: usize, i1: usize, rng: &mut ChaCha8Rng) {
+    fn gen_fract(height: &mut [f32], i0: usize, i1: usize, roughness: f32, rng: &mut ChaCha8Rng) {
         let h0 = height[i0];
         let h1 = height[i1];
         let im = (i0 + i1) / 2;
-        height[im] = (h0 + h1) * 0.5 + rng.random_range(-0.02..0.02);
+        height[im] = (h0 + h1) * 0.5 + rng.random_range(-1.0..1.0) * roughness;
         if i1 > i0 + 1 {
-            gen_fract(height, i0, im, rng);
-            gen_fract(height, im, i1, rng);
+            gen_fract(height, i0, im, roughness, rng);
+            gen_fract(height, im, i1, roughness, rng);
         }
     }
 
-    for i in (0..NUM_SAMPLES).step_by(4) {
-        gen_fract(&mut height, i, i + 4, rng);
+    for i in (0..NUM_SAMPLES).step_by(spacing) {
+        gen_fract(&mut height, i, i + spacing, shape.roughness, rng);
     }
 
     // Remove last sample
@@ -121,28 +183,36 @@ fn create_mountain_mesh(rng: &mut ChaCha8Rng) -> Mesh {
     // wraparound seamlessly.
     let mut v_pos: Vec<[f32; 3]> = Vec::with_capacity(NUM_SAMPLES * 2);
     let mut v_uv: Vec<[f32; 2]> = Vec::with_capacity(NUM_SAMPLES * 2);
+    // The second UV set is 1.0 along the ridge line and 0.0 at the base, for ridge highlights.
+    let mut v_ridge: Vec<[f32; 2]> = Vec::with_capacity(NUM_SAMPLES * 2);
     for (i, h) in height.iter().enumerate() {
-        let x = i as f32 * PLAYFIELD_WIDTH / NUM_SAMPLES as f32;
+        let x = i as f32 * width / NUM_SAMPLES as f32;
         v_pos.push([x, *h, 0.0]);
         v_pos.push([x, 0.0, 0.0]);
         v_uv.push([x, *h]);
         v_uv.push([x, 0.0]);
+        v_ridge.push([0.0, 1.0]);
+        v_ridge.push([0.0, 0.0]);
     }
     for (i, h) in height.iter().enumerate() {
-        let x = i as f32 * PLAYFIELD_WIDTH / NUM_SAMPLES as f32;
-        v_pos.push([x + PLAYFIELD_WIDTH, *h, 0.0]);
-        v_pos.push([x + PLAYFIELD_WIDTH, 0.0, 0.0]);
-        v_uv.push([x + PLAYFIELD_WIDTH, *h]);
-        v_uv.push([x + PLAYFIELD_WIDTH, 0.0]);
+        let x = i as f32 * width / NUM_SAMPLES as f32;
+        v_pos.push([x + width, *h, 0.0]);
+        v_pos.push([x + width, 0.0, 0.0]);
+        v_uv.push([x + width, *h]);
+        v_uv.push([x + width, 0.0]);
+        v_ridge.push([0.0, 1.0]);
+        v_ridge.push([0.0, 0.0]);
     }
 
     mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, v_pos);
     mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, v_uv);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_1, v_ridge);
     mesh
 }
 
 pub(crate) fn update_mountains(
     r_viewpoint: Res<Viewpoint>,
+    r_playfield: Res<Playfield>,
     mut q_mountains: Query<(&Mountains, &mut Transform)>,
 ) {
     for (mtn, mut transform) in q_mountains.iter_mut() {
@@ -150,18 +220,29 @@ pub(crate) fn update_mountains(
         // then use modulo to implement wrap-around. Each range is initially scaled by its speed, so
         // that when the viewpoint moves by a full playfield width, each mountain range will have
         // moved by its own width.
-        let dist_traveled = PLAYFIELD_WIDTH * mtn.speed;
+        let dist_traveled = r_playfield.width * mtn.speed;
         transform.translation.x =
             (-r_viewpoint.position * mtn.speed).rem_euclid(dist_traveled) - dist_traveled * 1.5;
     }
 }
 
-#[derive(AsBindGroup, Asset, TypePath, Debug, Clone)]
+#[derive(AsBindGroup, Asset, Reflect, Debug, Clone)]
 pub(crate) struct MountainMaterialExt {
     #[uniform(100)]
     pub(crate) color_start: Vec4,
     #[uniform(101)]
     pub(crate) color_end: Vec4,
+    #[uniform(102)]
+    pub(crate) snow_color: Vec4,
+    /// Alpha controls the strength of the highlight
+    #[uniform(103)]
+    pub(crate) ridge_color: Vec4,
+    /// x: snow line height, y: snow blend height
+    #[uniform(104)]
+    pub(crate) params: Vec4,
+    /// Pulsing glow around the peaks, e.g. for volcanoes; alpha controls its strength
+    #[uniform(105)]
+    pub(crate) glow_color: Vec4,
 }
 
 impl MaterialExtension for MountainMaterialExt {
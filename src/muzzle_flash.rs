@@ -0,0 +1,112 @@
+//! Muzzle flashes: a brief burst of light at the mouth of each gun when the ship fires.
+use bevy::prelude::*;
+
+use crate::{
+    FX_DEPTH, UnitPosition,
+    game_state::{DespawnOnExit, GameState},
+    ship::{Facing, PlayerShip},
+};
+
+/// A muzzle flash, which sticks to the gun that fired it.
+#[derive(Component, Debug)]
+pub struct MuzzleFlash {
+    /// Gun mount offset, relative to the ship position when facing right
+    mount: Vec2,
+
+    /// Direction the gun was facing
+    facing: Facing,
+
+    /// Time remaining
+    timer: f32,
+}
+
+impl MuzzleFlash {
+    pub fn new(mount: Vec2, facing: Facing) -> Self {
+        Self {
+            mount,
+            facing,
+            timer: FLASH_TIME,
+        }
+    }
+
+    /// Offset of the flash from the ship position.
+    fn offset(&self) -> Vec2 {
+        match self.facing {
+            Facing::Right => self.mount,
+            Facing::Left => Vec2::new(-self.mount.x, self.mount.y),
+        }
+    }
+}
+
+/// Shared mesh and material for muzzle flashes
+#[derive(Resource, Default, Debug)]
+pub struct MuzzleFlashAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+/// How long a muzzle flash lasts, in seconds
+const FLASH_TIME: f32 = 0.06;
+
+/// Size of a muzzle flash, which is longer than it is wide
+const FLASH_SIZE: Vec2 = Vec2::new(0.05, 0.02);
+
+pub(crate) fn setup_muzzle_flash(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    mut r_assets: ResMut<MuzzleFlashAssets>,
+) {
+    // The mesh starts at the muzzle and extends forwards, so that it can be flipped for
+    // left-facing guns by rotating it.
+    r_assets.mesh = meshes.add(
+        Mesh::from(Rectangle::from_size(FLASH_SIZE)).translated_by(Vec3::X * FLASH_SIZE.x * 0.5),
+    );
+    r_assets.material = materials.add(StandardMaterial {
+        base_color: Color::srgb(1.0, 0.85, 0.6),
+        base_color_texture: Some(asset_server.load("textures/glowspark.png")),
+        alpha_mode: AlphaMode::Add,
+        unlit: true,
+        ..default()
+    });
+}
+
+pub(crate) fn on_add_muzzle_flash(
+    trigger: Trigger<OnAdd, MuzzleFlash>,
+    mut commands: Commands,
+    q_flashes: Query<&MuzzleFlash>,
+    r_assets: Res<MuzzleFlashAssets>,
+) {
+    let Ok(flash) = q_flashes.get(trigger.target()) else {
+        return;
+    };
+    let rotation = match flash.facing {
+        Facing::Right => Quat::IDENTITY,
+        Facing::Left => Quat::from_rotation_z(std::f32::consts::PI),
+    };
+    commands.entity(trigger.target()).insert((
+        Mesh3d(r_assets.mesh.clone()),
+        MeshMaterial3d(r_assets.material.clone()),
+        Transform::from_xyz(0., 0., FX_DEPTH).with_rotation(rotation),
+        DespawnOnExit(GameState::Playing),
+    ));
+}
+
+/// Muzzle flashes follow the ship, and shrink away.
+pub(crate) fn update_muzzle_flash(
+    mut commands: Commands,
+    mut q_flashes: Query<(Entity, &mut MuzzleFlash, &mut UnitPosition, &mut Transform)>,
+    q_player: Query<&UnitPosition, (With<PlayerShip>, Without<MuzzleFlash>)>,
+    r_time: Res<Time>,
+) {
+    let ship_pos = q_player.single().ok();
+    for (entity, mut flash, mut position, mut transform) in q_flashes.iter_mut() {
+        flash.timer -= r_time.delta_secs();
+        let Some(ship_pos) = ship_pos.filter(|_| flash.timer > 0.0) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        position.0 = ship_pos.0 + flash.offset();
+        transform.scale = Vec3::splat(flash.timer / FLASH_TIME);
+    }
+}
@@ -0,0 +1,123 @@
+//! Ambient lightning flashes inside the nebula backdrop
+use std::ops::Range;
+
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+};
+use rand::Rng;
+
+use crate::{NEBULA_DEPTH, RandomGenerator, low_power::BackgroundClock, settings::Settings};
+
+/// A lightning flash glowing inside the nebula
+#[derive(Component, Default, Debug)]
+pub struct LightningFlash {
+    /// Time since the flash started
+    elapsed: f32,
+
+    /// Whether the rumble has been played yet. Like thunder, it arrives a little after the flash.
+    rumbled: bool,
+}
+
+/// Countdown to the next lightning flash
+#[derive(Resource, Debug)]
+pub struct NebulaLightning {
+    timer: f32,
+}
+
+impl Default for NebulaLightning {
+    fn default() -> Self {
+        Self {
+            timer: LIGHTNING_INTERVAL.start,
+        }
+    }
+}
+
+/// Time between flashes, in seconds
+const LIGHTNING_INTERVAL: Range<f32> = 6.0..20.0;
+
+/// How long a flash lasts
+const FLASH_DURATION: f32 = 0.8;
+
+/// Delay between the flash and the rumble
+const RUMBLE_DELAY: f32 = 0.4;
+
+/// Spawn lightning flashes at random intervals and positions within the nebula.
+pub(crate) fn spawn_nebula_lightning(
+    mut commands: Commands,
+    mut r_lightning: ResMut<NebulaLightning>,
+    mut r_rng: ResMut<RandomGenerator>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    r_settings: Res<Settings>,
+    r_clock: Res<BackgroundClock>,
+) {
+    r_lightning.timer -= r_clock.delta_secs();
+    if r_lightning.timer > 0.0 {
+        return;
+    }
+    r_lightning.timer =
+        r_rng.0.random_range(LIGHTNING_INTERVAL) / r_settings.graphics.background_frequency();
+
+    let position = Vec3::new(
+        r_rng.0.random_range(-1.2..1.2),
+        r_rng.0.random_range(-0.2..0.5),
+        NEBULA_DEPTH + 1.0,
+    );
+    let size = r_rng.0.random_range(0.3..0.7);
+    commands.spawn((
+        Mesh3d(meshes.add(Rectangle::new(size, size))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::NONE,
+            base_color_texture: Some(asset_server.load("textures/glowspark.png")),
+            alpha_mode: AlphaMode::Add,
+            unlit: true,
+            ..default()
+        })),
+        Transform::from_translation(position),
+        LightningFlash::default(),
+    ));
+}
+
+/// Flicker the flash and fade it out, and play the rumble.
+pub(crate) fn update_nebula_lightning(
+    mut commands: Commands,
+    mut q_flashes: Query<(
+        Entity,
+        &mut LightningFlash,
+        &MeshMaterial3d<StandardMaterial>,
+    )>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    r_clock: Res<BackgroundClock>,
+) {
+    for (entity, mut flash, material) in q_flashes.iter_mut() {
+        flash.elapsed += r_clock.delta_secs();
+        if flash.elapsed >= FLASH_DURATION {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        if !flash.rumbled && flash.elapsed >= RUMBLE_DELAY {
+            flash.rumbled = true;
+            commands.spawn((
+                AudioPlayer::new(asset_server.load("sounds/rattle.ogg")),
+                PlaybackSettings {
+                    mode: PlaybackMode::Despawn,
+                    volume: Volume::Linear(0.3),
+                    speed: 0.4,
+                    ..default()
+                },
+            ));
+        }
+
+        // A couple of quick flickers, decaying
+        let flicker = 0.6 + 0.4 * (flash.elapsed * 60.0).sin();
+        let intensity = (-flash.elapsed * 6.0).exp() * flicker;
+        if let Some(material) = materials.get_mut(material.id()) {
+            material.base_color =
+                LinearRgba::new(0.6 * intensity, 0.5 * intensity, intensity, 1.0).into();
+        }
+    }
+}
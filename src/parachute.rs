@@ -0,0 +1,127 @@
+//! Parachutes: with the parachute mutator in effect, treasure dropped by a destroyed saucer
+//! opens a small parachute and drifts down slowly, giving the player more time to catch it. The
+//! parachute detaches when the treasure is caught or lands, and floats away.
+use bevy::prelude::*;
+
+use crate::{
+    TREASURE_DEPTH, UnitPosition,
+    game_state::{DespawnOnExit, GameState},
+};
+
+/// Falling treasure with an open parachute. The canopy is a separate entity, since the treasure
+/// model is scaled and tilted.
+#[derive(Component, Debug)]
+pub struct Parachute {
+    canopy: Entity,
+}
+
+/// Parachute canopy. Once detached from its treasure, it drifts away and shrinks out of sight.
+#[derive(Component, Default, Debug)]
+pub struct ParachuteCanopy {
+    /// Time since the canopy detached, or `None` while still attached
+    detached: Option<f32>,
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct ParachuteMesh {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+/// Slowest speed at which treasure falls under a parachute. This is well below the speed at
+/// which treasure is destroyed on landing.
+pub const PARACHUTE_FALL_SPEED: f32 = 0.06;
+
+/// Height of the canopy above the treasure
+const CANOPY_OFFSET: f32 = 0.05;
+
+/// Speed at which a detached canopy drifts away
+const DRIFT_VELOCITY: Vec2 = Vec2::new(0.04, 0.06);
+
+/// Time a detached canopy takes to disappear, in seconds
+const DRIFT_TIME: f32 = 1.2;
+
+pub(crate) fn setup_parachutes(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut r_mesh: ResMut<ParachuteMesh>,
+) {
+    r_mesh.mesh = meshes.add(Cone::new(0.035, 0.018).mesh().resolution(12));
+    r_mesh.material = materials.add(StandardMaterial {
+        base_color: Color::srgb(1.0, 0.5, 0.2),
+        emissive: LinearRgba::new(0.4, 0.15, 0.05, 1.0),
+        ..default()
+    });
+}
+
+/// Make a treasure fall by parachute. The canopy is spawned here; [`update_treasure`] limits the
+/// fall speed.
+///
+/// [`update_treasure`]: crate::treasure::update_treasure
+pub fn open_parachute(
+    commands: &mut Commands,
+    treasure: Entity,
+    position: Vec2,
+    r_mesh: &ParachuteMesh,
+) {
+    let canopy = commands
+        .spawn((
+            ParachuteCanopy::default(),
+            UnitPosition(position + Vec2::Y * CANOPY_OFFSET),
+            Mesh3d(r_mesh.mesh.clone()),
+            MeshMaterial3d(r_mesh.material.clone()),
+            Transform::from_xyz(0., 0., TREASURE_DEPTH),
+            DespawnOnExit(GameState::Playing),
+        ))
+        .id();
+    commands.entity(treasure).insert(Parachute { canopy });
+}
+
+/// Detach the canopy when the parachute is removed from its treasure, either because it landed
+/// or because the treasure was caught or destroyed.
+pub(crate) fn on_remove_parachute(
+    trigger: Trigger<OnRemove, Parachute>,
+    q_parachutes: Query<&Parachute>,
+    mut q_canopies: Query<&mut ParachuteCanopy>,
+) {
+    let Ok(parachute) = q_parachutes.get(trigger.target()) else {
+        return;
+    };
+    if let Ok(mut canopy) = q_canopies.get_mut(parachute.canopy) {
+        canopy.detached = Some(0.0);
+    }
+}
+
+/// Keep attached canopies above their treasure, and float detached canopies away.
+pub(crate) fn update_parachutes(
+    mut commands: Commands,
+    q_treasures: Query<(&Parachute, &UnitPosition), Without<ParachuteCanopy>>,
+    mut q_canopies: Query<(
+        Entity,
+        &mut ParachuteCanopy,
+        &mut UnitPosition,
+        &mut Transform,
+    )>,
+    r_time: Res<Time>,
+) {
+    for (parachute, treasure_position) in q_treasures.iter() {
+        if let Ok((_, _, mut position, _)) = q_canopies.get_mut(parachute.canopy) {
+            position.0 = treasure_position.0 + Vec2::Y * CANOPY_OFFSET;
+        }
+    }
+
+    let dt = r_time.delta_secs();
+    for (entity, mut canopy, mut position, mut transform) in q_canopies.iter_mut() {
+        let Some(elapsed) = canopy.detached.as_mut() else {
+            continue;
+        };
+        *elapsed += dt;
+        if *elapsed >= DRIFT_TIME {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        position.0 += DRIFT_VELOCITY * dt;
+        transform.scale = Vec3::splat(1.0 - *elapsed / DRIFT_TIME);
+        transform.rotation = Quat::from_rotation_z(-*elapsed);
+    }
+}
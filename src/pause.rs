@@ -0,0 +1,398 @@
+//! Pausing the game, and the options menu shown while paused
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+
+use crate::{
+    game_state::{DespawnOnExit, Level, PauseState},
+    hud::{MAX_HUD_SCALE, MIN_HUD_SCALE},
+    input_glyphs::{ButtonPrompt, InputDevice, PromptButton},
+    menu_input::MenuInput,
+    settings::{MAX_EXTRA_LIVES, MIN_GAME_SPEED, MinimapPosition, MinimapStyle, Settings},
+    ui_audio::UiSound,
+};
+
+/// Marker for the pause overlay
+#[derive(Component, Default, Debug)]
+pub struct PauseMenu;
+
+/// An entry in the pause menu. Every setting which has a pause menu shortcut key can also be
+/// changed here, so that the menu can be used with a gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseOption {
+    Resume,
+    HudScale,
+    MinimapPosition,
+    ShowMinimap,
+    MinimapStyle,
+    HitIndicators,
+    ShieldAlert,
+    RunTimer,
+    DamageNumbers,
+    Ticker,
+    GraphicsQuality,
+    FrameLimit,
+    LowPower,
+    SkillLevel,
+    Adaptive,
+    Challenge,
+    Announcer,
+    GameSpeed,
+    ExtraLives,
+    AutoFire,
+    LaserHeat,
+}
+
+impl PauseOption {
+    pub const ALL: [PauseOption; 21] = [
+        PauseOption::Resume,
+        PauseOption::HudScale,
+        PauseOption::MinimapPosition,
+        PauseOption::ShowMinimap,
+        PauseOption::MinimapStyle,
+        PauseOption::HitIndicators,
+        PauseOption::ShieldAlert,
+        PauseOption::RunTimer,
+        PauseOption::DamageNumbers,
+        PauseOption::Ticker,
+        PauseOption::GraphicsQuality,
+        PauseOption::FrameLimit,
+        PauseOption::LowPower,
+        PauseOption::SkillLevel,
+        PauseOption::Adaptive,
+        PauseOption::Challenge,
+        PauseOption::Announcer,
+        PauseOption::GameSpeed,
+        PauseOption::ExtraLives,
+        PauseOption::AutoFire,
+        PauseOption::LaserHeat,
+    ];
+
+    /// Text of the menu entry, showing the current value of the setting
+    fn text(self, settings: &Settings) -> String {
+        let on_off = |on: bool| if on { "On" } else { "Off" };
+        let (hud, graphics, gameplay, assist) = (
+            &settings.hud,
+            &settings.graphics,
+            &settings.gameplay,
+            &settings.assist,
+        );
+        match self {
+            PauseOption::Resume => "Resume".to_string(),
+            PauseOption::HudScale => format!("HUD scale: {:.0}%", hud.scale * 100.0),
+            PauseOption::MinimapPosition => format!(
+                "Minimap position: {}",
+                match hud.minimap_position {
+                    MinimapPosition::Top => "Top",
+                    MinimapPosition::Bottom => "Bottom",
+                }
+            ),
+            PauseOption::ShowMinimap => format!("Minimap: {}", on_off(hud.show_minimap)),
+            PauseOption::MinimapStyle => format!(
+                "Minimap style: {}",
+                match hud.minimap_style {
+                    MinimapStyle::Blips => "Blips",
+                    MinimapStyle::Scanner => "Scanner",
+                }
+            ),
+            PauseOption::HitIndicators => {
+                format!("Hit indicators: {}", on_off(hud.show_hit_indicators))
+            }
+            PauseOption::ShieldAlert => format!("Shield alert: {}", on_off(hud.show_shield_alert)),
+            PauseOption::RunTimer => format!("Run timer: {}", on_off(hud.show_timer)),
+            PauseOption::DamageNumbers => {
+                format!("Damage numbers: {}", on_off(hud.show_damage_numbers))
+            }
+            PauseOption::Ticker => format!("Event ticker: {}", on_off(hud.show_ticker)),
+            PauseOption::GraphicsQuality => {
+                format!("Graphics quality: {}", graphics.quality.name())
+            }
+            PauseOption::FrameLimit => format!("Frame limit: {}", graphics.frame_limit.name()),
+            PauseOption::LowPower => format!("Low-power mode: {}", on_off(graphics.low_power)),
+            PauseOption::SkillLevel => format!("Skill level: {}", gameplay.skill.name()),
+            PauseOption::Adaptive => {
+                format!("Adaptive difficulty: {}", on_off(gameplay.adaptive))
+            }
+            PauseOption::Challenge => format!("Challenge: {:.1}", gameplay.challenge),
+            PauseOption::Announcer => format!("Announcer: {}", on_off(settings.audio.announcer)),
+            PauseOption::GameSpeed => format!("Game speed: {:.0}%", assist.game_speed * 100.0),
+            PauseOption::ExtraLives => format!("Extra lives: {}", assist.extra_lives),
+            PauseOption::AutoFire => format!("Auto-fire: {}", on_off(assist.auto_fire)),
+            PauseOption::LaserHeat => format!("Laser heat: {}", on_off(assist.laser_heat)),
+        }
+    }
+
+    /// Change the setting. `step` is 1 or -1; numeric settings are raised or lowered, and the
+    /// others are toggled or cycled either way.
+    fn adjust(self, settings: &mut Settings, step: f32) {
+        let (hud, graphics, gameplay, assist) = (
+            &mut settings.hud,
+            &mut settings.graphics,
+            &mut settings.gameplay,
+            &mut settings.assist,
+        );
+        match self {
+            PauseOption::Resume => {}
+            PauseOption::HudScale => {
+                hud.scale = (hud.scale + step * 0.1).clamp(MIN_HUD_SCALE, MAX_HUD_SCALE);
+            }
+            PauseOption::MinimapPosition => {
+                hud.minimap_position = match hud.minimap_position {
+                    MinimapPosition::Top => MinimapPosition::Bottom,
+                    MinimapPosition::Bottom => MinimapPosition::Top,
+                };
+            }
+            PauseOption::ShowMinimap => hud.show_minimap = !hud.show_minimap,
+            PauseOption::MinimapStyle => {
+                hud.minimap_style = match hud.minimap_style {
+                    MinimapStyle::Blips => MinimapStyle::Scanner,
+                    MinimapStyle::Scanner => MinimapStyle::Blips,
+                };
+            }
+            PauseOption::HitIndicators => hud.show_hit_indicators = !hud.show_hit_indicators,
+            PauseOption::ShieldAlert => hud.show_shield_alert = !hud.show_shield_alert,
+            PauseOption::RunTimer => hud.show_timer = !hud.show_timer,
+            PauseOption::DamageNumbers => hud.show_damage_numbers = !hud.show_damage_numbers,
+            PauseOption::Ticker => hud.show_ticker = !hud.show_ticker,
+            PauseOption::GraphicsQuality => graphics.quality = graphics.quality.next(),
+            PauseOption::FrameLimit => graphics.frame_limit = graphics.frame_limit.next(),
+            PauseOption::LowPower => graphics.low_power = !graphics.low_power,
+            PauseOption::SkillLevel => gameplay.skill = gameplay.skill.next(),
+            PauseOption::Adaptive => gameplay.adaptive = !gameplay.adaptive,
+            PauseOption::Challenge => {
+                gameplay.challenge = (gameplay.challenge + step * 0.1).clamp(0.0, 1.0);
+            }
+            PauseOption::Announcer => settings.audio.announcer = !settings.audio.announcer,
+            PauseOption::GameSpeed => {
+                assist.game_speed = (assist.game_speed + step * 0.1).clamp(MIN_GAME_SPEED, 1.0);
+            }
+            PauseOption::ExtraLives => {
+                let count = MAX_EXTRA_LIVES + 1;
+                assist.extra_lives = if step > 0.0 {
+                    (assist.extra_lives + 1) % count
+                } else {
+                    (assist.extra_lives + count - 1) % count
+                };
+            }
+            PauseOption::AutoFire => assist.auto_fire = !assist.auto_fire,
+            PauseOption::LaserHeat => assist.laser_heat = !assist.laser_heat,
+        }
+    }
+}
+
+/// A pause menu entry
+#[derive(Component, Debug)]
+pub struct PauseOptionRow(PauseOption);
+
+/// Index of the focused pause menu entry. This is kept between pauses.
+#[derive(Resource, Default, Debug)]
+pub struct PauseFocus(usize);
+
+/// While set, the game clock stays stopped even though the game is running. This is how the
+/// frame-step debug tool freezes the game without bringing up the pause menu.
+#[derive(Resource, Default, Debug)]
+pub struct ClockHold(pub bool);
+
+const TITLE_COLOR: Color = Color::srgb(0.3, 0.6, 1.0);
+const OPTION_COLOR: Color = Color::srgb(0.5, 0.5, 0.6);
+const FOCUS_COLOR: Color = Color::srgb(0.2, 1.0, 0.2);
+const HINT_COLOR: Color = Color::srgb(0.4, 0.4, 0.5);
+
+/// Toggle pause with the P or Escape keys, or the start button.
+pub(crate) fn toggle_pause(
+    mut commands: Commands,
+    r_menu: Res<MenuInput>,
+    r_state: Res<State<PauseState>>,
+    mut next_state: ResMut<NextState<PauseState>>,
+) {
+    if !r_menu.pause {
+        return;
+    }
+    match r_state.get() {
+        PauseState::Running => {
+            next_state.set(PauseState::Paused);
+            commands.trigger(UiSound::Click);
+        }
+        PauseState::Paused => {
+            next_state.set(PauseState::Running);
+            commands.trigger(UiSound::Back);
+        }
+        _ => {}
+    }
+}
+
+/// The game clock is `Time<Virtual>`, which is what `Res<Time>` resolves to in `Update`. All
+/// simulation systems (movement, AI, effects) read it, so they freeze whenever the clock stops.
+/// UI animations which should keep playing while paused read `Time<Real>` instead.
+///
+/// The clock runs only while the game is running: it stops when paused, in photo mode and after
+/// game over, and restarts whenever `PauseState` goes away, such as when returning to the menu.
+/// A [`ClockHold`] keeps it stopped regardless.
+pub(crate) fn sync_game_clock(
+    r_pause: Option<Res<State<PauseState>>>,
+    r_hold: Res<ClockHold>,
+    mut r_time: ResMut<Time<Virtual>>,
+) {
+    let running = !r_hold.0 && r_pause.is_none_or(|state| *state.get() == PauseState::Running);
+    if running && r_time.is_paused() {
+        r_time.unpause();
+    } else if !running && !r_time.is_paused() {
+        r_time.pause();
+    }
+}
+
+pub(crate) fn spawn_pause_menu(
+    mut commands: Commands,
+    r_level: Res<Level>,
+    r_settings: Res<Settings>,
+) {
+    let rows: Vec<_> = PauseOption::ALL
+        .into_iter()
+        .map(|option| (option, option.text(&r_settings)))
+        .collect();
+    commands.spawn((
+        DespawnOnExit(PauseState::Paused),
+        Node {
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(12.0),
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            right: Val::Px(0.0),
+            top: Val::Px(0.0),
+            bottom: Val::Px(0.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+        PauseMenu,
+        Children::spawn((
+            Spawn((
+                Text::new("PAUSED"),
+                TextFont {
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(TITLE_COLOR),
+            )),
+            Spawn((
+                Text::new(format!("{}   Seed {}", r_level.name(), r_level.run_seed)),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.8)),
+            )),
+            Spawn((
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(2.0),
+                    ..default()
+                },
+                Children::spawn(SpawnIter(rows.into_iter().map(|(option, text)| {
+                    (
+                        Text::new(text),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(OPTION_COLOR),
+                        PauseOptionRow(option),
+                    )
+                }))),
+            )),
+            Spawn((
+                Text::default(),
+                ButtonPrompt(menu_prompt),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(HINT_COLOR),
+            )),
+            Spawn((
+                Text::new(
+                    "Shortcuts: - / = HUD scale, M move minimap, H hide minimap, \
+                     I hit indicators, K shield alert, T run timer, U damage numbers, J ticker, \
+                     Y scanner. G graphics quality, V frame limit, B low power, L skill level, \
+                     A adaptive difficulty, [ / ] challenge, O announcer. Assists: Z / X game \
+                     speed, E extra lives, Q auto-fire, W laser heat",
+                ),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(HINT_COLOR),
+                Node {
+                    max_width: Val::Px(800.0),
+                    ..default()
+                },
+            )),
+        )),
+    ));
+}
+
+fn menu_prompt(device: InputDevice) -> String {
+    let prompt = format!(
+        "{} up / down to choose, left / right or {} to change, {} to resume",
+        device.glyph(PromptButton::Navigate),
+        device.glyph(PromptButton::Confirm),
+        device.glyph(PromptButton::Pause),
+    );
+    // Photo mode is only available from the keyboard.
+    if device.is_gamepad() {
+        prompt
+    } else {
+        prompt + ", F for photo mode"
+    }
+}
+
+/// Move the focus through the pause menu, and change the focused setting. The focused entry is
+/// highlighted and marked with an arrow.
+pub(crate) fn update_pause_menu(
+    mut commands: Commands,
+    mut q_rows: Query<(&PauseOptionRow, &mut Text, &mut TextColor)>,
+    r_menu: Res<MenuInput>,
+    mut r_focus: ResMut<PauseFocus>,
+    mut r_settings: ResMut<Settings>,
+    mut next_state: ResMut<NextState<PauseState>>,
+) {
+    let count = PauseOption::ALL.len();
+    if r_menu.up {
+        r_focus.0 = (r_focus.0 + count - 1) % count;
+        commands.trigger(UiSound::Hover);
+    } else if r_menu.down {
+        r_focus.0 = (r_focus.0 + 1) % count;
+        commands.trigger(UiSound::Hover);
+    }
+
+    let focused = PauseOption::ALL[r_focus.0.min(count - 1)];
+    if focused == PauseOption::Resume {
+        if r_menu.confirm {
+            next_state.set(PauseState::Running);
+            commands.trigger(UiSound::Back);
+        }
+    } else if r_menu.left {
+        focused.adjust(&mut r_settings, -1.0);
+        commands.trigger(UiSound::Click);
+    } else if r_menu.right || r_menu.confirm {
+        focused.adjust(&mut r_settings, 1.0);
+        commands.trigger(UiSound::Click);
+    }
+    if r_menu.back {
+        next_state.set(PauseState::Running);
+        commands.trigger(UiSound::Back);
+    }
+
+    for (row, mut text, mut color) in q_rows.iter_mut() {
+        let label = row.0.text(&r_settings);
+        let (label, row_color) = if row.0 == focused {
+            (format!("> {label} <"), FOCUS_COLOR)
+        } else {
+            (label, OPTION_COLOR)
+        };
+        if text.0 != label {
+            text.0 = label;
+        }
+        color.set_if_neq(TextColor(row_color));
+    }
+}
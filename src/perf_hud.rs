@@ -0,0 +1,172 @@
+//! Performance overlay, only built with the `debug` feature. Toggle with F4.
+//!
+//! Shows frame time, plus wall-clock time spent in the main groups of game systems. Each group is
+//! timed from a marker system placed before it to one placed after it, so the figures include
+//! anything else which runs in parallel; they are a guide to where the time goes rather than
+//! exact per-system costs. Rendering is estimated as the frame time not spent in the main world.
+use bevy::{
+    diagnostic::{
+        Diagnostic, DiagnosticPath, Diagnostics, DiagnosticsStore, FrameTimeDiagnosticsPlugin,
+        RegisterDiagnostic,
+    },
+    platform::{collections::HashMap, time::Instant},
+    prelude::*,
+};
+
+use avian2d::prelude::{PhysicsSchedule, PhysicsStepSet};
+
+use crate::{EffectSet, EnemySet, PlayerSet};
+
+pub const MAIN_TIME: DiagnosticPath = DiagnosticPath::const_new("guardian/main");
+pub const MOVEMENT_TIME: DiagnosticPath = DiagnosticPath::const_new("guardian/movement");
+pub const EFFECTS_TIME: DiagnosticPath = DiagnosticPath::const_new("guardian/effects");
+pub const PHYSICS_TIME: DiagnosticPath = DiagnosticPath::const_new("guardian/physics");
+
+/// Groups which are timed, with their display names
+const TIMED: [(&str, DiagnosticPath); 4] = [
+    ("Main world", MAIN_TIME),
+    ("Movement & AI", MOVEMENT_TIME),
+    ("Effects", EFFECTS_TIME),
+    ("Physics", PHYSICS_TIME),
+];
+
+/// Time accumulated by each group during the current frame. Physics may step several times in
+/// one frame, or not at all.
+#[derive(Resource, Default, Debug)]
+struct PerfTimers {
+    started: HashMap<DiagnosticPath, Instant>,
+    total: HashMap<DiagnosticPath, f64>,
+}
+
+/// Whether the overlay is shown
+#[derive(Resource, Default, Debug)]
+pub struct PerfHudVisible(pub bool);
+
+/// Marker for the overlay text
+#[derive(Component, Default, Debug)]
+pub struct PerfHud;
+
+pub struct PerfHudPlugin;
+
+impl Plugin for PerfHudPlugin {
+    fn build(&self, app: &mut App) {
+        for (_, path) in TIMED {
+            app.register_diagnostic(Diagnostic::new(path).with_suffix("ms"));
+        }
+        app.add_plugins(FrameTimeDiagnosticsPlugin::default())
+            .init_resource::<PerfTimers>()
+            .init_resource::<PerfHudVisible>()
+            .add_systems(Startup, spawn_perf_hud)
+            .add_systems(First, begin(MAIN_TIME))
+            .add_systems(
+                Update,
+                (
+                    begin(MOVEMENT_TIME).before(PlayerSet),
+                    end(MOVEMENT_TIME).after(EnemySet).before(EffectSet),
+                    begin(EFFECTS_TIME).before(EffectSet).after(EnemySet),
+                    end(EFFECTS_TIME).after(EffectSet),
+                    toggle_perf_hud,
+                    update_perf_hud.run_if(|r_visible: Res<PerfHudVisible>| r_visible.0),
+                ),
+            )
+            .add_systems(
+                PhysicsSchedule,
+                begin(PHYSICS_TIME).in_set(PhysicsStepSet::First),
+            )
+            .add_systems(
+                PhysicsSchedule,
+                end(PHYSICS_TIME).in_set(PhysicsStepSet::Last),
+            )
+            .add_systems(Last, (end(MAIN_TIME), record_timers).chain());
+    }
+}
+
+fn begin(path: DiagnosticPath) -> impl FnMut(ResMut<PerfTimers>) {
+    move |mut r_timers: ResMut<PerfTimers>| {
+        r_timers.started.insert(path.clone(), Instant::now());
+    }
+}
+
+fn end(path: DiagnosticPath) -> impl FnMut(ResMut<PerfTimers>) {
+    move |mut r_timers: ResMut<PerfTimers>| {
+        let Some(start) = r_timers.started.remove(&path) else {
+            return;
+        };
+        *r_timers.total.entry(path.clone()).or_default() += start.elapsed().as_secs_f64() * 1000.0;
+    }
+}
+
+/// Report this frame's totals to the diagnostics store.
+fn record_timers(mut r_timers: ResMut<PerfTimers>, mut diagnostics: Diagnostics) {
+    for (_, path) in TIMED {
+        let total = r_timers.total.remove(&path).unwrap_or(0.0);
+        diagnostics.add_measurement(&path, || total);
+    }
+}
+
+fn spawn_perf_hud(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(12.0),
+            top: Val::Px(110.0),
+            padding: UiRect::all(Val::Px(6.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        Text::default(),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.6, 1.0, 0.6)),
+        Visibility::Hidden,
+        GlobalZIndex(10),
+        PerfHud,
+    ));
+}
+
+fn toggle_perf_hud(
+    r_keys: Res<ButtonInput<KeyCode>>,
+    mut r_visible: ResMut<PerfHudVisible>,
+    mut q_hud: Query<&mut Visibility, With<PerfHud>>,
+) {
+    if !r_keys.just_pressed(KeyCode::F4) {
+        return;
+    }
+    r_visible.0 = !r_visible.0;
+    for mut visibility in q_hud.iter_mut() {
+        *visibility = if r_visible.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn update_perf_hud(mut q_hud: Query<&mut Text, With<PerfHud>>, r_store: Res<DiagnosticsStore>) {
+    let smoothed = |path: &DiagnosticPath| {
+        r_store
+            .get(path)
+            .and_then(|diagnostic| diagnostic.smoothed())
+            .unwrap_or(0.0)
+    };
+    let frame = smoothed(&FrameTimeDiagnosticsPlugin::FRAME_TIME);
+    let mut lines = vec![format!(
+        "Frame: {:.2} ms ({:.0} fps)",
+        frame,
+        smoothed(&FrameTimeDiagnosticsPlugin::FPS)
+    )];
+    for (name, path) in TIMED {
+        lines.push(format!("{}: {:.2} ms", name, smoothed(&path)));
+    }
+    lines.push(format!(
+        "Rendering & other: {:.2} ms",
+        (frame - smoothed(&MAIN_TIME)).max(0.0)
+    ));
+
+    let text = lines.join("\n");
+    for mut hud in q_hud.iter_mut() {
+        hud.0.clone_from(&text);
+    }
+}
@@ -0,0 +1,168 @@
+//! Photo mode: while paused, detach the playfield camera so the scene can be framed freely.
+use bevy::{
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll},
+    prelude::*,
+    render::camera::{PerspectiveProjection, Projection},
+};
+
+use crate::{Hud, PlayfieldCamera, game_state::PauseState, ui_audio::UiSound};
+
+/// Camera pan speed, in world units per second
+const PAN_SPEED: f32 = 0.6;
+
+/// Camera roll speed, in radians per second
+const ROLL_SPEED: f32 = 1.0;
+
+/// Camera rotation per pixel of mouse motion, in radians
+const LOOK_SPEED: f32 = 0.005;
+
+/// Zoom factor per second (keyboard) or per scroll line (mouse wheel)
+const ZOOM_RATE: f32 = 1.5;
+
+/// Vertical field of view used for the perspective view. At the default camera distance this
+/// frames the same height as the ortho view.
+const PERSPECTIVE_FOV: f32 = 0.49;
+
+/// Playfield camera settings saved on entry to photo mode, restored on exit.
+#[derive(Resource, Debug)]
+pub struct PhotoMode {
+    saved_transform: Transform,
+    saved_projection: Projection,
+}
+
+/// Enter photo mode from the pause menu, and leave it again.
+pub(crate) fn toggle_photo_mode(
+    mut commands: Commands,
+    r_keys: Res<ButtonInput<KeyCode>>,
+    r_state: Res<State<PauseState>>,
+    mut next_state: ResMut<NextState<PauseState>>,
+) {
+    match r_state.get() {
+        PauseState::Paused if r_keys.just_pressed(KeyCode::KeyF) => {
+            next_state.set(PauseState::PhotoMode);
+            commands.trigger(UiSound::Confirm);
+        }
+        PauseState::PhotoMode if r_keys.any_just_pressed([KeyCode::KeyF, KeyCode::Escape]) => {
+            next_state.set(PauseState::Paused);
+            commands.trigger(UiSound::Back);
+        }
+        _ => {}
+    }
+}
+
+pub(crate) fn enter_photo_mode(
+    mut commands: Commands,
+    q_camera: Single<(&mut Camera, &Transform, &Projection), With<PlayfieldCamera>>,
+    mut q_hud: Query<&mut Visibility, With<Hud>>,
+) {
+    let (mut camera, transform, projection) = q_camera.into_inner();
+    commands.insert_resource(PhotoMode {
+        saved_transform: *transform,
+        saved_projection: projection.clone(),
+    });
+
+    // Use the whole window, without letterboxing.
+    camera.viewport = None;
+    for mut visibility in q_hud.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+pub(crate) fn exit_photo_mode(
+    mut commands: Commands,
+    q_camera: Single<(&mut Transform, &mut Projection), With<PlayfieldCamera>>,
+    mut q_hud: Query<&mut Visibility, With<Hud>>,
+    r_photo: Res<PhotoMode>,
+) {
+    let (mut transform, mut projection) = q_camera.into_inner();
+    *transform = r_photo.saved_transform;
+    *projection = r_photo.saved_projection.clone();
+    commands.remove_resource::<PhotoMode>();
+    for mut visibility in q_hud.iter_mut() {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+/// Free camera controls: WASD / arrows to pan, Q / E to roll, right mouse drag to look around,
+/// mouse wheel or Z / X to zoom, Tab to switch between orthographic and perspective projection.
+/// Uses the real clock, since the virtual clock is paused.
+pub(crate) fn update_photo_camera(
+    q_camera: Single<(&mut Transform, &mut Projection), With<PlayfieldCamera>>,
+    r_keys: Res<ButtonInput<KeyCode>>,
+    r_buttons: Res<ButtonInput<MouseButton>>,
+    r_motion: Res<AccumulatedMouseMotion>,
+    r_scroll: Res<AccumulatedMouseScroll>,
+    r_time: Res<Time<Real>>,
+) {
+    let (mut transform, mut projection) = q_camera.into_inner();
+    let dt = r_time.delta_secs();
+
+    let mut pan = Vec2::ZERO;
+    if r_keys.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]) {
+        pan.x -= 1.0;
+    }
+    if r_keys.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight]) {
+        pan.x += 1.0;
+    }
+    if r_keys.any_pressed([KeyCode::KeyS, KeyCode::ArrowDown]) {
+        pan.y -= 1.0;
+    }
+    if r_keys.any_pressed([KeyCode::KeyW, KeyCode::ArrowUp]) {
+        pan.y += 1.0;
+    }
+    let offset = (transform.right() * pan.x + transform.up() * pan.y) * PAN_SPEED * dt;
+    transform.translation += offset;
+
+    let mut roll = 0.0;
+    if r_keys.pressed(KeyCode::KeyQ) {
+        roll += 1.0;
+    }
+    if r_keys.pressed(KeyCode::KeyE) {
+        roll -= 1.0;
+    }
+    transform.rotate_local_z(roll * ROLL_SPEED * dt);
+
+    if r_buttons.pressed(MouseButton::Right) {
+        let look = r_motion.delta * LOOK_SPEED;
+        transform.rotate_local_y(-look.x);
+        transform.rotate_local_x(-look.y);
+    }
+
+    // Positive zoom moves closer.
+    let mut zoom = r_scroll.delta.y * 0.1;
+    if r_keys.pressed(KeyCode::KeyZ) {
+        zoom += dt;
+    }
+    if r_keys.pressed(KeyCode::KeyX) {
+        zoom -= dt;
+    }
+    let factor = ZOOM_RATE.powf(zoom);
+
+    if r_keys.just_pressed(KeyCode::Tab) {
+        *projection = match *projection {
+            Projection::Orthographic(_) => Projection::Perspective(PerspectiveProjection {
+                fov: PERSPECTIVE_FOV,
+                ..default()
+            }),
+            _ => Projection::from(OrthographicProjection {
+                scaling_mode: bevy::render::camera::ScalingMode::FixedVertical {
+                    viewport_height: 1.0,
+                },
+                ..OrthographicProjection::default_2d()
+            }),
+        };
+    }
+
+    match &mut *projection {
+        Projection::Orthographic(ortho) => {
+            ortho.scale = (ortho.scale / factor).clamp(0.1, 4.0);
+        }
+        _ => {
+            // Dolly along the view direction, staying in front of the playfield.
+            let forward = transform.forward();
+            let distance = transform.translation.z;
+            let new_distance = (distance / factor).clamp(0.2, 8.0);
+            transform.translation += forward * (distance - new_distance);
+        }
+    }
+}
@@ -0,0 +1,273 @@
+//! Power-up pickups, occasionally dropped by destroyed enemies.
+use avian2d::prelude::{Collider, CollidingEntities, CollisionLayers, RigidBody, Sensor};
+use bevy::{audio::PlaybackMode, platform::collections::HashMap, prelude::*};
+use rand::Rng;
+
+use crate::{
+    EnemyDestroyed, FX_DEPTH, PICKUP_LAYER, PLAYER_LAYER, Playfield, RandomGenerator, SeamGhostOf,
+    SeamMirrored, UnitPosition,
+    game_state::{DespawnOnExit, GameState, RunState},
+    score::Reserves,
+    seam_source,
+    ship::PlayerShip,
+    terrain::Terrain,
+    treasure::{Treasure, TreasureState},
+};
+
+/// Types of power-up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PickupKind {
+    /// Adds a rear-facing gun
+    RearGun,
+
+    /// Companion drone which fights alongside the ship
+    Drone,
+
+    /// Increases the ship's magnet radius
+    Magnet,
+
+    /// Adds a smart bomb to the reserves
+    SmartBomb,
+
+    /// Angled shots bounce off the top and bottom of the playfield
+    Ricochet,
+
+    /// Laser shots pass through one more enemy
+    Pierce,
+}
+
+impl PickupKind {
+    pub const ALL: [PickupKind; 6] = [
+        PickupKind::RearGun,
+        PickupKind::Drone,
+        PickupKind::Magnet,
+        PickupKind::SmartBomb,
+        PickupKind::Ricochet,
+        PickupKind::Pierce,
+    ];
+
+    /// Relative likelihood of this kind being chosen when a pickup drops.
+    fn weight(&self) -> f32 {
+        match self {
+            PickupKind::RearGun => 1.0,
+            PickupKind::Drone => 0.2,
+            PickupKind::Magnet => 1.0,
+            PickupKind::SmartBomb => 0.15,
+            PickupKind::Ricochet => 0.5,
+            PickupKind::Pierce => 0.4,
+        }
+    }
+
+    fn color(&self) -> LinearRgba {
+        match self {
+            PickupKind::RearGun => LinearRgba::new(0.2, 1.5, 2.0, 1.0),
+            PickupKind::Drone => LinearRgba::new(0.4, 2.0, 0.4, 1.0),
+            PickupKind::Magnet => LinearRgba::new(2.0, 0.4, 1.5, 1.0),
+            PickupKind::SmartBomb => LinearRgba::new(2.0, 1.5, 0.2, 1.0),
+            PickupKind::Ricochet => LinearRgba::new(2.0, 2.0, 2.0, 1.0),
+            PickupKind::Pierce => LinearRgba::new(2.5, 0.6, 0.2, 1.0),
+        }
+    }
+}
+
+/// A power-up floating in the playfield, waiting to be collected.
+#[derive(Component, Debug)]
+pub struct Pickup {
+    kind: PickupKind,
+
+    /// Time until the pickup disappears
+    lifetime: f32,
+}
+
+/// Event sent when the player collects a pickup.
+#[derive(Event, Debug)]
+pub struct PickupCollected {
+    pub kind: PickupKind,
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct PickupMeshes {
+    mesh: Handle<Mesh>,
+    materials: HashMap<PickupKind, Handle<StandardMaterial>>,
+}
+
+/// Chance that a destroyed enemy drops a pickup.
+const PICKUP_DROP_CHANCE: f32 = 0.15;
+
+/// How long a pickup remains before disappearing, in seconds.
+const PICKUP_LIFETIME: f32 = 10.0;
+
+/// Speed at which pickups sink towards the ground.
+const PICKUP_SINK_SPEED: f32 = 0.05;
+
+/// Radius of the sensor with which the player ship collects a pickup.
+const PICKUP_RADIUS: f32 = 0.06;
+
+/// Rate at which pickups spin, in radians per second.
+const PICKUP_SPIN: f32 = 2.0;
+
+/// Speed at which objects at the edge of the magnet radius are drawn in. Objects closer to the
+/// ship move faster.
+const MAGNET_SPEED: f32 = 0.3;
+
+/// Amount the magnet radius grows with each magnet pickup.
+const MAGNET_BONUS_STEP: f32 = 0.05;
+
+/// Upper limit on the magnet radius bonus.
+const MAGNET_BONUS_MAX: f32 = 0.2;
+
+/// Most enemies a laser shot can pass through.
+const PIERCE_MAX: u32 = 3;
+
+pub(crate) fn setup_pickups(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut pickup_meshes: ResMut<PickupMeshes>,
+) {
+    pickup_meshes.mesh = meshes.add(Cuboid::from_length(0.025));
+    for kind in PickupKind::ALL {
+        pickup_meshes.materials.insert(
+            kind,
+            materials.add(StandardMaterial {
+                base_color: Color::BLACK,
+                emissive: kind.color(),
+                ..default()
+            }),
+        );
+    }
+}
+
+/// Destroyed enemies have a chance of dropping a pickup.
+pub(crate) fn on_enemy_destroyed_drop_pickup(
+    trigger: Trigger<EnemyDestroyed>,
+    mut commands: Commands,
+    mut r_rng: ResMut<RandomGenerator>,
+    r_pickup_meshes: Res<PickupMeshes>,
+) {
+    if !trigger.event().kind.rewarded() || !r_rng.0.random_bool(PICKUP_DROP_CHANCE as f64) {
+        return;
+    }
+
+    let total: f32 = PickupKind::ALL.iter().map(|kind| kind.weight()).sum();
+    let mut choice = r_rng.0.random_range(0.0..total);
+    let kind = PickupKind::ALL
+        .into_iter()
+        .find(|kind| {
+            choice -= kind.weight();
+            choice < 0.0
+        })
+        .unwrap_or(PickupKind::ALL[0]);
+
+    commands.spawn((
+        Pickup {
+            kind,
+            lifetime: PICKUP_LIFETIME,
+        },
+        RigidBody::Kinematic,
+        Collider::circle(PICKUP_RADIUS),
+        Sensor,
+        CollisionLayers::from_bits(PICKUP_LAYER, PLAYER_LAYER),
+        UnitPosition(trigger.event().position),
+        Mesh3d(r_pickup_meshes.mesh.clone()),
+        MeshMaterial3d(r_pickup_meshes.materials[&kind].clone()),
+        Transform::from_xyz(0., 0., FX_DEPTH),
+        SeamMirrored,
+        DespawnOnExit(GameState::Playing),
+    ));
+}
+
+/// Pickups sink slowly to the ground and eventually expire. The player collects them by flying
+/// over them, touching either the pickup's sensor or that of its seam ghost.
+pub(crate) fn update_pickups(
+    mut commands: Commands,
+    mut q_pickups: Query<(Entity, &mut Pickup, &mut UnitPosition, &mut Transform)>,
+    q_player: Query<&CollidingEntities, With<PlayerShip>>,
+    q_ghosts: Query<&SeamGhostOf>,
+    asset_server: Res<AssetServer>,
+    r_terrain: Res<Terrain>,
+    r_time: Res<Time>,
+) {
+    let dt = r_time.delta_secs();
+    let touching: Vec<Entity> = q_player
+        .iter()
+        .flat_map(|collisions| collisions.iter())
+        .map(|entity| seam_source(&q_ghosts, *entity))
+        .collect();
+    for (entity, mut pickup, mut position, mut transform) in q_pickups.iter_mut() {
+        pickup.lifetime -= dt;
+        if pickup.lifetime <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let ground = r_terrain.height_at(position.0.x) + PICKUP_RADIUS * 0.5;
+        position.0.y = (position.0.y - PICKUP_SINK_SPEED * dt).max(ground);
+        transform.rotate_y(PICKUP_SPIN * dt);
+
+        if touching.contains(&entity) {
+            commands.entity(entity).despawn();
+            commands.trigger(PickupCollected { kind: pickup.kind });
+            commands.spawn((
+                AudioPlayer::new(asset_server.load("sounds/ming.ogg")),
+                PlaybackSettings {
+                    mode: PlaybackMode::Despawn,
+                    ..default()
+                },
+            ));
+        }
+    }
+}
+
+/// Draw pickups and falling treasures within the ship's magnet radius towards the ship.
+pub(crate) fn apply_magnet(
+    q_player: Query<(&PlayerShip, &UnitPosition)>,
+    mut q_attracted: Query<
+        (&mut UnitPosition, Option<&Treasure>),
+        (Or<(With<Pickup>, With<Treasure>)>, Without<PlayerShip>),
+    >,
+    r_run: Res<RunState>,
+    r_playfield: Res<Playfield>,
+    r_time: Res<Time>,
+) {
+    let Ok((ship, player_pos)) = q_player.single() else {
+        return;
+    };
+    let radius = ship.variant.stats().magnet_radius + r_run.magnet_bonus;
+    if radius <= 0.0 {
+        return;
+    }
+
+    let dt = r_time.delta_secs();
+    for (mut position, treasure) in q_attracted.iter_mut() {
+        if treasure.is_some_and(|treasure| treasure.state != TreasureState::Falling) {
+            continue;
+        }
+        let delta = r_playfield.delta(position.0, player_pos.0);
+        let distance = delta.length();
+        if distance >= radius || distance < 0.001 {
+            continue;
+        }
+        let pull = MAGNET_SPEED * (2.0 - distance / radius) * dt;
+        position.0 += delta / distance * pull.min(distance);
+        position.0.x = r_playfield.wrap(position.0.x);
+    }
+}
+
+/// Apply the effect of a collected pickup. Pickups which spawn entities are handled by their own
+/// observers.
+pub(crate) fn on_pickup_collected(
+    trigger: Trigger<PickupCollected>,
+    mut r_run: ResMut<RunState>,
+    mut r_reserves: ResMut<Reserves>,
+) {
+    match trigger.event().kind {
+        PickupKind::RearGun => r_run.rear_gun = true,
+        PickupKind::Drone => {}
+        PickupKind::Magnet => {
+            r_run.magnet_bonus = (r_run.magnet_bonus + MAGNET_BONUS_STEP).min(MAGNET_BONUS_MAX);
+        }
+        PickupKind::SmartBomb => r_reserves.smart_bombs += 1,
+        PickupKind::Ricochet => r_run.ricochet = true,
+        PickupKind::Pierce => r_run.pierce = (r_run.pierce + 1).min(PIERCE_MAX),
+    }
+}
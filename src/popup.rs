@@ -0,0 +1,148 @@
+//! World-space text popups: small labels which appear at a point in the playfield, drift
+//! upwards and fade out. Used for the points scored by each kill, and optionally for the damage
+//! dealt by each hit.
+use bevy::prelude::*;
+
+use crate::{
+    EnemyDestroyed, EnemyHit, FX_DEPTH, Playfield, PlayfieldCamera, UnitPosition, Viewpoint,
+    game_state::{DespawnOnExit, GameState},
+    settings::Settings,
+};
+
+/// A text label attached to a point in the playfield. The position is kept here rather than in a
+/// [`UnitPosition`], since the label itself is a UI node.
+#[derive(Component, Debug)]
+pub struct WorldPopup {
+    /// Position in world coordinates
+    position: Vec2,
+
+    /// Time since the popup appeared
+    age: f32,
+
+    /// Base color of the text, before fading
+    color: Color,
+}
+
+/// How long popups last, in seconds
+const POPUP_LIFETIME: f32 = 0.9;
+
+/// Speed at which popups drift upwards
+const POPUP_RISE_SPEED: f32 = 0.08;
+
+const SCORE_COLOR: Color = Color::srgb(1.0, 0.9, 0.4);
+const DAMAGE_COLOR: Color = Color::srgb(1.0, 0.5, 0.4);
+
+/// Spawn a popup showing `text` at a position in the playfield.
+pub fn spawn_popup(
+    commands: &mut Commands,
+    position: Vec2,
+    text: impl Into<String>,
+    color: Color,
+    font_size: f32,
+) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        Text::new(text),
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextColor(color),
+        // Hidden until it has been placed on screen
+        Visibility::Hidden,
+        WorldPopup {
+            position,
+            age: 0.0,
+            color,
+        },
+        DespawnOnExit(GameState::Playing),
+    ));
+}
+
+/// Show the points scored for each kill.
+pub(crate) fn on_enemy_destroyed_popup(trigger: Trigger<EnemyDestroyed>, mut commands: Commands) {
+    let event = trigger.event();
+    if !event.kind.rewarded() {
+        return;
+    }
+    spawn_popup(
+        &mut commands,
+        event.position,
+        event.kind.points().to_string(),
+        SCORE_COLOR,
+        18.0,
+    );
+}
+
+/// Show the damage dealt by each hit, if enabled. Damage is shown as a percentage of a
+/// full-strength shot.
+pub(crate) fn on_enemy_hit_damage_number(
+    trigger: Trigger<EnemyHit>,
+    mut commands: Commands,
+    q_position: Query<&UnitPosition>,
+    r_settings: Res<Settings>,
+) {
+    if !r_settings.hud.show_damage_numbers {
+        return;
+    }
+    let Ok(position) = q_position.get(trigger.target()) else {
+        return;
+    };
+    spawn_popup(
+        &mut commands,
+        // Offset so as not to overlap the score popup
+        position.0 + Vec2::Y * 0.04,
+        format!("{:.0}", trigger.event().damage * 100.0),
+        DAMAGE_COLOR,
+        13.0,
+    );
+}
+
+/// Drift popups upwards, fading them out, and place them over the playfield camera's view.
+pub(crate) fn update_popups(
+    mut commands: Commands,
+    mut q_popups: Query<(
+        Entity,
+        &mut WorldPopup,
+        &mut Node,
+        &mut TextColor,
+        &mut Visibility,
+    )>,
+    q_camera: Single<(&Camera, &GlobalTransform), With<PlayfieldCamera>>,
+    r_viewpoint: Res<Viewpoint>,
+    r_playfield: Res<Playfield>,
+    r_ui_scale: Res<UiScale>,
+    r_time: Res<Time>,
+) {
+    let (camera, camera_transform) = q_camera.into_inner();
+    let viewport_origin = camera
+        .logical_viewport_rect()
+        .map_or(Vec2::ZERO, |rect| rect.min);
+    let dt = r_time.delta_secs();
+    for (entity, mut popup, mut node, mut color, mut visibility) in q_popups.iter_mut() {
+        popup.age += dt;
+        if popup.age >= POPUP_LIFETIME {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        popup.position.y += POPUP_RISE_SPEED * dt;
+        color.0 = popup.color.with_alpha(1.0 - popup.age / POPUP_LIFETIME);
+
+        let world = Vec3::new(
+            r_playfield.relative(popup.position.x, r_viewpoint.position),
+            popup.position.y,
+            FX_DEPTH,
+        );
+        let Ok(screen) = camera.world_to_viewport(camera_transform, world) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        let screen = (screen + viewport_origin) / r_ui_scale.0;
+        node.left = Val::Px(screen.x);
+        node.top = Val::Px(screen.y);
+        *visibility = Visibility::Inherited;
+    }
+}
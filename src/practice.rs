@@ -0,0 +1,33 @@
+//! Practice mode: start on any wave the player has already reached, with unlimited lives and
+//! hitboxes drawn so that collisions are easy to study.
+use avian2d::prelude::PhysicsGizmos;
+use bevy::prelude::*;
+
+use crate::{
+    game_state::{Level, RunState},
+    profile::Profile,
+};
+
+/// Draw physics hitboxes only during practice runs. The physics debug systems don't run at all
+/// while their gizmos are disabled. Changing the gizmo config also resets the visibility of every
+/// rigid body, so it is only touched when practice mode is switched on or off.
+pub(crate) fn apply_practice_hitboxes(
+    r_run: Res<RunState>,
+    mut r_gizmos: ResMut<GizmoConfigStore>,
+) {
+    let practice = r_run.practice.is_some();
+    if r_gizmos.config::<PhysicsGizmos>().0.enabled != practice {
+        r_gizmos.config_mut::<PhysicsGizmos>().0.enabled = practice;
+    }
+}
+
+/// Remember the furthest wave reached in a normal run, which unlocks it for practice.
+pub(crate) fn record_furthest_wave(
+    r_run: Res<RunState>,
+    r_level: Res<Level>,
+    mut r_profile: ResMut<Profile>,
+) {
+    if r_run.practice.is_none() {
+        r_profile.furthest_wave = r_profile.furthest_wave.max(r_level.number);
+    }
+}
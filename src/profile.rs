@@ -0,0 +1,69 @@
+//! Persistent player profile, stored as RON in the user's data directory.
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::stats::RunStats;
+
+/// Data which persists between sessions.
+#[derive(Resource, Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct Profile {
+    /// Number of runs started
+    pub runs: u32,
+
+    /// Statistics totalled over all runs
+    pub lifetime: RunStats,
+
+    /// Furthest wave reached in a normal run, counting from zero. Any wave up to this one can be
+    /// chosen in practice mode.
+    pub furthest_wave: u32,
+}
+
+pub(crate) fn data_path(file: &str) -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("guardian").join(file))
+}
+
+/// Load a RON file from the data directory, falling back to the default value if it doesn't exist
+/// or can't be read.
+pub(crate) fn load_data<T: DeserializeOwned + Default>(file: &str) -> T {
+    let Some(text) = data_path(file).and_then(|path| std::fs::read_to_string(path).ok()) else {
+        return T::default();
+    };
+    ron::from_str(&text).unwrap_or_else(|err| {
+        warn!("Could not parse {file}: {err}");
+        T::default()
+    })
+}
+
+/// Write a value as RON to the data directory. The file is written to a temporary file first and
+/// then renamed over the original, so that a crash part way through can't leave it truncated.
+pub(crate) fn save_data<T: Serialize>(file: &str, value: &T) {
+    let Some(path) = data_path(file) else {
+        return;
+    };
+    let result = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|err| err.to_string())
+        .and_then(|text| {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+            }
+            let temp = path.with_extension("ron.tmp");
+            std::fs::write(&temp, text).map_err(|err| err.to_string())?;
+            std::fs::rename(&temp, &path).map_err(|err| err.to_string())
+        });
+    if let Err(err) = result {
+        warn!("Could not save {file}: {err}");
+    }
+}
+
+/// Load the profile, falling back to a default one if it doesn't exist or can't be read.
+pub(crate) fn load_profile() -> Profile {
+    load_data("profile.ron")
+}
+
+/// Write the profile to disk.
+pub(crate) fn save_profile(profile: &Profile) {
+    save_data("profile.ron", profile);
+}
@@ -0,0 +1,72 @@
+//! Generic loader for game data assets stored as RON files.
+use std::{fmt, marker::PhantomData};
+
+use bevy::asset::{Asset, AssetLoader, LoadContext, io::Reader};
+use serde::de::DeserializeOwned;
+
+/// Asset loader which deserializes an asset of type `T` from RON.
+pub struct RonAssetLoader<T> {
+    extensions: &'static [&'static str],
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> RonAssetLoader<T> {
+    /// Create a loader for files with the given extensions, e.g. `"theme.ron"`.
+    pub fn new(extensions: &'static [&'static str]) -> Self {
+        Self {
+            extensions,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Errors which can occur while loading a RON asset.
+#[derive(Debug)]
+pub enum RonAssetError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl fmt::Display for RonAssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RonAssetError::Io(err) => write!(f, "could not read asset: {err}"),
+            RonAssetError::Ron(err) => write!(f, "could not parse asset: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RonAssetError {}
+
+impl From<std::io::Error> for RonAssetError {
+    fn from(err: std::io::Error) -> Self {
+        RonAssetError::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for RonAssetError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        RonAssetError::Ron(err)
+    }
+}
+
+impl<T: Asset + DeserializeOwned> AssetLoader for RonAssetLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = RonAssetError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<T, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.extensions
+    }
+}
@@ -0,0 +1,162 @@
+//! Opt-in run log for playtesting: a record of wave starts, kills, deaths and abductions, written
+//! to the data directory as JSON lines when the run ends, so that difficulty pacing can be
+//! compared across playtests. The file is rewritten whenever the log is saved, so a run which is
+//! continued after game over ends up in a single file.
+use std::{fmt::Write, time::SystemTime};
+
+use bevy::prelude::*;
+
+use crate::{
+    EnemyDestroyed, EnemyKind, ai::AiTransition, damage::ShipLost, game_state::Level,
+    profile::data_path, saucer::SaucerState, settings::Settings, stats::RunStats,
+};
+
+/// Something which happened during the run.
+#[derive(Debug, Clone, Copy)]
+pub enum RunLogEvent {
+    WaveStart,
+    Kill(EnemyKind),
+    Death,
+    Abduction,
+    Queened,
+    RunEnd { score: u32 },
+}
+
+impl RunLogEvent {
+    /// Name of the event, and any extra JSON fields.
+    fn fields(&self) -> (&'static str, String) {
+        match self {
+            RunLogEvent::WaveStart => ("wave_start", String::new()),
+            RunLogEvent::Kill(kind) => ("kill", format!(",\"enemy\":\"{}\"", kind.name())),
+            RunLogEvent::Death => ("death", String::new()),
+            RunLogEvent::Abduction => ("abduction", String::new()),
+            RunLogEvent::Queened => ("queened", String::new()),
+            RunLogEvent::RunEnd { score } => ("run_end", format!(",\"score\":{score}")),
+        }
+    }
+}
+
+/// Events recorded during the current run. Nothing is recorded unless the run log is enabled
+/// in the settings.
+#[derive(Resource, Default, Debug)]
+pub struct RunLog {
+    /// Game clock time at which the run started
+    start: f32,
+
+    /// Recorded events, with the time since the start of the run and the wave number
+    events: Vec<(f32, u32, RunLogEvent)>,
+
+    /// Name of the file the log is written to, taken from the time the run started
+    file: String,
+}
+
+impl RunLog {
+    /// Record an event, if the run log is enabled.
+    fn record(&mut self, settings: &Settings, time: &Time, level: &Level, event: RunLogEvent) {
+        if settings.gameplay.run_log {
+            self.events
+                .push((time.elapsed_secs() - self.start, level.number, event));
+        }
+    }
+
+    /// Format the log as JSON lines, one event per line, followed by the given final event.
+    fn to_json_lines(&self, end: (f32, u32, RunLogEvent)) -> String {
+        let mut text = String::new();
+        for (time, wave, event) in self.events.iter().chain([&end]) {
+            let (name, extra) = event.fields();
+            let _ = writeln!(
+                text,
+                "{{\"time\":{time:.3},\"wave\":{wave},\"event\":\"{name}\"{extra}}}"
+            );
+        }
+        text
+    }
+}
+
+pub(crate) fn start_run_log(mut r_log: ResMut<RunLog>, r_time: Res<Time>) {
+    let stamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    *r_log = RunLog {
+        start: r_time.elapsed_secs(),
+        events: Vec::new(),
+        file: format!("run-{stamp}.jsonl"),
+    };
+}
+
+pub(crate) fn log_wave_start(
+    mut r_log: ResMut<RunLog>,
+    r_settings: Res<Settings>,
+    r_level: Res<Level>,
+    r_time: Res<Time>,
+) {
+    r_log.record(&r_settings, &r_time, &r_level, RunLogEvent::WaveStart);
+}
+
+pub(crate) fn log_enemy_destroyed(
+    trigger: Trigger<EnemyDestroyed>,
+    mut r_log: ResMut<RunLog>,
+    r_settings: Res<Settings>,
+    r_level: Res<Level>,
+    r_time: Res<Time>,
+) {
+    let event = RunLogEvent::Kill(trigger.event().kind);
+    r_log.record(&r_settings, &r_time, &r_level, event);
+}
+
+pub(crate) fn log_ship_lost(
+    _trigger: Trigger<ShipLost>,
+    mut r_log: ResMut<RunLog>,
+    r_settings: Res<Settings>,
+    r_level: Res<Level>,
+    r_time: Res<Time>,
+) {
+    r_log.record(&r_settings, &r_time, &r_level, RunLogEvent::Death);
+}
+
+pub(crate) fn log_saucer_transition(
+    trigger: Trigger<AiTransition<SaucerState>>,
+    mut r_log: ResMut<RunLog>,
+    r_settings: Res<Settings>,
+    r_level: Res<Level>,
+    r_time: Res<Time>,
+) {
+    let event = match trigger.event().to {
+        SaucerState::Escaping => RunLogEvent::Abduction,
+        SaucerState::Queened => RunLogEvent::Queened,
+        _ => return,
+    };
+    r_log.record(&r_settings, &r_time, &r_level, event);
+}
+
+/// Write the log, ending with the score so far. This happens at game over, when returning to the
+/// intro menu, and when the app exits mid-run.
+pub(crate) fn save_run_log(
+    r_log: Res<RunLog>,
+    r_level: Res<Level>,
+    r_stats: Res<RunStats>,
+    r_time: Res<Time>,
+) {
+    if r_log.events.is_empty() {
+        return;
+    }
+    let end = (
+        r_time.elapsed_secs() - r_log.start,
+        r_level.number,
+        RunLogEvent::RunEnd {
+            score: r_stats.score,
+        },
+    );
+    let file = &r_log.file;
+    let Some(path) = data_path("runs").map(|dir| dir.join(file)) else {
+        return;
+    };
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, r_log.to_json_lines(end)));
+    match result {
+        Ok(()) => info!("Run log written to {}", path.display()),
+        Err(err) => warn!("Could not write run log {file}: {err}"),
+    }
+}
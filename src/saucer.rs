@@ -1,23 +1,39 @@
+use std::{ops::Range, time::Duration};
+
 use avian2d::prelude::{Collider, CollisionLayers, RigidBody};
-use bevy::{audio::PlaybackMode, prelude::*, scene::SceneInstanceReady};
+use bevy::prelude::*;
 use rand::Rng;
 use rand_chacha::ChaCha8Rng;
 
 use crate::{
-    ENEMY_LAYER, Enemy, EnemyHit, PLAYER_LAYER, PLAYER_SHOT_LAYER, PLAYFIELD_WIDTH,
-    RandomGenerator, SHIP_DEPTH, UnitPosition,
-    explosion::{FlareEffect, ShrapnelEffect},
+    ENEMY_LAYER, Enemy, EnemyDestroyed, EnemyHit, EnemyKind, PLAYER_LAYER, PLAYER_SHOT_LAYER,
+    Playfield, RandomGenerator, SHIP_DEPTH, SeamMirrored, TreasureLost, UnitPosition,
+    adaptive::AdaptiveDifficulty,
+    ai::{AiState, AiTransition, StateMachine},
+    explosion::{ExplosionSound, FlareEffect, ShrapnelEffect},
+    game_state::{DespawnOnExit, Difficulty, GameState},
+    laser::LaserShot,
+    leader::{FORMATION_LEADERS, FormationLeader, INSPIRED_SPEED, Inspired, LEADER_HEALTH},
+    parachute::{ParachuteMesh, open_parachute},
+    scene_anim::{SceneAnimationSet, SwitchSceneAnimation},
+    settings::Settings,
+    spatial::SpatialHash,
+    steering::{apply_steering, arrive, separation},
+    treasure::{TREASURE_GROUND_Y, Treasure, TreasureState},
+    treasure_count::AllTreasuresLost,
+    tuning::{SaucerTuning, Tuning},
+    wave::{WaveConfig, WaveMutator},
 };
 
 /// State of a saucer
-#[derive(Default, Debug, Copy, Clone)]
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
 pub enum SaucerState {
     /// Arrival animation
     #[default]
     Arriving,
 
     /// Wandering around
-    Patrolling(Vec2),
+    Patrolling,
 
     /// Located an abduction target, moving to location
     Seeking,
@@ -32,160 +48,669 @@ pub enum SaucerState {
     Queened,
 }
 
-/// Saucers are a type of enemy that abducts treasure
-#[derive(Component, Default, Debug)]
+impl AiState for SaucerState {
+    fn debug_color(&self) -> Color {
+        match self {
+            SaucerState::Arriving => Color::srgb(0.5, 0.5, 0.5),
+            SaucerState::Patrolling => Color::srgb(0.0, 1.0, 0.0),
+            SaucerState::Seeking => Color::srgb(1.0, 1.0, 0.0),
+            SaucerState::Grabbing => Color::srgb(1.0, 0.5, 0.0),
+            SaucerState::Escaping => Color::srgb(1.0, 0.0, 0.0),
+            SaucerState::Queened => Color::srgb(1.0, 0.0, 1.0),
+        }
+    }
+}
+
+/// Saucers are a type of enemy that abducts treasure. What's happening with the saucer is
+/// tracked by a `StateMachine<SaucerState>`.
+#[derive(Component, Default, Debug, Reflect)]
+#[reflect(Component)]
 pub struct Saucer {
-    /// What's happening with this saucer
-    state: SaucerState,
+    /// Current velocity
+    velocity: Vec2,
 
-    /// Where we are going to
+    /// Where we are heading to while patrolling
+    waypoint: Vec2,
+
+    /// Time until we choose a new waypoint
     timer: f32,
+
+    /// Time until we look for a treasure to abduct
+    abduct_timer: f32,
+
+    /// Treasure we are abducting
+    target: Option<Entity>,
+
+    /// Remaining hit points
+    health: f32,
+
+    /// Hit points at full health
+    max_health: f32,
 }
 
-const SAUCER_SPEED_X: f32 = 0.4;
-const SAUCER_SPEED_Y: f32 = 0.2;
+impl Saucer {
+    /// Remaining hit points as a fraction of full health.
+    pub fn health_fraction(&self) -> f32 {
+        (self.health / self.max_health).clamp(0.0, 1.0)
+    }
+
+    /// Time until the saucer next looks for a treasure to abduct.
+    pub fn abduct_timer(&self) -> f32 {
+        self.abduct_timer
+    }
 
-#[derive(Component)]
-struct AnimationToPlay {
-    graph_handle: Handle<AnimationGraph>,
-    index: AnimationNodeIndex,
+    /// How far an escaping saucer at the given height has carried its treasure, from 0 when it
+    /// lifts off to 1 when it reaches the top and the treasure is lost.
+    pub fn escape_progress(height: f32) -> f32 {
+        let start = TREASURE_GROUND_Y + GRAB_HEIGHT;
+        ((height - start) / (ESCAPE_HEIGHT - start)).clamp(0.0, 1.0)
+    }
 }
 
+/// Staggers abductions so that only a limited number happen at once, as set by the
+/// [`WaveConfig`]. Saucers which aren't allowed to abduct keep patrolling.
+#[derive(Resource, Default, Debug)]
+pub struct AbductionDirector {
+    /// Time since the last abduction started
+    since_last: f32,
+}
+
+/// Lets an enemy sidestep incoming laser fire by making a quick vertical move.
+#[derive(Component, Default, Debug)]
+pub struct Dodger {
+    /// Time until we can react to another shot
+    cooldown: f32,
+
+    /// Time remaining in the current dodge
+    remaining: f32,
+
+    /// Vertical velocity of the current dodge
+    velocity: f32,
+}
+
+/// Treasure which has been claimed by a saucer as its abduction target. A treasure can only be
+/// claimed by one saucer at a time.
+#[derive(Component, Debug)]
+#[relationship(relationship_target = AbductionClaims)]
+pub struct ClaimedBy(pub Entity);
+
+/// Treasures claimed by a saucer. Claims are released automatically when the saucer despawns.
+#[derive(Component, Debug, Default)]
+#[relationship_target(relationship = ClaimedBy)]
+pub struct AbductionClaims(Vec<Entity>);
+
+/// Initial hit points of a saucer; a full-strength shot destroys it.
+const SAUCER_HEALTH: f32 = 1.0;
+
+/// Maximum horizontal distance to the next patrol waypoint.
+const PATROL_RANGE: f32 = 1.0;
+
+/// Range of time a saucer patrols before looking for a treasure to abduct.
+const ABDUCT_DELAY: Range<f32> = 4.0..10.0;
+
+/// Height above the treasure at which the saucer hovers while grabbing it.
+const GRAB_HEIGHT: f32 = 0.12;
+
+/// How long it takes to pull a treasure up to the saucer.
+const GRAB_TIME: f32 = 1.5;
+
+/// Distance below the saucer at which treasure is carried.
+const CARRY_OFFSET: f32 = 0.05;
+
+/// Saucers closer together than this push each other apart.
+const SEPARATION_RADIUS: f32 = 0.12;
+
+/// Strength of the push between neighboring saucers.
+const SEPARATION_ACCEL: f32 = 1.5;
+
+/// How much faster saucers escape per level of difficulty.
+const ESCAPE_SCALE: f32 = 0.25;
+
+/// Largest angle of the carried treasure's swing, in radians.
+const SWING_ANGLE: f32 = 0.25;
+
+/// Angular frequency of the carried treasure's swing, in radians per second.
+const SWING_RATE: f32 = 3.0;
+
+/// Once an escaping saucer reaches this height, the treasure is lost.
+const ESCAPE_HEIGHT: f32 = 0.45;
+
+/// Shots within this vertical distance of a saucer are a threat.
+const DODGE_BAND: f32 = 0.04;
+
+/// Shots are noticed once they are this close horizontally.
+const DODGE_RANGE: f32 = 0.8;
+
+/// Chance of dodging at difficulty 0, and how much it increases per difficulty level.
+const DODGE_CHANCE_BASE: f32 = 0.1;
+const DODGE_CHANCE_SCALE: f32 = 0.15;
+const DODGE_CHANCE_MAX: f32 = 0.8;
+
+const DODGE_SPEED: f32 = 0.6;
+const DODGE_TIME: f32 = 0.2;
+const DODGE_COOLDOWN: f32 = 0.6;
+
+/// Clips in the saucer's animation set: the index of the glTF animation within saucer.glb, and
+/// its playback speed. In order: idle spin, beam-down pose, escape wobble.
+const SAUCER_CLIPS: [(usize, f32); 3] = [(0, 1.0), (1, 1.0), (2, 1.0)];
+
+/// Time taken to blend between saucer animations, in seconds.
+const SAUCER_CROSSFADE: f32 = 0.3;
+
 pub(crate) fn spawn_saucer(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut graphs: ResMut<Assets<AnimationGraph>>,
     mut rng: ResMut<RandomGenerator>,
+    r_playfield: Res<Playfield>,
 ) {
-    let animation = asset_server.load(GltfAssetLabel::Animation(0).from_asset("models/saucer.glb"));
-    let (graph, index) = AnimationGraph::from_clip(animation);
-    let graph_handle = graphs.add(graph);
-
-    for _ in 0..24 {
-        // Saucer model
-        commands
-            .spawn((
-                SceneRoot(
-                    asset_server.load(GltfAssetLabel::Scene(0).from_asset("models/saucer.glb")),
-                ),
-                Saucer {
-                    state: SaucerState::Arriving,
-                    timer: rng.0.random_range(1.0..2.0),
-                },
-                Enemy,
-                RigidBody::Kinematic,
-                Collider::capsule_endpoints(2.0, Vec2::new(-2., 0.2), Vec2::new(2., 0.2)),
-                CollisionLayers::from_bits(ENEMY_LAYER, PLAYER_LAYER | PLAYER_SHOT_LAYER),
-                UnitPosition(Vec2::new(
-                    rng.0.random_range(0.0..PLAYFIELD_WIDTH),
-                    rng.0.random_range(0.6..0.7),
-                )),
-                AnimationToPlay {
-                    graph_handle: graph_handle.clone(),
-                    index,
-                },
-                Transform::from_scale(Vec3::splat(0.013))
-                    .with_rotation(Quat::from_euler(EulerRot::XYZ, 0.1, 0.2, 0.0))
-                    .with_translation(Vec3::new(0., 0., SHIP_DEPTH)),
-            ))
-            .observe(play_animation_when_ready)
-            .observe(saucer_hit);
+    let animations = SceneAnimationSet::new(
+        SAUCER_CLIPS.map(|(index, _)| {
+            asset_server.load(GltfAssetLabel::Animation(index).from_asset("models/saucer.glb"))
+        }),
+        &mut graphs,
+    );
+
+    for index in 0..24 {
+        // The first few saucers of each wave lead formations.
+        let leader = index < FORMATION_LEADERS;
+        let health = if leader { LEADER_HEALTH } else { SAUCER_HEALTH };
+        let mut saucer = commands.spawn((
+            SceneRoot(asset_server.load(GltfAssetLabel::Scene(0).from_asset("models/saucer.glb"))),
+            Saucer {
+                velocity: Vec2::ZERO,
+                waypoint: Vec2::ZERO,
+                timer: rng.0.random_range(1.0..2.0),
+                abduct_timer: rng.0.random_range(ABDUCT_DELAY),
+                target: None,
+                health,
+                max_health: health,
+            },
+            StateMachine::new(SaucerState::Arriving),
+            Dodger::default(),
+            Enemy,
+            RigidBody::Kinematic,
+            Collider::capsule_endpoints(2.0, Vec2::new(-2., 0.2), Vec2::new(2., 0.2)),
+            CollisionLayers::from_bits(ENEMY_LAYER, PLAYER_LAYER | PLAYER_SHOT_LAYER),
+            UnitPosition(Vec2::new(
+                rng.0.random_range(0.0..r_playfield.width),
+                rng.0.random_range(0.6..0.7),
+            )),
+            animations.clone(),
+            Transform::from_scale(Vec3::splat(0.013))
+                .with_rotation(Quat::from_euler(EulerRot::XYZ, 0.1, 0.2, 0.0))
+                .with_translation(Vec3::new(0., 0., SHIP_DEPTH)),
+            SeamMirrored,
+            DespawnOnExit(GameState::Playing),
+        ));
+        if leader {
+            saucer.insert(FormationLeader);
+        }
+        saucer.observe(saucer_hit);
+    }
+}
+
+/// Index of the clip in [`SAUCER_CLIPS`] played in each state.
+fn saucer_clip(state: SaucerState) -> usize {
+    match state {
+        SaucerState::Grabbing => 1,
+        SaucerState::Escaping => 2,
+        _ => 0,
     }
 }
 
-// TODO: Clean this up, right now it's pasted from the example.
-fn play_animation_when_ready(
-    trigger: Trigger<SceneInstanceReady>,
+/// Crossfade to the animation for the saucer's new state.
+pub(crate) fn switch_saucer_animation(
+    trigger: Trigger<AiTransition<SaucerState>>,
     mut commands: Commands,
-    children: Query<&Children>,
-    animations_to_play: Query<&AnimationToPlay>,
-    mut players: Query<&mut AnimationPlayer>,
 ) {
-    // The entity we spawned in `setup_mesh_and_animation` is the trigger's target.
-    // Start by finding the AnimationToPlay component we added to that entity.
-    if let Ok(animation_to_play) = animations_to_play.get(trigger.target()) {
-        // The SceneRoot component will have spawned the scene as a hierarchy
-        // of entities parented to our entity. Since the asset contained a skinned
-        // mesh and animations, it will also have spawned an animation player
-        // component. Search our entity's descendants to find the animation player.
-        for child in children.iter_descendants(trigger.target()) {
-            if let Ok(mut player) = players.get_mut(child) {
-                // Tell the animation player to start the animation and keep
-                // repeating it.
-                //
-                // If you want to try stopping and switching animations, see the
-                // `animated_mesh_control.rs` example.
-                player.play(animation_to_play.index).repeat();
-
-                // Add the animation graph. This only needs to be done once to
-                // connect the animation player to the mesh.
-                commands
-                    .entity(child)
-                    .insert(AnimationGraphHandle(animation_to_play.graph_handle.clone()));
-            }
+    let event = trigger.event();
+    let clip = saucer_clip(event.to);
+    if clip == saucer_clip(event.from) {
+        return;
+    }
+    commands
+        .entity(trigger.target())
+        .trigger(SwitchSceneAnimation {
+            clip,
+            speed: SAUCER_CLIPS[clip].1,
+            crossfade: Duration::from_secs_f32(SAUCER_CROSSFADE),
+        });
+}
+
+/// With nothing left to protect, the planet is destroyed and every saucer becomes a queen.
+pub(crate) fn queen_saucers_on_planet_lost(
+    _trigger: Trigger<AllTreasuresLost>,
+    mut q_saucers: Query<&mut StateMachine<SaucerState>, With<Saucer>>,
+) {
+    for mut machine in q_saucers.iter_mut() {
+        if machine.state() != SaucerState::Queened {
+            machine.transition_to(SaucerState::Queened);
         }
     }
 }
 
 pub(crate) fn animate_saucers(
-    mut q_saucers: Query<(&mut Saucer, &mut UnitPosition)>,
+    mut commands: Commands,
+    mut q_saucers: Query<
+        (
+            Entity,
+            &mut Saucer,
+            &mut StateMachine<SaucerState>,
+            &mut UnitPosition,
+            Has<Inspired>,
+        ),
+        Without<Treasure>,
+    >,
+    mut q_treasures: Query<
+        (
+            Entity,
+            &mut Treasure,
+            &mut UnitPosition,
+            &mut Transform,
+            Has<ClaimedBy>,
+        ),
+        Without<Saucer>,
+    >,
     time: Res<Time>,
     mut rng: ResMut<RandomGenerator>,
+    r_playfield: Res<Playfield>,
+    r_wave: Res<WaveConfig>,
+    r_hash: Res<SpatialHash>,
+    mut r_director: ResMut<AbductionDirector>,
+    r_adaptive: Res<AdaptiveDifficulty>,
+    r_settings: Res<Settings>,
+    r_difficulty: Res<Difficulty>,
+    r_tuning: Res<Tuning>,
 ) {
-    // let move_dist = 0.5 * time.delta_secs();
-    for (mut saucer, mut position) in q_saucers.iter_mut() {
-        match saucer.state {
+    let dt = time.delta_secs();
+    let speed = r_wave.saucer_speed();
+    let tuning = &r_tuning.saucer;
+    // Adaptive difficulty makes saucers look for treasure more or less often.
+    let intensity = r_adaptive.intensity(&r_settings);
+    r_director.since_last += dt;
+
+    // Treasures claimed during this frame, since `ClaimedBy` isn't inserted until later.
+    let mut new_claims: Vec<Entity> = Vec::new();
+
+    // Number of abductions in progress
+    let mut active_abductions = q_saucers
+        .iter()
+        .filter(|(_, _, machine, _, _)| {
+            matches!(
+                machine.state(),
+                SaucerState::Seeking | SaucerState::Grabbing | SaucerState::Escaping
+            )
+        })
+        .count();
+
+    for (entity, mut saucer, mut machine, mut position, inspired) in q_saucers.iter_mut() {
+        // Saucers near a formation leader fly faster.
+        let speed = if inspired {
+            speed * INSPIRED_SPEED
+        } else {
+            speed
+        };
+        match machine.state() {
             SaucerState::Arriving => {
-                saucer.state = SaucerState::Patrolling(choose_random_angle(&mut rng.0));
+                saucer.velocity = choose_random_angle(&mut rng.0, tuning);
+                saucer.waypoint = choose_waypoint(&mut rng.0, position.0);
                 saucer.timer = rng.0.random_range(1.0..2.0);
+                machine.transition_to(SaucerState::Patrolling);
+            }
+
+            SaucerState::Patrolling => {
+                patrol(
+                    &mut saucer,
+                    &mut position.0,
+                    &mut rng.0,
+                    &r_playfield,
+                    tuning,
+                    speed,
+                    dt,
+                );
+
+                saucer.abduct_timer -= dt * intensity;
+                if saucer.abduct_timer > 0.0 {
+                    continue;
+                }
+                saucer.abduct_timer = rng.0.random_range(ABDUCT_DELAY);
+
+                // Ask the director for permission
+                if active_abductions >= r_wave.max_abductions
+                    || r_director.since_last < r_wave.abduction_interval / intensity
+                {
+                    continue;
+                }
+
+                // Claim the nearest standing treasure that nobody else has claimed.
+                let nearest = r_hash.nearest(position.0, |treasure_id| {
+                    q_treasures
+                        .get(treasure_id)
+                        .is_ok_and(|(_, treasure, _, _, claimed)| {
+                            !claimed && treasure.state == TreasureState::Standing
+                        })
+                        && !new_claims.contains(&treasure_id)
+                });
+                if let Some((treasure_id, _)) = nearest {
+                    active_abductions += 1;
+                    r_director.since_last = 0.0;
+                    new_claims.push(treasure_id);
+                    commands.entity(treasure_id).insert(ClaimedBy(entity));
+                    saucer.target = Some(treasure_id);
+                    machine.transition_to(SaucerState::Seeking);
+                }
+            }
+
+            SaucerState::Seeking => {
+                let Some(Ok((_, mut treasure, treasure_pos, _, _))) =
+                    saucer.target.map(|target| q_treasures.get_mut(target))
+                else {
+                    saucer.target = None;
+                    machine.transition_to(SaucerState::Patrolling);
+                    continue;
+                };
+
+                // Hover above the treasure
+                let hover = treasure_pos.0 + Vec2::Y * GRAB_HEIGHT;
+                let force = arrive(
+                    &r_playfield,
+                    position.0,
+                    saucer.velocity,
+                    hover,
+                    tuning.speed_x * speed,
+                    0.2,
+                );
+                saucer.velocity = apply_steering(
+                    saucer.velocity,
+                    force,
+                    tuning.acceleration * 2.0 * speed,
+                    tuning.speed() * speed,
+                    dt,
+                );
+                position.0 += saucer.velocity * dt;
+                position.0.x = r_playfield.relative(position.0.x, 0.0);
+
+                if r_playfield.distance(position.0, hover) < 0.01 {
+                    treasure.state = TreasureState::Abducted;
+                    saucer.velocity = Vec2::ZERO;
+                    machine.transition_to(SaucerState::Grabbing);
+                }
             }
 
-            SaucerState::Patrolling(vel) => {
-                saucer.timer -= time.delta_secs();
-
-                position.0 += vel * time.delta_secs();
-                position.0.x = (position.0.x + PLAYFIELD_WIDTH * 0.5).rem_euclid(PLAYFIELD_WIDTH)
-                    - PLAYFIELD_WIDTH * 0.5;
-                if position.0.y > 0.4 {
-                    saucer.state = SaucerState::Patrolling(Vec2::new(vel.x, -SAUCER_SPEED_Y));
-                } else if position.0.y < -0.4 {
-                    saucer.state = SaucerState::Patrolling(Vec2::new(vel.x, SAUCER_SPEED_Y));
-                } else if saucer.timer <= 0.0 {
-                    saucer.state = SaucerState::Patrolling(choose_random_angle(&mut rng.0));
-                    saucer.timer = rng.0.random_range(1.0..2.0);
+            SaucerState::Grabbing => {
+                let Some(Ok((_, _, mut treasure_pos, _, _))) =
+                    saucer.target.map(|target| q_treasures.get_mut(target))
+                else {
+                    saucer.target = None;
+                    machine.transition_to(SaucerState::Patrolling);
+                    continue;
+                };
+
+                // Pull the treasure up towards the saucer
+                let t = (machine.elapsed() / GRAB_TIME).min(1.0);
+                let carry_y = position.0.y - CARRY_OFFSET;
+                treasure_pos.0.y = treasure_pos.0.y.lerp(carry_y, t);
+                if t >= 1.0 {
+                    machine.transition_to(SaucerState::Escaping);
                 }
             }
-            _ => todo!(),
+
+            SaucerState::Escaping => {
+                let Some(target) = saucer.target else {
+                    machine.transition_to(SaucerState::Patrolling);
+                    continue;
+                };
+                let Ok((_, mut treasure, mut treasure_pos, mut treasure_transform, _)) =
+                    q_treasures.get_mut(target)
+                else {
+                    saucer.target = None;
+                    machine.transition_to(SaucerState::Patrolling);
+                    continue;
+                };
+
+                // Heavier treasures slow the saucer down, and later waves speed it up.
+                let escape_speed =
+                    tuning.escape_speed * speed * (1.0 + r_difficulty.0 * ESCAPE_SCALE)
+                        / treasure.info.weight.max(0.1);
+                position.0.y += escape_speed * dt;
+
+                // The treasure dangles beneath the saucer, swinging gently.
+                let swing = SWING_ANGLE * (machine.elapsed() * SWING_RATE).sin();
+                treasure_transform.rotate_z(swing - treasure.swing);
+                treasure.swing = swing;
+                treasure_pos.0 = position.0 + Vec2::new(swing.sin(), -swing.cos()) * CARRY_OFFSET;
+
+                // Made it to the top: the treasure is lost and the saucer becomes a queen.
+                if position.0.y >= ESCAPE_HEIGHT {
+                    commands.entity(target).despawn();
+                    commands.trigger(TreasureLost {
+                        position: treasure_pos.0,
+                    });
+                    saucer.target = None;
+                    saucer.timer = 0.0;
+                    machine.transition_to(SaucerState::Queened);
+                }
+            }
+
+            SaucerState::Queened => {
+                patrol(
+                    &mut saucer,
+                    &mut position.0,
+                    &mut rng.0,
+                    &r_playfield,
+                    tuning,
+                    tuning.queen_speed_scale * speed,
+                    dt,
+                );
+            }
         };
     }
 }
 
-fn choose_random_angle(rng: &mut ChaCha8Rng) -> Vec2 {
+/// Keep saucers from stacking up on top of each other by pushing apart any that get too close.
+/// Saucers which are grabbing or carrying treasure hold their course, but still push others away.
+pub(crate) fn separate_saucers(
+    mut q_saucers: Query<(
+        Entity,
+        &mut Saucer,
+        &StateMachine<SaucerState>,
+        &UnitPosition,
+    )>,
+    q_others: Query<(), With<Saucer>>,
+    time: Res<Time>,
+    r_playfield: Res<Playfield>,
+    r_hash: Res<SpatialHash>,
+    r_tuning: Res<Tuning>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut saucer, machine, position) in q_saucers.iter_mut() {
+        if !matches!(
+            machine.state(),
+            SaucerState::Patrolling | SaucerState::Seeking | SaucerState::Queened
+        ) {
+            continue;
+        }
+        let neighbors = r_hash
+            .within(position.0, SEPARATION_RADIUS)
+            .filter(|(other, _)| *other != entity && q_others.contains(*other))
+            .map(|(_, other_pos)| other_pos);
+        let force = separation(&r_playfield, position.0, neighbors, SEPARATION_RADIUS);
+        if force != Vec2::ZERO {
+            saucer.velocity = apply_steering(
+                saucer.velocity,
+                force * SEPARATION_ACCEL,
+                SEPARATION_ACCEL,
+                r_tuning.saucer.speed(),
+                dt,
+            );
+        }
+    }
+}
+
+/// Watch for laser shots approaching along our flight level, and randomly decide whether to
+/// dodge them. The chance of dodging increases with difficulty. Saucers which are busy
+/// abducting treasure don't dodge.
+pub(crate) fn dodge_shots(
+    mut q_saucers: Query<(&mut Dodger, &StateMachine<SaucerState>, &mut UnitPosition)>,
+    q_shots: Query<(&LaserShot, &UnitPosition), Without<Dodger>>,
+    time: Res<Time>,
+    mut rng: ResMut<RandomGenerator>,
+    r_playfield: Res<Playfield>,
+    r_difficulty: Res<Difficulty>,
+) {
+    let dt = time.delta_secs();
+    let chance = (DODGE_CHANCE_BASE + r_difficulty.0 * DODGE_CHANCE_SCALE).min(DODGE_CHANCE_MAX);
+    for (mut dodger, machine, mut position) in q_saucers.iter_mut() {
+        if dodger.remaining > 0.0 {
+            dodger.remaining -= dt;
+            position.0.y = (position.0.y + dodger.velocity * dt).clamp(-0.45, 0.45);
+            continue;
+        }
+
+        dodger.cooldown -= dt;
+        if dodger.cooldown > 0.0
+            || !matches!(
+                machine.state(),
+                SaucerState::Patrolling | SaucerState::Seeking | SaucerState::Queened
+            )
+        {
+            continue;
+        }
+
+        let threat = q_shots.iter().find(|(shot, shot_pos)| {
+            let dx = r_playfield.relative(position.0.x, shot_pos.0.x);
+            (shot_pos.0.y - position.0.y).abs() < DODGE_BAND
+                && dx.abs() < DODGE_RANGE
+                && dx.signum() == shot.speed().signum()
+        });
+        let Some((_, shot_pos)) = threat else {
+            continue;
+        };
+
+        // Only roll the dice once per threat
+        dodger.cooldown = DODGE_COOLDOWN;
+        if rng.0.random_range(0.0..1.0) < chance {
+            let direction = if position.0.y > shot_pos.0.y {
+                1.0
+            } else {
+                -1.0
+            };
+            dodger.velocity = direction * DODGE_SPEED;
+            dodger.remaining = DODGE_TIME;
+        }
+    }
+}
+
+/// Wander between randomly-chosen waypoints.
+fn patrol(
+    saucer: &mut Saucer,
+    position: &mut Vec2,
+    rng: &mut ChaCha8Rng,
+    playfield: &Playfield,
+    tuning: &SaucerTuning,
+    speed_scale: f32,
+    dt: f32,
+) {
+    saucer.timer -= dt;
+    if saucer.timer <= 0.0 || playfield.distance(*position, saucer.waypoint) < 0.05 {
+        saucer.waypoint = choose_waypoint(rng, *position);
+        saucer.timer = rng.random_range(1.0..2.0);
+    }
+
+    let max_speed = tuning.speed() * speed_scale;
+    let force = arrive(
+        playfield,
+        *position,
+        saucer.velocity,
+        saucer.waypoint,
+        max_speed.x,
+        0.2,
+    );
+    saucer.velocity = apply_steering(
+        saucer.velocity,
+        force,
+        tuning.acceleration * speed_scale,
+        max_speed,
+        dt,
+    );
+    *position += saucer.velocity * dt;
+    position.x = playfield.relative(position.x, 0.0);
+}
+
+/// Pick a new patrol waypoint within range of the current position. The horizontal coordinate
+/// may be outside of the playfield; steering takes care of the wraparound.
+fn choose_waypoint(rng: &mut ChaCha8Rng, position: Vec2) -> Vec2 {
+    Vec2::new(
+        position.x + rng.random_range(-PATROL_RANGE..PATROL_RANGE),
+        rng.random_range(-0.4..0.4),
+    )
+}
+
+fn choose_random_angle(rng: &mut ChaCha8Rng, tuning: &SaucerTuning) -> Vec2 {
     let dir: f32 = rng.random_range(0.0..8.0);
     let angle = dir.trunc() * std::f32::consts::FRAC_PI_4; // 0, 45, ..., 315 deg
-    Vec2::new(angle.cos(), angle.sin()) * Vec2::new(SAUCER_SPEED_X, SAUCER_SPEED_Y)
+    Vec2::new(angle.cos(), angle.sin()) * tuning.speed()
 }
 
-/// Action triggered when a saucer is hit by a player shot. We despawn the saucer and replace
-/// it with an explosion (both sound and visuals).
+/// Action triggered when a saucer is hit by a player shot. Once its health is used up, we
+/// despawn the saucer and replace it with an explosion (both sound and visuals). Any treasure it
+/// was carrying is dropped.
 fn saucer_hit(
     trigger: Trigger<EnemyHit>,
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     q_position: Query<&UnitPosition>,
+    mut q_saucers: Query<(
+        &mut Saucer,
+        &StateMachine<SaucerState>,
+        Has<FormationLeader>,
+    )>,
+    mut q_treasures: Query<&mut Treasure>,
+    mut rng: ResMut<RandomGenerator>,
+    r_wave: Res<WaveConfig>,
+    r_parachute: Res<ParachuteMesh>,
 ) {
     let Ok(unit_pos) = q_position.get(trigger.target()) else {
         return;
     };
     let position = unit_pos.0;
+
+    let Ok((mut saucer, machine, leader)) = q_saucers.get_mut(trigger.target()) else {
+        return;
+    };
+    saucer.health -= trigger.event().damage;
+    if saucer.health > 0.0 {
+        return;
+    }
+
+    // Drop the loot
+    let carried = saucer.target.filter(|target| {
+        q_treasures
+            .get(*target)
+            .is_ok_and(|treasure| treasure.state == TreasureState::Abducted)
+    });
+    if let Some(target) = carried {
+        if let Ok(mut treasure) = q_treasures.get_mut(target) {
+            treasure.drop();
+        }
+        let parachute = q_position
+            .get(target)
+            .ok()
+            .filter(|_| r_wave.has(WaveMutator::Parachutes));
+        if let Some(treasure_pos) = parachute {
+            open_parachute(&mut commands, target, treasure_pos.0, &r_parachute);
+        }
+    }
+
     commands.entity(trigger.target()).despawn();
-    commands.spawn((
-        AudioPlayer::new(asset_server.load("sounds/softexplode.ogg")),
-        PlaybackSettings {
-            mode: PlaybackMode::Despawn,
-            ..default()
-        },
-    ));
+    let kind = match machine.state() {
+        SaucerState::Queened => EnemyKind::Queen,
+        _ if leader => EnemyKind::Leader,
+        _ => EnemyKind::Saucer,
+    };
+    commands.trigger(EnemyDestroyed { kind, position });
+    commands.spawn(ExplosionSound::for_enemy(kind).play(&asset_server, &mut rng.0));
     commands.spawn((
         FlareEffect {
             size: 0.01,
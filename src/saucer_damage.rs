@@ -0,0 +1,119 @@
+//! Progressive damage on wounded saucers: as a saucer's health drops it throws out sparks,
+//! trails smoke, and its lights flicker, so that the player can spot enemies worth finishing off.
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    RandomGenerator, UnitPosition,
+    explosion::{DustEffect, SparkEffect},
+    saucer::Saucer,
+};
+
+/// Damage effects for a wounded saucer.
+#[derive(Component, Default, Debug)]
+pub struct SaucerDamage {
+    /// Time until the next spark
+    spark_timer: f32,
+
+    /// Time until the next puff of smoke
+    smoke_timer: f32,
+
+    /// This saucer's own copies of its model materials, with their original emissive colors
+    materials: Vec<(Handle<StandardMaterial>, LinearRgba)>,
+}
+
+/// Time between sparks on a saucer which is almost destroyed, in seconds. Less damaged saucers
+/// spark less often.
+const SPARK_INTERVAL: f32 = 0.1;
+
+/// Time between smoke puffs, in seconds
+const SMOKE_INTERVAL: f32 = 0.08;
+
+/// Saucers must have lost at least this much of their health before they trail smoke.
+const SMOKE_THRESHOLD: f32 = 0.3;
+
+const SMOKE_COLOR: Color = Color::srgb(0.15, 0.15, 0.17);
+
+/// Start the damage effects once a saucer has been wounded. The saucer model's materials are
+/// shared between all saucers, so the wounded saucer is given its own copies to flicker.
+pub(crate) fn mark_damaged_saucers(
+    mut commands: Commands,
+    q_saucers: Query<(Entity, &Saucer), Without<SaucerDamage>>,
+    q_children: Query<&Children>,
+    mut q_meshes: Query<&mut MeshMaterial3d<StandardMaterial>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for (entity, saucer) in q_saucers.iter() {
+        if saucer.health_fraction() >= 1.0 {
+            continue;
+        }
+        let mut damage = SaucerDamage::default();
+        for child in q_children.iter_descendants(entity) {
+            let Ok(mut mesh_material) = q_meshes.get_mut(child) else {
+                continue;
+            };
+            let Some(material) = materials.get(&mesh_material.0).cloned() else {
+                continue;
+            };
+            let emissive = material.emissive;
+            let handle = materials.add(material);
+            mesh_material.0 = handle.clone();
+            damage.materials.push((handle, emissive));
+        }
+        commands.entity(entity).insert(damage);
+    }
+}
+
+/// Emit sparks and smoke, and flicker the lights, at a rate which rises with the damage taken.
+pub(crate) fn update_saucer_damage(
+    mut commands: Commands,
+    mut q_saucers: Query<(&Saucer, &mut SaucerDamage, &UnitPosition)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut r_rng: ResMut<RandomGenerator>,
+    r_time: Res<Time>,
+) {
+    let dt = r_time.delta_secs();
+    for (saucer, mut damage, position) in q_saucers.iter_mut() {
+        let wound = 1.0 - saucer.health_fraction();
+
+        damage.spark_timer -= dt;
+        if damage.spark_timer <= 0.0 {
+            damage.spark_timer = SPARK_INTERVAL / wound.max(0.1);
+            commands.spawn((
+                SparkEffect {
+                    velocity: Vec2::new(
+                        r_rng.0.random_range(-0.3..0.3),
+                        r_rng.0.random_range(0.0..0.3),
+                    ),
+                },
+                UnitPosition(position.0),
+            ));
+        }
+
+        if wound >= SMOKE_THRESHOLD {
+            damage.smoke_timer -= dt;
+            if damage.smoke_timer <= 0.0 {
+                damage.smoke_timer = SMOKE_INTERVAL;
+                commands.spawn((
+                    DustEffect {
+                        size: 0.015 + 0.02 * wound,
+                        color: SMOKE_COLOR,
+                    },
+                    UnitPosition(position.0),
+                ));
+            }
+        }
+
+        // Lights cut out at random, more often the worse the damage.
+        let flicker = if r_rng.0.random_bool((wound * 0.6) as f64) {
+            r_rng.0.random_range(0.0..0.5)
+        } else {
+            1.0
+        };
+        for (handle, emissive) in damage.materials.iter() {
+            if let Some(material) = materials.get_mut(handle) {
+                material.emissive = *emissive * flicker;
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Scanner-style minimap. Instead of laying out blips in the UI, a dedicated low-resolution
+//! camera renders a simple marker for each blip into a texture, which is shown in the header
+//! through a shader that adds a sweep line and static.
+use bevy::{
+    asset::RenderAssetUsages,
+    image::ImageSampler,
+    prelude::*,
+    render::{
+        camera::{RenderTarget, ScalingMode},
+        render_resource::{
+            AsBindGroup, Extent3d, ShaderRef, TextureDimension, TextureFormat, TextureUsages,
+        },
+        view::RenderLayers,
+    },
+};
+
+use crate::{
+    Minimap,
+    minimap::MinimapBlip,
+    settings::{MinimapStyle, Settings},
+};
+
+/// Marker for the camera which renders the scanner texture
+#[derive(Component, Default, Debug)]
+pub struct ScannerCamera;
+
+/// Marker for the UI node which displays the scanner texture
+#[derive(Component, Default, Debug)]
+pub struct ScannerDisplay;
+
+/// A marker drawn by the scanner camera, mirroring a minimap blip.
+#[derive(Component, Debug)]
+pub struct ScannerMarker {
+    blip: Entity,
+}
+
+/// Shared mesh for scanner markers
+#[derive(Resource, Default, Debug)]
+pub struct ScannerMesh(Handle<Mesh>);
+
+/// Size of the scanner texture, in pixels
+const SCANNER_WIDTH: u32 = 256;
+const SCANNER_HEIGHT: u32 = 64;
+
+/// Render layer which only the scanner camera sees
+const SCANNER_LAYER: usize = 1;
+
+pub(crate) fn setup_scanner(
+    mut commands: Commands,
+    q_minimap: Query<Entity, With<Minimap>>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ScannerMaterial>>,
+    mut r_mesh: ResMut<ScannerMesh>,
+    r_settings: Res<Settings>,
+) {
+    let mut image = Image::new_fill(
+        Extent3d {
+            width: SCANNER_WIDTH,
+            height: SCANNER_HEIGHT,
+            ..default()
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Bgra8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    // Keep the chunky pixels
+    image.sampler = ImageSampler::nearest();
+    let image = images.add(image);
+    let enabled = r_settings.hud.minimap_style == MinimapStyle::Scanner;
+
+    // The camera looks at a unit square, matching the minimap's 0..1 coordinates.
+    commands.spawn((
+        Camera2d,
+        Camera {
+            target: RenderTarget::Image(image.clone().into()),
+            clear_color: ClearColorConfig::Custom(Color::BLACK),
+            order: -1,
+            is_active: enabled,
+            ..default()
+        },
+        Projection::from(OrthographicProjection {
+            scaling_mode: ScalingMode::Fixed {
+                width: 1.0,
+                height: 1.0,
+            },
+            ..OrthographicProjection::default_2d()
+        }),
+        RenderLayers::layer(SCANNER_LAYER),
+        ScannerCamera,
+    ));
+
+    r_mesh.0 = meshes.add(Rectangle::new(1.0, 1.0));
+
+    let Ok(minimap) = q_minimap.single() else {
+        return;
+    };
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Percent(0.0),
+            right: Val::Percent(0.0),
+            top: Val::Percent(0.0),
+            bottom: Val::Percent(0.0),
+            ..default()
+        },
+        MaterialNode(materials.add(ScannerMaterial {
+            params: Vec4::ZERO,
+            texture: image,
+        })),
+        if enabled {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        },
+        ScannerDisplay,
+        ChildOf(minimap),
+    ));
+}
+
+/// Give each new minimap blip a marker for the scanner camera.
+pub(crate) fn on_add_scanner_marker(
+    trigger: Trigger<OnAdd, MinimapBlip>,
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    r_mesh: Res<ScannerMesh>,
+) {
+    commands.spawn((
+        Mesh2d(r_mesh.0.clone()),
+        MeshMaterial2d(materials.add(Color::NONE)),
+        Transform::default(),
+        RenderLayers::layer(SCANNER_LAYER),
+        ScannerMarker {
+            blip: trigger.target(),
+        },
+    ));
+}
+
+/// Switch the scanner on or off when the minimap style changes.
+pub(crate) fn apply_scanner_settings(
+    r_settings: Res<Settings>,
+    mut q_camera: Query<&mut Camera, With<ScannerCamera>>,
+    mut q_display: Query<&mut Visibility, With<ScannerDisplay>>,
+) {
+    let enabled = r_settings.hud.minimap_style == MinimapStyle::Scanner;
+    for mut camera in q_camera.iter_mut() {
+        camera.is_active = enabled;
+    }
+    for mut visibility in q_display.iter_mut() {
+        *visibility = if enabled {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+/// Move scanner markers to follow their blips, and advance the sweep.
+pub(crate) fn update_scanner(
+    mut commands: Commands,
+    mut q_markers: Query<(
+        Entity,
+        &ScannerMarker,
+        &MeshMaterial2d<ColorMaterial>,
+        &mut Transform,
+    )>,
+    q_blips: Query<(&MinimapBlip, &BackgroundColor)>,
+    q_display: Query<&MaterialNode<ScannerMaterial>, With<ScannerDisplay>>,
+    mut color_materials: ResMut<Assets<ColorMaterial>>,
+    mut scanner_materials: ResMut<Assets<ScannerMaterial>>,
+    r_settings: Res<Settings>,
+    r_time: Res<Time<Real>>,
+) {
+    for (entity, marker, material, mut transform) in q_markers.iter_mut() {
+        let Ok((blip, color)) = q_blips.get(marker.blip) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let position = blip.position();
+        let size = blip.kind().size();
+        transform.translation = Vec3::new(position.x - 0.5, 0.5 - position.y, 0.0);
+        transform.scale = Vec3::new(
+            size / SCANNER_WIDTH as f32,
+            size / SCANNER_HEIGHT as f32,
+            1.0,
+        );
+        // Only touch the material when the color changes, since that re-uploads it.
+        let stale = color_materials
+            .get(material.id())
+            .is_some_and(|current| current.color != color.0);
+        if let Some(current) = stale
+            .then(|| color_materials.get_mut(material.id()))
+            .flatten()
+        {
+            current.color = color.0;
+        }
+    }
+
+    if r_settings.hud.minimap_style != MinimapStyle::Scanner {
+        return;
+    }
+    for material in q_display.iter() {
+        if let Some(material) = scanner_materials.get_mut(material.id()) {
+            material.params.x = r_time.elapsed_secs();
+        }
+    }
+}
+
+#[derive(AsBindGroup, Asset, TypePath, Debug, Clone)]
+pub(crate) struct ScannerMaterial {
+    /// x: elapsed time
+    #[uniform(0)]
+    pub(crate) params: Vec4,
+
+    /// Markers rendered by the scanner camera
+    #[texture(1)]
+    #[sampler(2)]
+    pub(crate) texture: Handle<Image>,
+}
+
+impl UiMaterial for ScannerMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "embedded://guardian/assets/shaders/scanner.wgsl".into()
+    }
+}
@@ -0,0 +1,160 @@
+//! Playing animations on glTF scenes. Add [`PlaySceneAnimation`] to an entity with a
+//! [`SceneRoot`], and the clip starts as soon as the scene has been spawned. Entities which
+//! switch between several clips use a [`SceneAnimationSet`] instead.
+use std::time::Duration;
+
+use bevy::{platform::collections::HashMap, prelude::*, scene::SceneInstanceReady};
+
+/// Animation clip to play on an entity's glTF scene once it is ready.
+#[derive(Component, Debug, Clone)]
+pub struct PlaySceneAnimation {
+    /// Clip to play
+    pub clip: Handle<AnimationClip>,
+
+    /// Whether the clip loops
+    pub repeat: bool,
+}
+
+/// A set of looping clips for an entity's glTF scene, of which one plays at a time. Trigger
+/// [`SwitchSceneAnimation`] on the entity to crossfade to another clip.
+#[derive(Component, Debug, Clone)]
+pub struct SceneAnimationSet {
+    /// Graph with one node for each clip
+    graph: Handle<AnimationGraph>,
+
+    /// Graph node for each clip, in the order given
+    nodes: Vec<AnimationNodeIndex>,
+
+    /// Index of the clip currently playing
+    current: usize,
+
+    /// Playback speed of the current clip
+    speed: f32,
+}
+
+impl SceneAnimationSet {
+    /// Build the animation graph for a set of clips. The first clip plays when the scene is
+    /// ready. The result can be cloned to share the graph between entities.
+    pub fn new(
+        clips: impl IntoIterator<Item = Handle<AnimationClip>>,
+        graphs: &mut Assets<AnimationGraph>,
+    ) -> Self {
+        let (graph, nodes) = AnimationGraph::from_clips(clips);
+        Self {
+            graph: graphs.add(graph),
+            nodes,
+            current: 0,
+            speed: 1.0,
+        }
+    }
+}
+
+/// Event triggered on an entity with a [`SceneAnimationSet`] to switch to another clip. If the
+/// scene isn't ready yet, the clip will be played once it is.
+#[derive(Event, Debug)]
+pub struct SwitchSceneAnimation {
+    /// Index of the clip within the set
+    pub clip: usize,
+
+    /// Playback speed
+    pub speed: f32,
+
+    /// Time taken to blend from the previous clip
+    pub crossfade: Duration,
+}
+
+/// The entity within a scene which holds its [`AnimationPlayer`]. Added to the scene root once
+/// the animation has started, so that the animation can be controlled later.
+#[derive(Component, Debug)]
+pub struct SceneAnimationPlayer(pub Entity);
+
+/// Animation graphs built for each clip, shared by every entity playing that clip.
+#[derive(Resource, Default, Debug)]
+pub struct SceneAnimationGraphs(
+    HashMap<AssetId<AnimationClip>, (Handle<AnimationGraph>, AnimationNodeIndex)>,
+);
+
+/// When a scene has been spawned, find its animation player and start the requested clip.
+pub(crate) fn on_scene_ready_play_animation(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    q_animations: Query<(Option<&PlaySceneAnimation>, Option<&SceneAnimationSet>)>,
+    q_children: Query<&Children>,
+    mut q_players: Query<&mut AnimationPlayer>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+    mut r_graphs: ResMut<SceneAnimationGraphs>,
+) {
+    let root = trigger.target();
+    let Ok((single, set)) = q_animations.get(root) else {
+        return;
+    };
+    if single.is_none() && set.is_none() {
+        return;
+    }
+    let Some(child) = q_children
+        .iter_descendants(root)
+        .find(|child| q_players.contains(*child))
+    else {
+        return;
+    };
+    let Ok(mut player) = q_players.get_mut(child) else {
+        return;
+    };
+
+    if let Some(set) = set {
+        let mut transitions = AnimationTransitions::new();
+        transitions
+            .play(&mut player, set.nodes[set.current], Duration::ZERO)
+            .set_speed(set.speed)
+            .repeat();
+        commands
+            .entity(child)
+            .insert((AnimationGraphHandle(set.graph.clone()), transitions));
+    } else if let Some(animation) = single {
+        let (graph, index) = r_graphs
+            .0
+            .entry(animation.clip.id())
+            .or_insert_with(|| {
+                let (graph, index) = AnimationGraph::from_clip(animation.clip.clone());
+                (graphs.add(graph), index)
+            })
+            .clone();
+        let active = player.play(index);
+        if animation.repeat {
+            active.repeat();
+        }
+        commands
+            .entity(child)
+            .insert(AnimationGraphHandle(graph.clone()));
+    }
+    commands.entity(root).insert(SceneAnimationPlayer(child));
+}
+
+/// Crossfade to another clip in an entity's animation set.
+pub(crate) fn on_switch_scene_animation(
+    trigger: Trigger<SwitchSceneAnimation>,
+    mut q_sets: Query<(&mut SceneAnimationSet, Option<&SceneAnimationPlayer>)>,
+    mut q_players: Query<(&mut AnimationPlayer, &mut AnimationTransitions)>,
+) {
+    let event = trigger.event();
+    let Ok((mut set, scene_player)) = q_sets.get_mut(trigger.target()) else {
+        return;
+    };
+    let Some(&node) = set.nodes.get(event.clip) else {
+        warn!("Scene animation set has no clip {}", event.clip);
+        return;
+    };
+    set.current = event.clip;
+    set.speed = event.speed;
+
+    let Some(scene_player) = scene_player else {
+        return;
+    };
+    let Ok((mut player, mut transitions)) = q_players.get_mut(scene_player.0) else {
+        return;
+    };
+    transitions
+        .play(&mut player, node, event.crossfade)
+        .set_speed(event.speed)
+        .repeat();
+}
@@ -0,0 +1,202 @@
+//! Score, and the extra lives and smart bombs earned by reaching score milestones. Smart bombs
+//! are also awarded at the end of each wave, and completing a loop earns a bonus.
+use bevy::prelude::*;
+
+use crate::{
+    game_state::{DespawnOnExit, GameState, Level},
+    settings::Settings,
+    stats::RunStats,
+    ui_audio::UiSound,
+    wave::{WaveComplete, WaveConfig},
+};
+
+/// Event sent whenever points are awarded. Negative points are a penalty.
+#[derive(Event, Debug)]
+pub struct ScoreEvent {
+    pub points: i32,
+}
+
+/// Event sent when the score passes a milestone.
+#[derive(Event, Debug)]
+pub struct MilestoneReached;
+
+/// Spare ships and smart bombs held in reserve.
+#[derive(Resource, Debug)]
+pub struct Reserves {
+    /// Ships remaining after the current one is lost
+    pub lives: u32,
+
+    /// Smart bombs available
+    pub smart_bombs: u32,
+}
+
+impl Default for Reserves {
+    fn default() -> Self {
+        Self {
+            lives: STARTING_LIVES,
+            smart_bombs: STARTING_SMART_BOMBS,
+        }
+    }
+}
+
+/// Score thresholds at which the player is rewarded with an extra life and a smart bomb.
+#[derive(Resource, Debug)]
+pub struct Milestones {
+    /// Points between milestones
+    pub interval: u32,
+
+    /// Score at which the next reward is given
+    next: u32,
+}
+
+impl Milestones {
+    /// Count milestones from a score of zero.
+    pub fn reset(&mut self) {
+        self.next = self.interval;
+    }
+}
+
+impl Default for Milestones {
+    fn default() -> Self {
+        Self {
+            interval: MILESTONE_INTERVAL,
+            next: MILESTONE_INTERVAL,
+        }
+    }
+}
+
+/// Marker for the HUD text showing lives and smart bombs
+#[derive(Component, Default, Debug)]
+pub struct ReservesDisplay {
+    /// Time remaining in the milestone highlight
+    flash: f32,
+}
+
+const STARTING_LIVES: u32 = 2;
+const STARTING_SMART_BOMBS: u32 = 3;
+const MILESTONE_INTERVAL: u32 = 10000;
+
+/// Points awarded for completing the final wave of a loop
+const LOOP_BONUS: i32 = 10000;
+
+/// Length of the highlight when a milestone is reached, in seconds
+const FLASH_TIME: f32 = 1.5;
+
+const RESERVES_COLOR: Color = Color::srgb(0.7, 0.7, 0.8);
+const FLASH_COLOR: Color = Color::srgb(1.0, 0.9, 0.3);
+
+/// Add points to the score, awarding any milestones passed along the way. Points (but not
+/// penalties) are multiplied in bonus score waves.
+pub(crate) fn on_score(
+    trigger: Trigger<ScoreEvent>,
+    mut commands: Commands,
+    mut r_stats: ResMut<RunStats>,
+    mut r_milestones: ResMut<Milestones>,
+    mut r_reserves: ResMut<Reserves>,
+    r_wave: Res<WaveConfig>,
+) {
+    let mut points = trigger.event().points;
+    if points > 0 {
+        points *= r_wave.score_multiplier();
+    }
+    r_stats.score = r_stats.score.saturating_add_signed(points);
+    while r_milestones.interval > 0 && r_stats.score >= r_milestones.next {
+        r_milestones.next += r_milestones.interval;
+        r_reserves.lives += 1;
+        r_reserves.smart_bombs += 1;
+        commands.trigger(UiSound::ExtraLife);
+        commands.trigger(MilestoneReached);
+    }
+}
+
+/// Award a smart bomb for completing a wave.
+pub(crate) fn award_wave_smart_bomb(
+    _trigger: Trigger<WaveComplete>,
+    mut r_reserves: ResMut<Reserves>,
+) {
+    r_reserves.smart_bombs += 1;
+}
+
+/// Completing the final wave of a loop counts towards the run's loops, and earns a bonus.
+pub(crate) fn award_loop_bonus(
+    _trigger: Trigger<WaveComplete>,
+    mut commands: Commands,
+    mut r_stats: ResMut<RunStats>,
+    r_level: Res<Level>,
+) {
+    if !r_level.is_final_wave() {
+        return;
+    }
+    r_stats.loops += 1;
+    commands.trigger(ScoreEvent { points: LOOP_BONUS });
+}
+
+/// Start a new run with a full set of reserves, plus any extra lives from the assist options.
+pub(crate) fn start_run_reserves(
+    mut r_reserves: ResMut<Reserves>,
+    mut r_milestones: ResMut<Milestones>,
+    r_settings: Res<Settings>,
+) {
+    *r_reserves = Reserves::default();
+    r_reserves.lives += r_settings.assist.extra_lives;
+    r_milestones.reset();
+}
+
+pub(crate) fn spawn_reserves_display(mut commands: Commands, r_reserves: Res<Reserves>) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(12.0),
+            bottom: Val::Px(8.0),
+            ..default()
+        },
+        Text::new(reserves_text(&r_reserves)),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(RESERVES_COLOR),
+        ReservesDisplay::default(),
+        DespawnOnExit(GameState::Playing),
+    ));
+}
+
+pub(crate) fn update_reserves_display(
+    mut q_display: Query<&mut Text, With<ReservesDisplay>>,
+    r_reserves: Res<Reserves>,
+) {
+    for mut text in q_display.iter_mut() {
+        text.0 = reserves_text(&r_reserves);
+    }
+}
+
+pub(crate) fn on_milestone_flash(
+    _trigger: Trigger<MilestoneReached>,
+    mut q_display: Query<&mut ReservesDisplay>,
+) {
+    for mut display in q_display.iter_mut() {
+        display.flash = FLASH_TIME;
+    }
+}
+
+/// Blink the reserves display after a milestone.
+pub(crate) fn update_milestone_flash(
+    mut q_display: Query<(&mut ReservesDisplay, &mut TextColor)>,
+    r_time: Res<Time<Real>>,
+) {
+    for (mut display, mut color) in q_display.iter_mut() {
+        if display.flash <= 0.0 {
+            continue;
+        }
+        display.flash -= r_time.delta_secs();
+        let blink = (display.flash * 8.0).fract() > 0.5 && display.flash > 0.0;
+        color.0 = if blink { FLASH_COLOR } else { RESERVES_COLOR };
+    }
+}
+
+fn reserves_text(reserves: &Reserves) -> String {
+    format!(
+        "Ships: {}   Smart bombs: {}",
+        reserves.lives, reserves.smart_bombs
+    )
+}
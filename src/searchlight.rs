@@ -0,0 +1,160 @@
+//! Searchlight cones cast downward by patrolling saucers. The beam sweeps back and forth, and
+//! shortly before the saucer goes after a treasure it locks onto the one it is about to seek.
+use bevy::{asset::RenderAssetUsages, prelude::*, render::mesh::PrimitiveTopology};
+
+use crate::{
+    Playfield, SeamMirrored, TREASURE_DEPTH, UnitPosition,
+    ai::StateMachine,
+    saucer::{ClaimedBy, Saucer, SaucerState},
+    treasure::{Treasure, TreasureState},
+};
+
+/// The searchlight belonging to a saucer
+#[derive(Component, Debug)]
+#[relationship(relationship_target = Searchlights)]
+pub struct SearchlightOf(pub Entity);
+
+#[derive(Component, Debug)]
+#[relationship_target(relationship = SearchlightOf, linked_spawn)]
+pub struct Searchlights(Vec<Entity>);
+
+#[derive(Resource, Default, Debug)]
+pub struct SearchlightMesh {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+/// Length of the beam
+const BEAM_LENGTH: f32 = 1.1;
+
+/// Half-width of the beam at its far end
+const BEAM_SPREAD: f32 = 0.1;
+
+/// Maximum sweep angle either side of straight down, in radians
+const SWEEP_ANGLE: f32 = 0.5;
+
+/// Sweep rate, in radians per second
+const SWEEP_RATE: f32 = 1.3;
+
+/// How long before seeking the beam locks onto its target, in seconds
+const TELEGRAPH_TIME: f32 = 1.5;
+
+/// Rate at which the beam turns towards its aim angle
+const TURN_RATE: f32 = 6.0;
+
+pub(crate) fn setup_searchlights(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut searchlight_mesh: ResMut<SearchlightMesh>,
+) {
+    // A triangle with its apex at the origin, pointing down, fading towards the far end.
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vec![
+            [0.0, 0.0, 0.0],
+            [-BEAM_SPREAD, -BEAM_LENGTH, 0.0],
+            [BEAM_SPREAD, -BEAM_LENGTH, 0.0],
+        ],
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; 3]);
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_COLOR,
+        vec![
+            [1.0, 1.0, 0.7, 0.35],
+            [1.0, 1.0, 0.7, 0.0],
+            [1.0, 1.0, 0.7, 0.0],
+        ],
+    );
+    searchlight_mesh.mesh = meshes.add(mesh);
+    searchlight_mesh.material = materials.add(StandardMaterial {
+        alpha_mode: AlphaMode::Add,
+        unlit: true,
+        cull_mode: None,
+        ..default()
+    });
+}
+
+/// Give each saucer a searchlight. The spot light child brightens treasures under the beam.
+pub(crate) fn on_add_saucer(
+    trigger: Trigger<OnAdd, Saucer>,
+    mut commands: Commands,
+    searchlight_mesh: Res<SearchlightMesh>,
+) {
+    commands.spawn((
+        SearchlightOf(trigger.target()),
+        UnitPosition::default(),
+        Mesh3d(searchlight_mesh.mesh.clone()),
+        MeshMaterial3d(searchlight_mesh.material.clone()),
+        Transform::from_xyz(0.0, 0.0, TREASURE_DEPTH + 1.0),
+        Visibility::Hidden,
+        SeamMirrored,
+        children![(
+            SpotLight {
+                color: Color::srgb(1.0, 1.0, 0.7),
+                intensity: 25_000.0,
+                range: BEAM_LENGTH * 1.5,
+                inner_angle: 0.05,
+                outer_angle: BEAM_SPREAD / BEAM_LENGTH,
+                ..default()
+            },
+            Transform::default().looking_to(Vec3::NEG_Y, Vec3::Z),
+        )],
+    ));
+}
+
+/// Follow the saucer, and sweep the beam. The beam is only shown while patrolling.
+pub(crate) fn update_searchlights(
+    mut q_searchlights: Query<(
+        &SearchlightOf,
+        &mut UnitPosition,
+        &mut Transform,
+        &mut Visibility,
+    )>,
+    q_saucers: Query<(&Saucer, &StateMachine<SaucerState>, &UnitPosition), Without<SearchlightOf>>,
+    q_treasures: Query<
+        (&Treasure, &UnitPosition, Has<ClaimedBy>),
+        (Without<Saucer>, Without<SearchlightOf>),
+    >,
+    r_playfield: Res<Playfield>,
+    r_time: Res<Time>,
+) {
+    let t = r_time.elapsed_secs();
+    for (searchlight_of, mut position, mut transform, mut visibility) in q_searchlights.iter_mut() {
+        let Ok((saucer, machine, saucer_pos)) = q_saucers.get(searchlight_of.0) else {
+            continue;
+        };
+        if machine.state() != SaucerState::Patrolling {
+            visibility.set_if_neq(Visibility::Hidden);
+            continue;
+        }
+        visibility.set_if_neq(Visibility::Inherited);
+        position.0 = saucer_pos.0;
+
+        // Each saucer sweeps out of phase with the others.
+        let phase = searchlight_of.0.index() as f32;
+        let mut aim = (t * SWEEP_RATE + phase).sin() * SWEEP_ANGLE;
+
+        // About to seek: lock onto the treasure that will be chosen.
+        if saucer.abduct_timer() < TELEGRAPH_TIME {
+            let nearest = q_treasures
+                .iter()
+                .filter(|(treasure, _, claimed)| {
+                    !claimed && treasure.state == TreasureState::Standing
+                })
+                .map(|(_, treasure_pos, _)| r_playfield.delta(saucer_pos.0, treasure_pos.0))
+                .min_by(|a, b| a.length().total_cmp(&b.length()));
+            if let Some(delta) = nearest {
+                aim = delta.x.atan2(-delta.y).clamp(-SWEEP_ANGLE, SWEEP_ANGLE);
+            }
+        }
+
+        // The beam points down, so a positive angle turns it to the right.
+        let current = -transform.rotation.to_euler(EulerRot::XYZ).2;
+        let angle = current.lerp(aim, (r_time.delta_secs() * TURN_RATE).min(1.0));
+        transform.rotation = Quat::from_rotation_z(-angle);
+    }
+}
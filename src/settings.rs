@@ -0,0 +1,435 @@
+//! Player settings, persisted between sessions.
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::profile::{load_data, save_data};
+
+/// Where the minimap is placed on screen
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinimapPosition {
+    #[default]
+    Top,
+    Bottom,
+}
+
+/// How the minimap is drawn
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinimapStyle {
+    /// Blips laid out directly in the UI
+    #[default]
+    Blips,
+
+    /// Rendered to a texture by a dedicated camera, with a sweeping scanner effect
+    Scanner,
+}
+
+/// HUD layout options
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct HudSettings {
+    /// Scale factor applied to all UI
+    pub scale: f32,
+
+    /// Placement of the minimap header
+    pub minimap_position: MinimapPosition,
+
+    /// Whether the minimap header is shown
+    pub show_minimap: bool,
+
+    /// How the minimap is drawn
+    pub minimap_style: MinimapStyle,
+
+    /// Whether directional hit indicators are shown
+    pub show_hit_indicators: bool,
+
+    /// Whether the low-shield border pulse is shown
+    pub show_shield_alert: bool,
+
+    /// Whether the run timer and wave splits are shown
+    pub show_timer: bool,
+
+    /// Whether the damage dealt by each hit is shown above the enemy
+    pub show_damage_numbers: bool,
+
+    /// Whether the ticker of recent events is shown
+    pub show_ticker: bool,
+}
+
+impl Default for HudSettings {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            minimap_position: MinimapPosition::Top,
+            show_minimap: true,
+            minimap_style: MinimapStyle::Blips,
+            show_hit_indicators: true,
+            show_shield_alert: true,
+            show_timer: false,
+            show_damage_numbers: false,
+            show_ticker: true,
+        }
+    }
+}
+
+/// Level of background and effect detail, so that slower machines can dial down the scene.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsQuality {
+    Low,
+    Medium,
+    #[default]
+    High,
+}
+
+impl GraphicsQuality {
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            GraphicsQuality::Low => "Low",
+            GraphicsQuality::Medium => "Medium",
+            GraphicsQuality::High => "High",
+        }
+    }
+
+    /// The next quality level, wrapping around.
+    pub fn next(self) -> Self {
+        match self {
+            GraphicsQuality::Low => GraphicsQuality::Medium,
+            GraphicsQuality::Medium => GraphicsQuality::High,
+            GraphicsQuality::High => GraphicsQuality::Low,
+        }
+    }
+
+    /// Multiplier for the number of stars in the starfield
+    pub fn star_density(self) -> f32 {
+        match self {
+            GraphicsQuality::Low => 0.4,
+            GraphicsQuality::Medium => 0.7,
+            GraphicsQuality::High => 1.0,
+        }
+    }
+
+    /// Number of fragments in each shrapnel explosion
+    pub fn shrapnel_fragments(self) -> usize {
+        match self {
+            GraphicsQuality::Low => 16,
+            GraphicsQuality::Medium => 32,
+            GraphicsQuality::High => 64,
+        }
+    }
+
+    /// Multiplier for how often ambient background effects, such as nebula lightning, occur
+    pub fn background_frequency(self) -> f32 {
+        match self {
+            GraphicsQuality::Low => 0.25,
+            GraphicsQuality::Medium => 0.5,
+            GraphicsQuality::High => 1.0,
+        }
+    }
+}
+
+/// How the frame rate is limited. Capping the frame rate saves power, since the scene is simple
+/// enough to otherwise render far faster than the display can show.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameLimit {
+    /// Wait for the display's vertical sync
+    #[default]
+    Vsync,
+
+    /// Render as fast as possible
+    Uncapped,
+
+    /// Cap the frame rate without vsync
+    Fps30,
+    Fps60,
+    Fps120,
+}
+
+impl FrameLimit {
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            FrameLimit::Vsync => "Vsync",
+            FrameLimit::Uncapped => "Uncapped",
+            FrameLimit::Fps30 => "30 FPS",
+            FrameLimit::Fps60 => "60 FPS",
+            FrameLimit::Fps120 => "120 FPS",
+        }
+    }
+
+    /// The next frame limit, wrapping around.
+    pub fn next(self) -> Self {
+        match self {
+            FrameLimit::Vsync => FrameLimit::Uncapped,
+            FrameLimit::Uncapped => FrameLimit::Fps30,
+            FrameLimit::Fps30 => FrameLimit::Fps60,
+            FrameLimit::Fps60 => FrameLimit::Fps120,
+            FrameLimit::Fps120 => FrameLimit::Vsync,
+        }
+    }
+
+    /// Frame rate cap, if any
+    pub fn max_fps(self) -> Option<f32> {
+        match self {
+            FrameLimit::Vsync | FrameLimit::Uncapped => None,
+            FrameLimit::Fps30 => Some(30.0),
+            FrameLimit::Fps60 => Some(60.0),
+            FrameLimit::Fps120 => Some(120.0),
+        }
+    }
+}
+
+/// Player skill level. This adjusts how forgiving the game is, independently of the wave
+/// difficulty.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkillLevel {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl SkillLevel {
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            SkillLevel::Easy => "Easy",
+            SkillLevel::Normal => "Normal",
+            SkillLevel::Hard => "Hard",
+        }
+    }
+
+    /// The next skill level, wrapping around.
+    pub fn next(self) -> Self {
+        match self {
+            SkillLevel::Easy => SkillLevel::Normal,
+            SkillLevel::Normal => SkillLevel::Hard,
+            SkillLevel::Hard => SkillLevel::Easy,
+        }
+    }
+
+    /// Multiplier for the size of the ship's hurtbox
+    pub fn hurtbox_scale(self) -> f32 {
+        match self {
+            SkillLevel::Easy => 0.8,
+            SkillLevel::Normal => 1.0,
+            SkillLevel::Hard => 1.2,
+        }
+    }
+}
+
+/// Gameplay options
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct GameplaySettings {
+    /// How forgiving the game is
+    pub skill: SkillLevel,
+
+    /// Whether enemy aggression adapts to how the player is doing
+    pub adaptive: bool,
+
+    /// Level of challenge targeted by adaptive difficulty, from 0 (relaxed) to 1 (intense)
+    pub challenge: f32,
+
+    /// Whether a log of each run's events is written for playtest analysis. This can only be
+    /// turned on in the settings file.
+    pub run_log: bool,
+}
+
+impl Default for GameplaySettings {
+    fn default() -> Self {
+        Self {
+            skill: SkillLevel::Normal,
+            adaptive: false,
+            challenge: 0.5,
+            run_log: false,
+        }
+    }
+}
+
+/// Assist options, which make the game easier for players who need it. Runs played with any
+/// assist enabled are flagged in the high score table.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AssistSettings {
+    /// Speed of the game clock, from 0.5 to 1.0
+    pub game_speed: f32,
+
+    /// Ships added to the reserves at the start of a run
+    pub extra_lives: u32,
+
+    /// Whether holding Fire keeps firing
+    pub auto_fire: bool,
+
+    /// Whether the guns heat up and can overheat when auto-fire is enabled
+    pub laser_heat: bool,
+}
+
+impl Default for AssistSettings {
+    fn default() -> Self {
+        Self {
+            game_speed: 1.0,
+            extra_lives: 0,
+            auto_fire: false,
+            laser_heat: true,
+        }
+    }
+}
+
+impl AssistSettings {
+    /// Whether any assist is enabled.
+    pub fn active(&self) -> bool {
+        self.game_speed < 1.0 || self.extra_lives > 0 || self.auto_fire
+    }
+
+    /// Whether firing builds up laser heat. This only applies with auto-fire.
+    pub fn laser_heat_enabled(&self) -> bool {
+        self.auto_fire && self.laser_heat
+    }
+}
+
+/// Smallest game speed allowed by the assist options
+pub(crate) const MIN_GAME_SPEED: f32 = 0.5;
+
+/// Most extra lives allowed by the assist options
+pub(crate) const MAX_EXTRA_LIVES: u32 = 3;
+
+/// Graphics options
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct GraphicsSettings {
+    /// Detail level of the background and effects
+    pub quality: GraphicsQuality,
+
+    /// Vsync or frame rate cap
+    pub frame_limit: FrameLimit,
+
+    /// Whether to save power by updating background effects less often, and capping the frame
+    /// rate while paused or unfocused
+    pub low_power: bool,
+}
+
+impl GraphicsSettings {
+    /// Multiplier for how often ambient background effects occur, which is reduced further in
+    /// low-power mode
+    pub fn background_frequency(&self) -> f32 {
+        let scale = if self.low_power { 0.5 } else { 1.0 };
+        self.quality.background_frequency() * scale
+    }
+}
+
+/// Audio options
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AudioSettings {
+    /// Whether kill streaks are called out
+    pub announcer: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self { announcer: true }
+    }
+}
+
+/// All player settings
+#[derive(Resource, Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct Settings {
+    pub hud: HudSettings,
+    pub graphics: GraphicsSettings,
+    pub gameplay: GameplaySettings,
+    pub audio: AudioSettings,
+    pub assist: AssistSettings,
+}
+
+pub(crate) fn load_settings() -> Settings {
+    load_data("settings.ron")
+}
+
+pub(crate) fn save_settings(settings: &Settings) {
+    save_data("settings.ron", settings);
+}
+
+/// Adjust graphics settings from the pause menu: G to cycle the quality level, V to cycle the
+/// frame limit, B to toggle low-power mode.
+pub(crate) fn adjust_graphics_settings(
+    r_keys: Res<ButtonInput<KeyCode>>,
+    mut r_settings: ResMut<Settings>,
+) {
+    if r_keys.just_pressed(KeyCode::KeyG) {
+        r_settings.graphics.quality = r_settings.graphics.quality.next();
+        info!("Graphics quality: {}", r_settings.graphics.quality.name());
+    }
+    if r_keys.just_pressed(KeyCode::KeyV) {
+        r_settings.graphics.frame_limit = r_settings.graphics.frame_limit.next();
+        info!("Frame limit: {}", r_settings.graphics.frame_limit.name());
+    }
+    if r_keys.just_pressed(KeyCode::KeyB) {
+        r_settings.graphics.low_power = !r_settings.graphics.low_power;
+        info!("Low-power mode: {}", r_settings.graphics.low_power);
+    }
+}
+
+/// Adjust gameplay settings from the pause menu: L to cycle the skill level, A to toggle
+/// adaptive difficulty, [ / ] to change the challenge it targets.
+pub(crate) fn adjust_gameplay_settings(
+    r_keys: Res<ButtonInput<KeyCode>>,
+    mut r_settings: ResMut<Settings>,
+) {
+    if r_keys.just_pressed(KeyCode::KeyL) {
+        r_settings.gameplay.skill = r_settings.gameplay.skill.next();
+        info!("Skill level: {}", r_settings.gameplay.skill.name());
+    }
+    if r_keys.just_pressed(KeyCode::KeyA) {
+        r_settings.gameplay.adaptive = !r_settings.gameplay.adaptive;
+        info!("Adaptive difficulty: {}", r_settings.gameplay.adaptive);
+    }
+    if r_keys.just_pressed(KeyCode::BracketLeft) {
+        r_settings.gameplay.challenge = (r_settings.gameplay.challenge - 0.1).max(0.0);
+        info!("Challenge: {:.1}", r_settings.gameplay.challenge);
+    }
+    if r_keys.just_pressed(KeyCode::BracketRight) {
+        r_settings.gameplay.challenge = (r_settings.gameplay.challenge + 0.1).min(1.0);
+        info!("Challenge: {:.1}", r_settings.gameplay.challenge);
+    }
+}
+
+/// Adjust audio settings from the pause menu: O to toggle the announcer.
+pub(crate) fn adjust_audio_settings(
+    r_keys: Res<ButtonInput<KeyCode>>,
+    mut r_settings: ResMut<Settings>,
+) {
+    if r_keys.just_pressed(KeyCode::KeyO) {
+        r_settings.audio.announcer = !r_settings.audio.announcer;
+        info!("Announcer: {}", r_settings.audio.announcer);
+    }
+}
+
+/// Adjust assist settings from the pause menu: Z / X to change the game speed, E to cycle extra
+/// lives, Q to toggle auto-fire, W to toggle laser heat.
+pub(crate) fn adjust_assist_settings(
+    r_keys: Res<ButtonInput<KeyCode>>,
+    mut r_settings: ResMut<Settings>,
+) {
+    let assist = &mut r_settings.assist;
+    if r_keys.just_pressed(KeyCode::KeyZ) {
+        assist.game_speed = (assist.game_speed - 0.1).max(MIN_GAME_SPEED);
+        info!("Game speed: {:.0}%", assist.game_speed * 100.0);
+    }
+    if r_keys.just_pressed(KeyCode::KeyX) {
+        assist.game_speed = (assist.game_speed + 0.1).min(1.0);
+        info!("Game speed: {:.0}%", assist.game_speed * 100.0);
+    }
+    if r_keys.just_pressed(KeyCode::KeyE) {
+        assist.extra_lives = (assist.extra_lives + 1) % (MAX_EXTRA_LIVES + 1);
+        info!("Extra lives: {}", assist.extra_lives);
+    }
+    if r_keys.just_pressed(KeyCode::KeyQ) {
+        assist.auto_fire = !assist.auto_fire;
+        info!("Auto-fire: {}", assist.auto_fire);
+    }
+    if r_keys.just_pressed(KeyCode::KeyW) {
+        assist.laser_heat = !assist.laser_heat;
+        info!("Laser heat: {}", assist.laser_heat);
+    }
+}
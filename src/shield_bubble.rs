@@ -0,0 +1,108 @@
+//! Shield bubble: a translucent ellipsoid around the player ship, which fades as the shields
+//! weaken and ripples outwards from the point of impact when a hit is absorbed.
+use bevy::{
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderRef},
+};
+
+use crate::{
+    Playfield, UnitPosition,
+    damage::{PlayerHit, Shields},
+    ship::PlayerShip,
+};
+
+/// Marker for the shield bubble, a child of the player ship
+#[derive(Component, Default, Debug)]
+pub struct ShieldBubble;
+
+/// Opacity of the bubble at full shield strength
+const BUBBLE_OPACITY: f32 = 0.5;
+
+/// Rate at which the impact flash fades, per second
+const FLASH_DECAY: f32 = 4.0;
+
+/// Attach a shield bubble when the ship is spawned. The bubble is a sphere stretched to enclose
+/// the hull; positions and sizes are in model units.
+pub(crate) fn on_add_ship_shield_bubble(
+    trigger: Trigger<OnAdd, PlayerShip>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ShieldMaterial>>,
+) {
+    commands.entity(trigger.target()).with_child((
+        Mesh3d(meshes.add(Sphere::new(1.0).mesh().uv(24, 12))),
+        MeshMaterial3d(materials.add(ShieldMaterial {
+            color: LinearRgba::new(0.3, 0.7, 1.0, 1.0).to_vec4(),
+            params: Vec4::new(BUBBLE_OPACITY, 0.0, 0.0, 0.0),
+            // Start with the ripple long finished
+            impact: Vec4::new(1.0, 0.0, 0.0, 10.0),
+        })),
+        Transform::from_xyz(0.5, 0.0, 0.0).with_scale(Vec3::new(4.5, 2.5, 2.5)),
+        ShieldBubble,
+    ));
+}
+
+/// Start a flash and ripple facing the source of the hit.
+pub(crate) fn on_player_hit_ripple(
+    trigger: Trigger<PlayerHit>,
+    q_ship: Query<&UnitPosition, With<PlayerShip>>,
+    q_bubble: Query<&MeshMaterial3d<ShieldMaterial>, With<ShieldBubble>>,
+    mut materials: ResMut<Assets<ShieldMaterial>>,
+    r_playfield: Res<Playfield>,
+) {
+    let Ok(ship_pos) = q_ship.single() else {
+        return;
+    };
+    let direction = r_playfield
+        .delta(ship_pos.0, trigger.event().source)
+        .normalize_or(Vec2::X);
+    for material in q_bubble.iter() {
+        if let Some(material) = materials.get_mut(material.id()) {
+            material.impact = direction.extend(0.0).extend(0.0);
+            material.params.y = 1.0;
+        }
+    }
+}
+
+/// Fade the bubble with shield strength, and advance the impact animation.
+pub(crate) fn update_shield_bubble(
+    q_ship: Query<&Shields, With<PlayerShip>>,
+    q_bubble: Query<&MeshMaterial3d<ShieldMaterial>, With<ShieldBubble>>,
+    mut materials: ResMut<Assets<ShieldMaterial>>,
+    r_time: Res<Time>,
+) {
+    let Ok(shields) = q_ship.single() else {
+        return;
+    };
+    let dt = r_time.delta_secs();
+    for material in q_bubble.iter() {
+        if let Some(material) = materials.get_mut(material.id()) {
+            material.params.x = BUBBLE_OPACITY * shields.fraction();
+            material.params.y = (material.params.y - FLASH_DECAY * dt).max(0.0);
+            material.impact.w += dt;
+        }
+    }
+}
+
+#[derive(AsBindGroup, Asset, Reflect, Debug, Clone)]
+pub(crate) struct ShieldMaterial {
+    /// Tint of the bubble
+    #[uniform(0)]
+    pub(crate) color: Vec4,
+    /// x: opacity, y: impact flash
+    #[uniform(1)]
+    pub(crate) params: Vec4,
+    /// xyz: direction of the last impact, w: time since the impact
+    #[uniform(2)]
+    pub(crate) impact: Vec4,
+}
+
+impl Material for ShieldMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "embedded://guardian/assets/shaders/shield_bubble.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+}
@@ -1,6 +1,6 @@
 use std::f32::consts::PI;
 
-use avian2d::prelude::{Collider, CollisionLayers, RigidBody};
+use avian2d::prelude::{Collider, CollidingEntities, CollisionLayers, RigidBody};
 use bevy::{
     audio::{PlaybackMode, Volume},
     prelude::*,
@@ -8,21 +8,157 @@ use bevy::{
 use bevy_enhanced_input::prelude::*;
 
 use crate::{
-    ENEMY_LAYER, Fire, MainInput, Move, PLAYER_LAYER, PLAYFIELD_WIDTH, SHIP_DEPTH, UnitPosition,
-    Viewpoint,
-    laser::{ShotMesh, spawn_laser},
+    Brake, Fire, MainInput, Move, PICKUP_LAYER, PLAYER_LAYER, Playfield, Reverse, SHIP_DEPTH,
+    ShotFired, UnitPosition, Viewpoint,
+    afterburner::Afterburner,
+    barrel_roll::{BarrelRoll, start_barrel_roll},
+    bomb::{detonate_smart_bomb, drop_bomb},
+    continues::accept_continue,
+    damage::Shields,
+    game_state::{DespawnOnExit, GameState, PauseState, RunState},
+    hurtbox::hurtbox,
+    laser::{GunShot, Ricochet, ShotMesh, spawn_laser},
+    laser_heat::LaserHeat,
+    muzzle_flash::MuzzleFlash,
+    settings::Settings,
+    tuning::Tuning,
 };
 
-#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Reflect)]
 pub enum Facing {
     #[default]
     Right,
     Left,
 }
 
+impl Facing {
+    /// The opposite direction
+    pub fn reversed(&self) -> Self {
+        match self {
+            Facing::Right => Facing::Left,
+            Facing::Left => Facing::Right,
+        }
+    }
+}
+
+/// Selectable player ship types
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Reflect)]
+pub enum ShipVariant {
+    /// All-rounder
+    #[default]
+    Guardian,
+
+    /// Fast and nimble, but with a single gun and a small hull
+    Interceptor,
+
+    /// Slow to accelerate, with twin guns and a larger hull
+    Bulwark,
+}
+
+/// Handling, weapon and hitbox parameters for a ship variant.
+#[derive(Debug)]
+pub struct ShipStats {
+    /// Display name, shown in the ship selection menu
+    pub name: &'static str,
+
+    /// Scale of the model relative to the playfield
+    pub scale: f32,
+
+    /// Horizontal acceleration at full thrust
+    pub acceleration: f32,
+
+    /// Proportion of speed lost per second
+    pub drag: f32,
+
+    /// Maximum horizontal speed
+    pub max_speed: f32,
+
+    /// Vertical acceleration when climbing or diving
+    pub climb_acceleration: f32,
+
+    /// Maximum vertical speed
+    pub max_climb_speed: f32,
+
+    /// Laser mount points, relative to the ship center when facing right
+    pub gun_mounts: &'static [Vec2],
+
+    /// Hitbox capsule radius, in model units
+    pub hitbox_radius: f32,
+
+    /// Hitbox capsule endpoints, in model units
+    pub hitbox_endpoints: (Vec2, Vec2),
+
+    /// Pickups and falling treasures within this distance are drawn towards the ship
+    pub magnet_radius: f32,
+}
+
+const GUARDIAN_STATS: ShipStats = ShipStats {
+    name: "Guardian",
+    scale: 0.015,
+    acceleration: 10.0,
+    drag: 4.0,
+    max_speed: 1.5,
+    climb_acceleration: 4.0,
+    max_climb_speed: 0.3,
+    gun_mounts: &[Vec2::new(0.18, 0.0)],
+    hitbox_radius: 1.5,
+    hitbox_endpoints: (Vec2::new(-2., 0.), Vec2::new(3., 0.)),
+    magnet_radius: 0.15,
+};
+
+const INTERCEPTOR_STATS: ShipStats = ShipStats {
+    name: "Interceptor",
+    scale: 0.012,
+    acceleration: 14.0,
+    drag: 3.0,
+    max_speed: 2.0,
+    climb_acceleration: 5.0,
+    max_climb_speed: 0.36,
+    gun_mounts: &[Vec2::new(0.15, 0.0)],
+    hitbox_radius: 1.2,
+    hitbox_endpoints: (Vec2::new(-2., 0.), Vec2::new(2.5, 0.)),
+    magnet_radius: 0.1,
+};
+
+const BULWARK_STATS: ShipStats = ShipStats {
+    name: "Bulwark",
+    scale: 0.018,
+    acceleration: 7.0,
+    drag: 4.5,
+    max_speed: 1.2,
+    climb_acceleration: 3.0,
+    max_climb_speed: 0.26,
+    gun_mounts: &[Vec2::new(0.2, 0.012), Vec2::new(0.2, -0.012)],
+    hitbox_radius: 1.8,
+    hitbox_endpoints: (Vec2::new(-2., 0.), Vec2::new(3., 0.)),
+    magnet_radius: 0.2,
+};
+
+impl ShipVariant {
+    /// All variants, in menu order.
+    pub const ALL: [ShipVariant; 3] = [
+        ShipVariant::Guardian,
+        ShipVariant::Interceptor,
+        ShipVariant::Bulwark,
+    ];
+
+    /// Return the stats for this variant.
+    pub fn stats(&self) -> &'static ShipStats {
+        match self {
+            ShipVariant::Guardian => &GUARDIAN_STATS,
+            ShipVariant::Interceptor => &INTERCEPTOR_STATS,
+            ShipVariant::Bulwark => &BULWARK_STATS,
+        }
+    }
+}
+
 /// State of the player's ship
-#[derive(Component, Default, Debug)]
+#[derive(Component, Default, Debug, Reflect)]
+#[reflect(Component)]
 pub struct PlayerShip {
+    /// Which type of ship this is
+    pub variant: ShipVariant,
+
     /// Direction we want to be facing, sticky based on thrust
     facing: Facing,
 
@@ -32,6 +168,9 @@ pub struct PlayerShip {
     /// Horizontal velocity
     speed: f32,
 
+    /// Vertical velocity
+    climb: f32,
+
     /// Current ship orientation - follows facing but smoothed
     pitch: f32,
 
@@ -40,6 +179,26 @@ pub struct PlayerShip {
 
     /// The size of the thrust animation
     thrust: f32,
+
+    /// Nose-up kick from firing, in radians
+    recoil: f32,
+}
+
+impl PlayerShip {
+    /// Horizontal velocity of the ship.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Current size of the thrust animation, from 0 to 1.
+    pub fn thrust(&self) -> f32 {
+        self.thrust
+    }
+
+    /// Direction the ship is facing.
+    pub fn facing(&self) -> Facing {
+        self.facing
+    }
 }
 
 /// Entity for playing the laser shot sound.
@@ -54,7 +213,10 @@ pub(crate) fn spawn_ship(
     asset_server: Res<AssetServer>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    r_run: Res<RunState>,
+    r_settings: Res<Settings>,
 ) {
+    let stats = r_run.ship.stats();
     let mut thrust_cone = ConicalFrustum {
         radius_top: 0.2,
         radius_bottom: 0.6,
@@ -82,21 +244,37 @@ pub(crate) fn spawn_ship(
     commands
         .spawn((
             SceneRoot(asset_server.load(GltfAssetLabel::Scene(0).from_asset("models/ship.glb"))),
-            Transform::from_scale(Vec3::splat(0.015))
+            Transform::from_scale(Vec3::splat(stats.scale))
                 .with_translation(Vec3::new(0.0, 0.0, SHIP_DEPTH)),
             PlayerShip {
+                variant: r_run.ship,
                 facing: Facing::Right,
                 camera_offset: 0.,
                 speed: 0.,
+                climb: 0.,
                 pitch: 0.,
                 yaw: 0.,
                 thrust: 0.,
+                recoil: 0.,
             },
             RigidBody::Kinematic,
-            Collider::capsule_endpoints(1.5, Vec2::new(-2., 0.), Vec2::new(3., 0.)),
-            CollisionLayers::from_bits(PLAYER_LAYER, ENEMY_LAYER),
+            Collider::capsule_endpoints(
+                stats.hitbox_radius,
+                stats.hitbox_endpoints.0,
+                stats.hitbox_endpoints.1,
+            ),
+            // The hull only collects pickups; enemies must touch the smaller hurtbox.
+            CollisionLayers::from_bits(PLAYER_LAYER, PICKUP_LAYER),
+            CollidingEntities::default(),
+            (
+                Shields::default(),
+                Afterburner::default(),
+                BarrelRoll::default(),
+                LaserHeat::default(),
+            ),
             UnitPosition(Vec2::new(0., 0.)),
             Actions::<MainInput>::default(),
+            DespawnOnExit(GameState::Playing),
             AudioPlayer::new(asset_server.load("sounds/thrust.ogg")),
             PlaybackSettings {
                 mode: PlaybackMode::Loop,
@@ -127,16 +305,27 @@ pub(crate) fn spawn_ship(
                         .with_translation(Vec3::new(-3.6, 0.1, 0.8)),
                     Thrust
                 ),
+                hurtbox(stats, &r_settings),
             ],
         ))
-        .observe(fire_shots);
+        .observe(fire_shots)
+        .observe(reverse_ship)
+        .observe(start_barrel_roll)
+        .observe(drop_bomb)
+        .observe(detonate_smart_bomb)
+        .observe(accept_continue);
 }
 
+/// Size of the thrust cone while the afterburner is firing, relative to normal thrust
+const AFTERBURNER_THRUST: f32 = 1.6;
+
 pub(crate) fn move_ship(
     player: Single<
         (
             &Actions<MainInput>,
             &mut PlayerShip,
+            &Afterburner,
+            &BarrelRoll,
             &mut UnitPosition,
             &mut Transform,
             &mut AudioSink,
@@ -145,16 +334,44 @@ pub(crate) fn move_ship(
     >,
     mut q_thrust: Query<&mut Transform, With<Thrust>>,
     r_time: Res<Time>,
+    r_tuning: Res<Tuning>,
     mut r_viewpoint: ResMut<Viewpoint>,
+    r_playfield: Res<Playfield>,
 ) -> Result<()> {
-    let (actions, mut ship, mut position, mut transform, mut audio) = player.into_inner();
+    let (actions, mut ship, afterburner, roll, mut position, mut transform, mut audio) =
+        player.into_inner();
     let move_action = actions.get::<Move>()?.value().as_axis2d();
+    let braking = actions.get::<Brake>()?.value().as_bool();
+    let stats = ship.variant.stats();
+    let tuning = &r_tuning.ship;
 
-    // Move the ship
-    let accel = (-ship.speed * 4.0 + move_action.x * 10.) * r_time.delta_secs();
-    ship.speed = (ship.speed + accel).clamp(-1.5, 1.5);
-    position.0.x = (position.0.x + ship.speed * r_time.delta_secs()).rem_euclid(PLAYFIELD_WIDTH);
-    position.0.y = (transform.translation.y + move_action.y * 0.005).clamp(-0.4, 0.45);
+    // Move the ship. The afterburner raises both acceleration and top speed; the air brake cuts
+    // the engine and greatly increases drag, which can bring the ship to a stop but never reverse
+    // it, however long the frame.
+    let max_speed = stats.max_speed * tuning.max_speed_scale * afterburner.speed_factor();
+    let accel = if braking {
+        -ship.speed * (stats.drag * tuning.brake_drag * r_time.delta_secs()).min(1.0)
+    } else {
+        (-ship.speed * stats.drag
+            + move_action.x
+                * stats.acceleration
+                * tuning.acceleration_scale
+                * afterburner.acceleration_factor())
+            * r_time.delta_secs()
+    };
+    ship.speed = (ship.speed + accel).clamp(-max_speed, max_speed);
+    position.0.x = r_playfield.wrap(position.0.x + ship.speed * r_time.delta_secs());
+
+    // Vertical speed eases towards the stick position, and stops dead at the edges of the
+    // playfield.
+    let target_climb = move_action.y * stats.max_climb_speed;
+    let max_change = stats.climb_acceleration * r_time.delta_secs();
+    ship.climb += (target_climb - ship.climb).clamp(-max_change, max_change);
+    let y = position.0.y + ship.climb * r_time.delta_secs();
+    position.0.y = y.clamp(r_playfield.floor, r_playfield.ceiling);
+    if position.0.y != y {
+        ship.climb = 0.0;
+    }
 
     // Facing is sticky: ship orientation matches most recent thrust action.
     let mut target_thrust = 0.;
@@ -165,6 +382,11 @@ pub(crate) fn move_ship(
         ship.facing = Facing::Left;
         target_thrust = 1.0;
     }
+    if braking {
+        target_thrust = 0.;
+    } else if afterburner.active() {
+        target_thrust *= AFTERBURNER_THRUST;
+    }
 
     // Adjust pitch if we flipped direction
     let target_pitch = match ship.facing {
@@ -211,36 +433,198 @@ pub(crate) fn move_ship(
         r_time.delta_secs() * 0.3,
     );
     ship.thrust = transition_to_target(ship.thrust, target_thrust, r_time.delta_secs() * 15.);
+    ship.recoil = transition_to_target(
+        ship.recoil,
+        0.0,
+        r_time.delta_secs() * tuning.recoil_recovery,
+    );
     // transform.translation.x = ship.camera_offset;
-    transform.rotation = Quat::from_euler(EulerRot::YXZ, ship.pitch, ship.yaw, 0.0);
-    r_viewpoint.position = (position.0.x - ship.camera_offset).rem_euclid(PLAYFIELD_WIDTH);
+    // A barrel roll spins the ship on top of the smoothed orientation.
+    transform.rotation = Quat::from_euler(
+        EulerRot::YXZ,
+        ship.pitch,
+        ship.yaw + roll.angle(),
+        ship.recoil,
+    );
+    r_viewpoint.position = r_playfield.wrap(position.0.x - ship.camera_offset);
 
     // Adjust shock cone scale
     for mut trust_transform in q_thrust.iter_mut() {
         trust_transform.scale = Vec3::new(1.0, ship.thrust * thrust_noise, 1.0);
     }
 
-    // Adjust thrust sound
+    // Adjust thrust sound. The engine note rises with the afterburner.
     audio.set_volume(Volume::Linear(ship.thrust * 0.8));
+    audio.set_speed(0.2 * ship.thrust.max(1.0));
 
     Ok(())
 }
 
+/// Turn the ship around on the spot, for example to fire back at a pursuer while coasting or
+/// braking. The ship keeps its momentum, and the pitch smoothing in [`move_ship`] animates the
+/// flip. Thrusting in either direction sets the facing again as usual.
+pub(crate) fn reverse_ship(
+    _trigger: Trigger<Started<Reverse>>,
+    r_pause: Option<Res<State<PauseState>>>,
+    mut q_player: Query<&mut PlayerShip>,
+) {
+    if r_pause.is_none_or(|state| *state.get() != PauseState::Running) {
+        return;
+    }
+    for mut ship in q_player.iter_mut() {
+        ship.facing = ship.facing.reversed();
+    }
+}
+
+/// Time between shots while auto-fire is holding the trigger, in seconds
+const AUTO_FIRE_INTERVAL: f32 = 0.15;
+
+/// Vertical speed of ricochet shots, relative to the ship's climb rate
+const RICOCHET_CLIMB_SCALE: f32 = 3.0;
+
 pub(crate) fn fire_shots(
     _trigger: Trigger<Started<Fire>>,
     mut commands: Commands,
-    player: Query<(&mut PlayerShip, &mut UnitPosition)>,
+    r_pause: Option<Res<State<PauseState>>>,
+    mut player: Query<(&mut PlayerShip, &UnitPosition, &mut LaserHeat)>,
+    q_audio: Query<Entity, With<ShotSound>>,
+    asset_server: Res<AssetServer>,
+    shot_mesh: Res<ShotMesh>,
+    r_run: Res<RunState>,
+    r_settings: Res<Settings>,
+    r_tuning: Res<Tuning>,
+) {
+    if r_pause.is_none_or(|state| *state.get() != PauseState::Running) {
+        return;
+    }
+    let Ok((mut ship, position, mut laser_heat)) = player.single_mut() else {
+        return;
+    };
+    if !laser_heat.try_fire(&r_settings) {
+        return;
+    }
+    fire_guns(
+        &mut commands,
+        &mut ship,
+        position,
+        &q_audio,
+        &asset_server,
+        &shot_mesh,
+        &r_run,
+        &r_tuning,
+    );
+}
+
+/// Assist option: while Fire is held, keep firing at a steady rate. The first shot comes from
+/// [`fire_shots`] when the button is pressed.
+pub(crate) fn auto_fire(
+    mut commands: Commands,
+    mut player: Query<(
+        &Actions<MainInput>,
+        &mut PlayerShip,
+        &UnitPosition,
+        &mut LaserHeat,
+    )>,
     q_audio: Query<Entity, With<ShotSound>>,
     asset_server: Res<AssetServer>,
     shot_mesh: Res<ShotMesh>,
+    r_run: Res<RunState>,
+    r_settings: Res<Settings>,
+    r_tuning: Res<Tuning>,
+    r_time: Res<Time>,
+    mut cooldown: Local<f32>,
 ) {
-    let Ok((ship, position)) = player.single() else {
+    let Ok((actions, mut ship, position, mut laser_heat)) = player.single_mut() else {
         return;
     };
-    spawn_laser(&mut commands, position.0, ship.facing, shot_mesh);
+    let held = actions
+        .get::<Fire>()
+        .is_ok_and(|action| action.value().as_bool());
+    if !r_settings.assist.auto_fire || !held {
+        *cooldown = AUTO_FIRE_INTERVAL;
+        return;
+    }
+    *cooldown -= r_time.delta_secs();
+    if *cooldown > 0.0 {
+        return;
+    }
+    *cooldown += AUTO_FIRE_INTERVAL;
+    if !laser_heat.try_fire(&r_settings) {
+        return;
+    }
+    fire_guns(
+        &mut commands,
+        &mut ship,
+        position,
+        &q_audio,
+        &asset_server,
+        &shot_mesh,
+        &r_run,
+        &r_tuning,
+    );
+}
+
+/// Fire a volley from each of the ship's guns, with a shot sound, muzzle flashes and a kick of
+/// recoil.
+fn fire_guns(
+    commands: &mut Commands,
+    ship: &mut PlayerShip,
+    position: &UnitPosition,
+    q_audio: &Query<Entity, With<ShotSound>>,
+    asset_server: &AssetServer,
+    shot_mesh: &ShotMesh,
+    r_run: &RunState,
+    tuning: &Tuning,
+) {
+    for mount in ship.variant.stats().gun_mounts {
+        let shot = spawn_laser(
+            commands,
+            position.0,
+            *mount,
+            ship.facing,
+            r_run.pierce,
+            1.0,
+            shot_mesh,
+            tuning,
+        );
+        commands.entity(shot).insert(GunShot);
+        // With the ricochet pickup, shots fired while climbing or diving are angled.
+        if r_run.ricochet && ship.climb != 0.0 {
+            commands
+                .entity(shot)
+                .insert(Ricochet::new(ship.climb * RICOCHET_CLIMB_SCALE));
+        }
+        commands.spawn((
+            MuzzleFlash::new(*mount, ship.facing),
+            UnitPosition(position.0),
+        ));
+        commands.trigger(ShotFired);
+    }
+    ship.recoil = tuning.ship.recoil_kick;
+
+    // The rear gun fires a single shot backwards from the first mount.
+    let rear_mount = ship.variant.stats().gun_mounts.first();
+    if let Some(mount) = rear_mount.filter(|_| r_run.rear_gun) {
+        let shot = spawn_laser(
+            commands,
+            position.0,
+            *mount,
+            ship.facing.reversed(),
+            r_run.pierce,
+            1.0,
+            shot_mesh,
+            tuning,
+        );
+        commands.entity(shot).insert(GunShot);
+        commands.spawn((
+            MuzzleFlash::new(*mount, ship.facing.reversed()),
+            UnitPosition(position.0),
+        ));
+        commands.trigger(ShotFired);
+    }
 
     // Despawn any playing shot sounds
-    for shot_sound in q_audio {
+    for shot_sound in q_audio.iter() {
         commands.entity(shot_sound).despawn();
     }
 
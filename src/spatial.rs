@@ -0,0 +1,163 @@
+//! Spatial hashing for proximity queries in the wraparound world.
+//!
+//! The shared hash serves queries which compare many units against many others: saucers looking
+//! for treasure and keeping apart from each other, drones picking a target, and finding the
+//! enemies caught in a bomb blast. Swarmlet homing and distance-attenuated sounds only ever
+//! measure against a single point (the player ship or the viewpoint), so they look positions up
+//! directly.
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{Enemy, Playfield, UnitPosition, treasure::Treasure};
+
+/// A uniform grid of buckets over the playfield. Columns wrap around horizontally, so a query
+/// near one edge of the world finds entities near the other edge.
+///
+/// As a resource, this holds every enemy and treasure, and is rebuilt at the start of each
+/// frame by [`update_spatial_hash`]. Stored positions may be slightly stale by the time they are
+/// queried, so callers which need exact positions should look them up.
+#[derive(Resource, Debug)]
+pub struct SpatialHash {
+    /// Size of each grid cell
+    cell_size: f32,
+
+    /// Number of columns around the world
+    columns: i32,
+
+    /// Width of the world
+    width: f32,
+
+    /// Entities in each cell, keyed by (column, row)
+    cells: HashMap<(i32, i32), Vec<(Entity, Vec2)>>,
+
+    /// Range of occupied rows
+    rows: (i32, i32),
+}
+
+impl Default for SpatialHash {
+    fn default() -> Self {
+        Self::new(&Playfield::default(), SPATIAL_CELL_SIZE)
+    }
+}
+
+/// Cell size of the shared spatial hash resource
+const SPATIAL_CELL_SIZE: f32 = 0.25;
+
+impl SpatialHash {
+    /// Create an empty spatial hash covering the playfield. Cells are at least `cell_size` wide,
+    /// and are stretched slightly so that a whole number of them fit around the world.
+    pub fn new(playfield: &Playfield, cell_size: f32) -> Self {
+        let columns = (playfield.width / cell_size).floor().max(1.0) as i32;
+        Self {
+            cell_size: playfield.width / columns as f32,
+            columns,
+            width: playfield.width,
+            cells: HashMap::default(),
+            rows: (0, -1),
+        }
+    }
+
+    /// Remove all entities, and resize the grid to fit the playfield.
+    pub fn clear(&mut self, playfield: &Playfield) {
+        if playfield.width != self.width {
+            *self = Self::new(playfield, self.cell_size);
+        } else {
+            self.cells.values_mut().for_each(Vec::clear);
+            self.rows = (0, -1);
+        }
+    }
+
+    fn cell(&self, position: Vec2) -> (i32, i32) {
+        (
+            (position.x.rem_euclid(self.width) / self.cell_size) as i32 % self.columns,
+            (position.y / self.cell_size).floor() as i32,
+        )
+    }
+
+    /// Add an entity at the given position.
+    pub fn insert(&mut self, entity: Entity, position: Vec2) {
+        let cell = self.cell(position);
+        self.rows = if self.rows.0 > self.rows.1 {
+            (cell.1, cell.1)
+        } else {
+            (self.rows.0.min(cell.1), self.rows.1.max(cell.1))
+        };
+        self.cells.entry(cell).or_default().push((entity, position));
+    }
+
+    /// Iterate over all entities within `radius` of `position`, along with their positions.
+    /// Distances are measured the short way around the world.
+    pub fn within(&self, position: Vec2, radius: f32) -> impl Iterator<Item = (Entity, Vec2)> {
+        let (column, row) = self.cell(position);
+        let reach = (radius / self.cell_size).ceil() as i32;
+        // Don't visit the same column twice when the query is wider than the world.
+        let span = (reach * 2 + 1).min(self.columns);
+        let playfield = Playfield {
+            width: self.width,
+            ..default()
+        };
+        (0..span)
+            .flat_map(move |dc| {
+                let c = (column - reach + dc).rem_euclid(self.columns);
+                (row - reach..=row + reach).filter_map(move |r| self.cells.get(&(c, r)))
+            })
+            .flatten()
+            .copied()
+            .filter(move |(_, other)| playfield.distance(position, *other) <= radius)
+    }
+
+    /// Find the nearest entity to `position` which passes `filter`, along with its distance.
+    /// Columns are searched outwards from the query position, stopping once no closer entity
+    /// can be found.
+    pub fn nearest(
+        &self,
+        position: Vec2,
+        mut filter: impl FnMut(Entity) -> bool,
+    ) -> Option<(Entity, f32)> {
+        let (column, _) = self.cell(position);
+        let playfield = Playfield {
+            width: self.width,
+            ..default()
+        };
+        let mut best: Option<(Entity, f32)> = None;
+        for ring in 0..=self.columns / 2 {
+            // Everything in this ring is at least this far away horizontally.
+            let min_distance = (ring - 1).max(0) as f32 * self.cell_size;
+            if best.is_some_and(|(_, distance)| distance <= min_distance) {
+                break;
+            }
+            let left = (column - ring).rem_euclid(self.columns);
+            let right = (column + ring).rem_euclid(self.columns);
+            let columns = if left == right {
+                [Some(left), None]
+            } else {
+                [Some(left), Some(right)]
+            };
+            for c in columns.into_iter().flatten() {
+                for r in self.rows.0..=self.rows.1 {
+                    let Some(cell) = self.cells.get(&(c, r)) else {
+                        continue;
+                    };
+                    for (entity, other) in cell.iter() {
+                        let distance = playfield.distance(position, *other);
+                        if best.is_none_or(|(_, d)| distance < d) && filter(*entity) {
+                            best = Some((*entity, distance));
+                        }
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Rebuild the spatial hash from the current positions of enemies and treasures.
+pub(crate) fn update_spatial_hash(
+    q_units: Query<(Entity, &UnitPosition), Or<(With<Enemy>, With<Treasure>)>>,
+    r_playfield: Res<Playfield>,
+    mut r_hash: ResMut<SpatialHash>,
+) {
+    r_hash.clear(&r_playfield);
+    for (entity, position) in q_units.iter() {
+        r_hash.insert(entity, position.0);
+    }
+}
@@ -0,0 +1,108 @@
+//! Optional run timer, with per-wave splits compared against the player's best times.
+use bevy::prelude::*;
+
+use crate::{
+    game_state::{DespawnOnExit, GameState, Level, RunState},
+    high_scores::HighScores,
+    settings::Settings,
+    wave::WaveComplete,
+};
+
+/// Time spent in the current run. The timer runs on the game clock, so it stops while paused.
+#[derive(Resource, Default, Debug)]
+pub struct RunTimer {
+    /// Time since the start of the run, in seconds
+    elapsed: f32,
+
+    /// The most recent split: wave number, time at the end of the wave, and the difference from
+    /// the personal best, if there was one.
+    last_split: Option<(u32, f32, Option<f32>)>,
+}
+
+/// Marker for the on-screen timer
+#[derive(Component, Default, Debug)]
+pub struct RunTimerDisplay;
+
+const TIMER_COLOR: Color = Color::srgb(0.7, 0.7, 0.8);
+
+pub(crate) fn start_run_timer(mut r_timer: ResMut<RunTimer>) {
+    *r_timer = RunTimer::default();
+}
+
+pub(crate) fn tick_run_timer(mut r_timer: ResMut<RunTimer>, r_time: Res<Time>) {
+    r_timer.elapsed += r_time.delta_secs();
+}
+
+/// Record the split at the end of each wave, and update the personal best. Practice runs
+/// don't start from the first wave, so their times aren't comparable and aren't recorded.
+pub(crate) fn record_split(
+    _trigger: Trigger<WaveComplete>,
+    mut r_timer: ResMut<RunTimer>,
+    r_level: Res<Level>,
+    r_run: Res<RunState>,
+    mut r_scores: ResMut<HighScores>,
+) {
+    let wave = r_level.number;
+    let time = r_timer.elapsed;
+    if r_run.practice.is_some() {
+        r_timer.last_split = Some((wave, time, None));
+        return;
+    }
+
+    let index = wave as usize;
+    if r_scores.best_splits.len() <= index {
+        r_scores.best_splits.resize(index + 1, None);
+    }
+    let best = r_scores.best_splits[index];
+    r_timer.last_split = Some((wave, time, best.map(|best| time - best)));
+    if best.is_none_or(|best| time < best) {
+        r_scores.best_splits[index] = Some(time);
+    }
+}
+
+pub(crate) fn spawn_run_timer(mut commands: Commands, r_settings: Res<Settings>) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(12.0),
+            bottom: Val::Px(8.0),
+            ..default()
+        },
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(TIMER_COLOR),
+        TextLayout::new_with_justify(JustifyText::Right),
+        if r_settings.hud.show_timer {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        },
+        RunTimerDisplay,
+        DespawnOnExit(GameState::Playing),
+    ));
+}
+
+pub(crate) fn update_run_timer_display(
+    mut q_display: Query<&mut Text, With<RunTimerDisplay>>,
+    r_timer: Res<RunTimer>,
+) {
+    let mut text = format_time(r_timer.elapsed);
+    if let Some((wave, time, delta)) = r_timer.last_split {
+        text += &format!("\nWave {}: {}", wave + 1, format_time(time));
+        if let Some(delta) = delta {
+            text += &format!(" ({:+.1})", delta);
+        }
+    }
+    for mut display in q_display.iter_mut() {
+        display.0.clone_from(&text);
+    }
+}
+
+/// Format a time in seconds as minutes, seconds and tenths.
+fn format_time(seconds: f32) -> String {
+    let minutes = (seconds / 60.0).floor();
+    format!("{}:{:04.1}", minutes as u32, seconds - minutes * 60.0)
+}
@@ -1,71 +1,212 @@
-use bevy::prelude::*;
+use bevy::{
+    asset::RenderAssetUsages,
+    pbr::{MaterialPipeline, MaterialPipelineKey},
+    prelude::*,
+    render::{
+        mesh::{Indices, MeshVertexAttribute, MeshVertexBufferLayoutRef, PrimitiveTopology},
+        render_resource::{
+            AsBindGroup, RenderPipelineDescriptor, ShaderRef, SpecializedMeshPipelineError,
+            VertexFormat,
+        },
+        view::NoFrustumCulling,
+    },
+};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 
-use crate::{PLAYFIELD_WIDTH, STARS_DEPTH, Viewpoint};
+use crate::{
+    Playfield, STARS_DEPTH, Viewpoint,
+    game_state::Level,
+    low_power::BackgroundClock,
+    settings::Settings,
+    ship::PlayerShip,
+    theme::{CurrentTheme, LevelTheme},
+};
 
+/// Marker for the starfield entity. All of the stars are drawn as a single mesh, with the
+/// parallax scrolling done in the vertex shader.
 #[derive(Component, Default, Debug)]
-pub struct Star {
-    /// Offset of the star relative to the playfield origin. To implement wrapping, the
-    /// star will be displayed at a modulus of it's position.
-    offset: Vec2,
+pub struct Starfield;
 
-    /// Speed at which the star parallax moves.
-    speed: f32,
-}
+/// Per-star vertex attribute: offset x, offset y, parallax speed, size.
+const ATTRIBUTE_STAR: MeshVertexAttribute =
+    MeshVertexAttribute::new("Star", 988540917, VertexFormat::Float32x4);
+
+/// Number of stars per unit of playfield width.
+const STAR_DENSITY: f32 = 25.0;
+
+/// Fraction of max speed at which stars start to streak.
+const STREAK_THRESHOLD: f32 = 0.7;
 
-const NUM_STARS: usize = 200;
+/// Rate at which the streak effect follows the ship speed.
+const STREAK_RATE: f32 = 4.0;
 
-/// Spawn the star sprites. Note that because we're using an ortho, rather than a 2d camera,
+/// Size multiplier for giant stars.
+const GIANT_SIZE: f32 = 2.5;
+
+/// Spawn the starfield. Note that because we're using an ortho, rather than a 2d camera,
 /// we can't actually use Bevy `Sprite` but instead are using planar meshes.
+///
+/// Stars are placed using the current level seed. Any existing starfield is despawned first, so
+/// this can be re-run when a new level starts, or the playfield, level theme or graphics quality
+/// changes.
 pub(crate) fn spawn_stars(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut materials: ResMut<Assets<StarMaterial>>,
     asset_server: Res<AssetServer>,
+    q_starfield: Query<Entity, With<Starfield>>,
+    r_playfield: Res<Playfield>,
+    r_viewpoint: Res<Viewpoint>,
+    r_theme: Res<CurrentTheme>,
+    themes: Res<Assets<LevelTheme>>,
+    r_settings: Res<Settings>,
+    r_level: Res<Level>,
 ) {
-    let star = asset_server.load("textures/star.png");
-    let mut rng = ChaCha8Rng::seed_from_u64(19878367467712);
-    let mesh = meshes.add(Rectangle::from_size(Vec2::splat(1.0)));
+    let theme = r_theme.get(&themes).stars;
+    for starfield in q_starfield.iter() {
+        commands.entity(starfield).despawn();
+    }
 
-    // Star
-    for _ in 0..NUM_STARS {
+    let _span = info_span!("generate_starfield").entered();
+    let mut rng = ChaCha8Rng::seed_from_u64(r_level.seed());
+    let num_stars = (r_playfield.width * STAR_DENSITY * r_settings.graphics.quality.star_density())
+        .round() as usize;
+
+    let mut v_pos: Vec<[f32; 3]> = Vec::with_capacity(num_stars * 4);
+    let mut v_uv: Vec<[f32; 2]> = Vec::with_capacity(num_stars * 4);
+    let mut v_color: Vec<[f32; 4]> = Vec::with_capacity(num_stars * 4);
+    let mut v_star: Vec<[f32; 4]> = Vec::with_capacity(num_stars * 4);
+    let mut indices: Vec<u32> = Vec::with_capacity(num_stars * 6);
+
+    // Each star is a quad; the shader positions the corners relative to the star center.
+    for i in 0..num_stars {
         let dist = rng.random_range(0.4..0.9);
-        let size = 0.006 * (1.0 - dist * 0.5);
-        commands.spawn((
-            Mesh3d(mesh.clone()),
-            MeshMaterial3d(materials.add(StandardMaterial {
-                base_color: Color::srgba(1.0, 1.0, 1.0, 1.0 - dist),
-                base_color_texture: Some(star.clone()),
-                alpha_mode: AlphaMode::Blend,
-                unlit: true,
-                ..Default::default()
-            })),
-            Star {
-                offset: Vec2 {
-                    x: rng.random_range(0.0..PLAYFIELD_WIDTH),
-                    y: rng.random_range(-0.35..0.49),
-                },
-                speed: 1.0 - dist * 0.7,
-            },
-            Transform::from_xyz(0.0, 0.0, STARS_DEPTH).with_scale(Vec3::splat(size)),
-        ));
+        let giant = (!theme.giant_colors.is_empty()
+            && rng.random_bool(theme.giant_chance.clamp(0.0, 1.0) as f64))
+        .then(|| theme.giant_colors[rng.random_range(0..theme.giant_colors.len())]);
+        let size = 0.006 * (1.0 - dist * 0.5) * if giant.is_some() { GIANT_SIZE } else { 1.0 };
+        let star = [
+            rng.random_range(0.0..r_playfield.width),
+            rng.random_range(-0.35..0.49),
+            1.0 - dist * 0.7,
+            size,
+        ];
+        // Near stars are warmer and brighter, far stars cooler and dimmer. Giants stand out at
+        // any depth.
+        let color = match giant {
+            Some(color) => color.with_alpha(1.0 - dist * 0.5),
+            None => theme
+                .near_color
+                .mix(&theme.far_color, (dist - 0.4) / 0.5)
+                .with_alpha(1.0 - dist),
+        }
+        .to_linear()
+        .to_f32_array();
+        for (x, y) in [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)] {
+            v_pos.push([x, y, 0.0]);
+            v_uv.push([x + 0.5, 0.5 - y]);
+            v_color.push(color);
+            v_star.push(star);
+        }
+        let base = i as u32 * 4;
+        indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
     }
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, v_pos);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, v_uv);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, v_color);
+    mesh.insert_attribute(ATTRIBUTE_STAR, v_star);
+    mesh.insert_indices(Indices::U32(indices));
+
+    commands.spawn((
+        Mesh3d(meshes.add(mesh)),
+        MeshMaterial3d(materials.add(StarMaterial {
+            params: Vec4::new(r_viewpoint.position, r_playfield.width, 0.0, 0.0),
+            texture: asset_server.load("textures/star.png"),
+        })),
+        Transform::from_xyz(0.0, 0.0, STARS_DEPTH),
+        // Star positions are computed in the shader, so the mesh bounds are meaningless.
+        NoFrustumCulling,
+        Starfield,
+    ));
 }
 
-/// Update the positions of the individual stars in the background.
+/// Pass the current viewpoint, and the speed streak amount, to the starfield shader. Stars
+/// streak when the ship is near its maximum speed.
 pub(crate) fn update_stars(
     r_viewpoint: Res<Viewpoint>,
-    mut q_stars: Query<(&Star, &mut Transform)>,
+    r_playfield: Res<Playfield>,
+    r_clock: Res<BackgroundClock>,
+    q_ship: Query<&PlayerShip>,
+    q_starfield: Query<&MeshMaterial3d<StarMaterial>, With<Starfield>>,
+    mut materials: ResMut<Assets<StarMaterial>>,
 ) {
-    for (star, mut transform) in q_stars.iter_mut() {
-        // Parallax scrolling: offset each star by it's speed relative to the camera offset,
-        // and then use modulo to implement wrap-around.
-        let dist_traveled = PLAYFIELD_WIDTH * star.speed;
-        transform.translation.x = (star.offset.x + dist_traveled * 0.5
-            - r_viewpoint.position * star.speed)
-            .rem_euclid(dist_traveled)
-            - dist_traveled * 0.5;
-        transform.translation.y = star.offset.y;
+    let target_streak = q_ship.single().map_or(0.0, |ship| {
+        let fraction = ship.speed() / ship.variant.stats().max_speed;
+        fraction.signum()
+            * ((fraction.abs() - STREAK_THRESHOLD) / (1.0 - STREAK_THRESHOLD)).max(0.0)
+    });
+
+    for material in q_starfield.iter() {
+        let Some(current) = materials.get(material.id()) else {
+            continue;
+        };
+        let streak = current
+            .params
+            .z
+            .lerp(target_streak, (r_clock.delta_secs() * STREAK_RATE).min(1.0));
+        if !r_viewpoint.is_changed() && (streak - current.params.z).abs() < 0.001 {
+            continue;
+        }
+        if let Some(material) = materials.get_mut(material.id()) {
+            material.params.x = r_viewpoint.position;
+            material.params.y = r_playfield.width;
+            material.params.z = streak;
+        }
+    }
+}
+
+#[derive(AsBindGroup, Asset, Reflect, Debug, Clone)]
+pub(crate) struct StarMaterial {
+    /// x: viewpoint position, y: playfield width, z: speed streak amount
+    #[uniform(0)]
+    pub(crate) params: Vec4,
+    #[texture(1)]
+    #[sampler(2)]
+    pub(crate) texture: Handle<Image>,
+}
+
+impl Material for StarMaterial {
+    fn vertex_shader() -> ShaderRef {
+        "embedded://guardian/assets/shaders/stars.wgsl".into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        "embedded://guardian/assets/shaders/stars.wgsl".into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let vertex_layout = layout.0.get_layout(&[
+            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
+            Mesh::ATTRIBUTE_UV_0.at_shader_location(1),
+            Mesh::ATTRIBUTE_COLOR.at_shader_location(2),
+            ATTRIBUTE_STAR.at_shader_location(3),
+        ])?;
+        descriptor.vertex.buffers = vec![vertex_layout];
+        Ok(())
     }
 }
@@ -0,0 +1,239 @@
+//! Statistics for the current run, and the end-of-wave summary screens.
+use std::collections::BTreeMap;
+
+use bevy::{ecs::spawn::SpawnIter, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    EnemyDestroyed, EnemyKind, ShotFired, ShotLanded, TreasureLost, TreasureRescued,
+    game_state::{DespawnOnExit, GameState, PauseState},
+    input_glyphs::{ButtonPrompt, InputDevice, PromptButton},
+    profile::Profile,
+    score::ScoreEvent,
+    ship::PlayerShip,
+};
+
+/// Counters tracked over the course of a run. The same structure is used for lifetime totals.
+#[derive(Resource, Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct RunStats {
+    pub shots_fired: u32,
+    pub shots_landed: u32,
+    pub kills: BTreeMap<EnemyKind, u32>,
+    pub treasures_rescued: u32,
+    pub treasures_lost: u32,
+    pub structures_saved: u32,
+    pub structures_lost: u32,
+    pub distance: f32,
+    pub score: u32,
+
+    /// Number of times the run has looped back to the first wave
+    pub loops: u32,
+}
+
+impl RunStats {
+    /// Proportion of shots which hit something.
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.0
+        } else {
+            self.shots_landed as f32 / self.shots_fired as f32
+        }
+    }
+
+    /// Total number of enemies destroyed, of all kinds.
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+}
+
+/// Marker for the end-of-wave summary screen
+#[derive(Component, Default, Debug)]
+pub struct StatsScreen;
+
+/// Statistics are updated for both the current run and the lifetime totals.
+pub(crate) fn on_shot_fired(
+    _trigger: Trigger<ShotFired>,
+    mut r_stats: ResMut<RunStats>,
+    mut r_profile: ResMut<Profile>,
+) {
+    r_stats.shots_fired += 1;
+    r_profile.lifetime.shots_fired += 1;
+}
+
+pub(crate) fn on_shot_landed(
+    _trigger: Trigger<ShotLanded>,
+    mut r_stats: ResMut<RunStats>,
+    mut r_profile: ResMut<Profile>,
+) {
+    r_stats.shots_landed += 1;
+    r_profile.lifetime.shots_landed += 1;
+}
+
+pub(crate) fn on_enemy_destroyed(
+    trigger: Trigger<EnemyDestroyed>,
+    mut commands: Commands,
+    mut r_stats: ResMut<RunStats>,
+    mut r_profile: ResMut<Profile>,
+) {
+    let kind = trigger.event().kind;
+    commands.trigger(ScoreEvent {
+        points: kind.points(),
+    });
+    *r_stats.kills.entry(kind).or_default() += 1;
+    *r_profile.lifetime.kills.entry(kind).or_default() += 1;
+}
+
+pub(crate) fn on_treasure_rescued(
+    trigger: Trigger<TreasureRescued>,
+    mut commands: Commands,
+    mut r_stats: ResMut<RunStats>,
+    mut r_profile: ResMut<Profile>,
+) {
+    r_stats.treasures_rescued += 1;
+    commands.trigger(ScoreEvent {
+        points: trigger.event().bonus as i32,
+    });
+    r_profile.lifetime.treasures_rescued += 1;
+}
+
+pub(crate) fn on_treasure_lost(
+    _trigger: Trigger<TreasureLost>,
+    mut r_stats: ResMut<RunStats>,
+    mut r_profile: ResMut<Profile>,
+) {
+    r_stats.treasures_lost += 1;
+    r_profile.lifetime.treasures_lost += 1;
+}
+
+pub(crate) fn track_distance(
+    q_ship: Query<&PlayerShip>,
+    r_time: Res<Time>,
+    mut r_stats: ResMut<RunStats>,
+    mut r_profile: ResMut<Profile>,
+) {
+    for ship in q_ship.iter() {
+        let distance = ship.speed().abs() * r_time.delta_secs();
+        r_stats.distance += distance;
+        r_profile.lifetime.distance += distance;
+    }
+}
+
+/// Start a new run with fresh statistics.
+pub(crate) fn start_run_stats(mut r_stats: ResMut<RunStats>, mut r_profile: ResMut<Profile>) {
+    *r_stats = RunStats::default();
+    r_profile.runs += 1;
+}
+
+pub(crate) fn on_game_over(commands: Commands, r_stats: Res<RunStats>, r_profile: Res<Profile>) {
+    spawn_stats_screen(
+        commands,
+        "GAME OVER",
+        None,
+        DespawnOnExit(PauseState::GameOver),
+        &r_stats,
+        &r_profile,
+    );
+}
+
+pub(crate) fn on_level_complete(
+    commands: Commands,
+    r_stats: Res<RunStats>,
+    r_profile: Res<Profile>,
+) {
+    spawn_stats_screen(
+        commands,
+        "LEVEL COMPLETE",
+        Some(continue_prompt),
+        DespawnOnExit(GameState::LevelComplete),
+        &r_stats,
+        &r_profile,
+    );
+}
+
+fn continue_prompt(device: InputDevice) -> String {
+    format!("Press {} to continue", device.glyph(PromptButton::Confirm))
+}
+
+/// Spawn the summary screen, with an optional `prompt` at the bottom. `scope` determines when it
+/// is despawned.
+fn spawn_stats_screen(
+    mut commands: Commands,
+    title: &str,
+    prompt: Option<fn(InputDevice) -> String>,
+    scope: impl Bundle,
+    stats: &RunStats,
+    profile: &Profile,
+) {
+    let mut lines = vec![
+        format!("Score: {}", stats.score),
+        format!("Shots fired: {}", stats.shots_fired),
+        format!("Accuracy: {:.0}%", stats.accuracy() * 100.0),
+    ];
+    for (kind, count) in stats.kills.iter() {
+        lines.push(format!("{} kills: {}", kind.name(), count));
+    }
+    lines.push(format!("Treasures saved: {}", stats.treasures_rescued));
+    lines.push(format!("Treasures lost: {}", stats.treasures_lost));
+    lines.push(format!("Structures saved: {}", stats.structures_saved));
+    lines.push(format!("Structures lost: {}", stats.structures_lost));
+    lines.push(format!("Distance traveled: {:.1}", stats.distance));
+    if stats.loops > 0 {
+        lines.push(format!("Loops completed: {}", stats.loops));
+    }
+    lines.push(format!(
+        "Lifetime: {} kills, {} treasures saved over {} runs",
+        profile.lifetime.total_kills(),
+        profile.lifetime.treasures_rescued,
+        profile.runs
+    ));
+
+    commands.spawn((
+        Node {
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(8.0),
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            right: Val::Px(0.0),
+            top: Val::Px(0.0),
+            bottom: Val::Px(0.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        StatsScreen,
+        scope,
+        Children::spawn((
+            Spawn((
+                Text::new(title),
+                TextFont {
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.3, 0.6, 1.0)),
+            )),
+            SpawnIter(lines.into_iter().map(|line| {
+                (
+                    Text::new(line),
+                    TextFont {
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.7, 0.7, 0.8)),
+                )
+            })),
+            SpawnIter(prompt.into_iter().map(|prompt| {
+                (
+                    Text::default(),
+                    ButtonPrompt(prompt),
+                    TextFont {
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.7, 0.7, 0.8)),
+                )
+            })),
+        )),
+    ));
+}
@@ -0,0 +1,66 @@
+//! Steering behaviors for units moving in the wraparound world. All displacements are computed
+//! using the shortest path around the world, so units never take the long way round.
+use bevy::prelude::*;
+
+use crate::Playfield;
+
+/// Return the steering force which moves a unit towards `target` at `max_speed`.
+pub fn seek(
+    playfield: &Playfield,
+    position: Vec2,
+    velocity: Vec2,
+    target: Vec2,
+    max_speed: f32,
+) -> Vec2 {
+    let desired = playfield.delta(position, target).normalize_or_zero() * max_speed;
+    desired - velocity
+}
+
+/// Like [`seek`], but slows down when within `slowing_radius` of the target so that the unit
+/// comes to rest on it.
+pub fn arrive(
+    playfield: &Playfield,
+    position: Vec2,
+    velocity: Vec2,
+    target: Vec2,
+    max_speed: f32,
+    slowing_radius: f32,
+) -> Vec2 {
+    let delta = playfield.delta(position, target);
+    let distance = delta.length();
+    if distance >= slowing_radius {
+        return seek(playfield, position, velocity, target, max_speed);
+    }
+    delta.normalize_or_zero() * max_speed * distance / slowing_radius - velocity
+}
+
+/// Return a force pushing the unit away from any neighbors closer than `radius`. The force is
+/// stronger for closer neighbors. Neighbors at exactly the same position are ignored.
+pub fn separation(
+    playfield: &Playfield,
+    position: Vec2,
+    neighbors: impl IntoIterator<Item = Vec2>,
+    radius: f32,
+) -> Vec2 {
+    let mut force = Vec2::ZERO;
+    for neighbor in neighbors {
+        let away = playfield.delta(neighbor, position);
+        let distance = away.length();
+        if distance > 0.0 && distance < radius {
+            force += away / distance * (1.0 - distance / radius);
+        }
+    }
+    force
+}
+
+/// Apply a steering force to a velocity, limiting both the acceleration and the resulting
+/// speed. `max_speed` is per-axis, since enemies generally move faster horizontally.
+pub fn apply_steering(
+    velocity: Vec2,
+    force: Vec2,
+    max_accel: f32,
+    max_speed: Vec2,
+    dt: f32,
+) -> Vec2 {
+    (velocity + force.clamp_length_max(max_accel) * dt).clamp(-max_speed, max_speed)
+}
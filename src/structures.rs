@@ -0,0 +1,280 @@
+//! Ground structures: small outposts which the player must defend from enemy bombing runs.
+use std::ops::Range;
+
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    FX_DEPTH, Playfield, RandomGenerator, TREASURE_DEPTH, UnitPosition,
+    adaptive::AdaptiveDifficulty,
+    ai::StateMachine,
+    explosion::{FlareEffect, ShrapnelEffect},
+    game_state::{DespawnOnExit, GameState, Level},
+    saucer::{Saucer, SaucerState},
+    score::ScoreEvent,
+    settings::Settings,
+    stats::RunStats,
+    terrain::Terrain,
+    wave::WaveComplete,
+};
+
+/// A building on the ground which can be destroyed by enemy bombs.
+#[derive(Component, Debug)]
+pub struct Structure {
+    /// Remaining hit points
+    health: u32,
+}
+
+/// A bomb dropped by an enemy saucer
+#[derive(Component, Default, Debug)]
+pub struct EnemyBomb {
+    velocity: Vec2,
+}
+
+/// Event sent when a structure is destroyed.
+#[derive(Event, Debug)]
+pub struct StructureDestroyed {
+    pub position: Vec2,
+}
+
+/// Shared meshes and materials for structures and enemy bombs
+#[derive(Resource, Default, Debug)]
+pub struct StructureAssets {
+    building_mesh: Handle<Mesh>,
+    building_material: Handle<StandardMaterial>,
+    bomb_mesh: Handle<Mesh>,
+    bomb_material: Handle<StandardMaterial>,
+}
+
+/// Countdown to the next enemy bombing run
+#[derive(Resource, Debug)]
+pub struct BombingRun {
+    timer: f32,
+}
+
+impl Default for BombingRun {
+    fn default() -> Self {
+        Self {
+            timer: BOMBING_INTERVAL.end,
+        }
+    }
+}
+
+/// Number of structures spawned at the start of each wave
+const NUM_STRUCTURES: usize = 4;
+
+/// Hit points of each structure
+const STRUCTURE_HEALTH: u32 = 2;
+
+/// Size of a structure; the height is scaled randomly per building.
+const STRUCTURE_SIZE: Vec3 = Vec3::new(0.03, 0.02, 0.02);
+
+/// Range of height multipliers for structures
+const STRUCTURE_HEIGHT: Range<f32> = 1.0..2.0;
+
+/// Time between enemy bombing runs, in seconds
+const BOMBING_INTERVAL: Range<f32> = 3.0..8.0;
+
+/// A patrolling saucer will only bomb a structure within this horizontal distance.
+const BOMBING_RANGE: f32 = 0.15;
+
+/// Downward acceleration of enemy bombs
+const BOMB_GRAVITY: f32 = 0.5;
+
+/// Structures within this distance of the impact are damaged.
+const BLAST_RADIUS: f32 = 0.05;
+
+/// Score awarded at the end of the wave for each surviving structure
+const STRUCTURE_BONUS: i32 = 500;
+
+/// Score deducted when every structure has been destroyed
+const ALL_STRUCTURES_LOST_PENALTY: i32 = 2000;
+
+pub(crate) fn setup_structures(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut r_assets: ResMut<StructureAssets>,
+) {
+    r_assets.building_mesh = meshes.add(Cuboid::from_size(STRUCTURE_SIZE));
+    r_assets.building_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.35, 0.4, 0.45),
+        emissive: LinearRgba::new(0.05, 0.15, 0.2, 1.0),
+        ..default()
+    });
+    r_assets.bomb_mesh = meshes.add(Sphere::new(0.006).mesh().ico(2).unwrap());
+    r_assets.bomb_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.3, 0.1, 0.3),
+        emissive: LinearRgba::new(1.5, 0.2, 2.0, 1.0),
+        ..default()
+    });
+}
+
+/// Spawn the structures for the wave, evenly spaced around the playfield.
+pub(crate) fn spawn_structures(
+    mut commands: Commands,
+    r_assets: Res<StructureAssets>,
+    r_playfield: Res<Playfield>,
+    r_terrain: Res<Terrain>,
+    r_level: Res<Level>,
+) {
+    let mut rng = ChaCha8Rng::seed_from_u64(r_level.seed());
+    let interval = r_playfield.width / NUM_STRUCTURES as f32;
+    for i in 0..NUM_STRUCTURES {
+        // Offset by half an interval so that structures sit between the treasures.
+        let x = (i as f32 + 0.5) * interval + rng.random_range(-0.2..0.2) * interval;
+        let height = rng.random_range(STRUCTURE_HEIGHT);
+        let y = r_terrain.height_at(x) + STRUCTURE_SIZE.y * height * 0.5;
+        commands.spawn((
+            Structure {
+                health: STRUCTURE_HEALTH,
+            },
+            Mesh3d(r_assets.building_mesh.clone()),
+            MeshMaterial3d(r_assets.building_material.clone()),
+            Transform::from_xyz(0.0, 0.0, TREASURE_DEPTH).with_scale(Vec3::new(1.0, height, 1.0)),
+            UnitPosition(Vec2::new(r_playfield.wrap(x), y)),
+            DespawnOnExit(GameState::Playing),
+        ));
+    }
+}
+
+/// Every so often, a patrolling saucer which is passing over a structure drops a bomb on it.
+pub(crate) fn drop_enemy_bombs(
+    mut commands: Commands,
+    q_saucers: Query<(&UnitPosition, &StateMachine<SaucerState>), With<Saucer>>,
+    q_structures: Query<&UnitPosition, With<Structure>>,
+    mut r_bombing: ResMut<BombingRun>,
+    mut r_rng: ResMut<RandomGenerator>,
+    r_assets: Res<StructureAssets>,
+    r_playfield: Res<Playfield>,
+    r_adaptive: Res<AdaptiveDifficulty>,
+    r_settings: Res<Settings>,
+    r_time: Res<Time>,
+) {
+    r_bombing.timer -= r_time.delta_secs() * r_adaptive.intensity(&r_settings);
+    if r_bombing.timer > 0.0 {
+        return;
+    }
+
+    let bomber = q_saucers.iter().find(|(saucer_pos, machine)| {
+        machine.state() == SaucerState::Patrolling
+            && q_structures.iter().any(|structure_pos| {
+                r_playfield.delta(saucer_pos.0, structure_pos.0).x.abs() < BOMBING_RANGE
+            })
+    });
+    let Some((position, _)) = bomber else {
+        return;
+    };
+
+    r_bombing.timer = r_rng.0.random_range(BOMBING_INTERVAL);
+    commands.spawn((
+        EnemyBomb::default(),
+        UnitPosition(position.0),
+        Mesh3d(r_assets.bomb_mesh.clone()),
+        MeshMaterial3d(r_assets.bomb_material.clone()),
+        Transform::from_xyz(0., 0., FX_DEPTH),
+        DespawnOnExit(GameState::Playing),
+    ));
+}
+
+/// Move enemy bombs, and detonate them when they reach the ground, damaging any nearby
+/// structures.
+pub(crate) fn update_enemy_bombs(
+    mut commands: Commands,
+    mut q_bombs: Query<(Entity, &mut EnemyBomb, &mut UnitPosition), Without<Structure>>,
+    mut q_structures: Query<(Entity, &mut Structure, &UnitPosition)>,
+    asset_server: Res<AssetServer>,
+    r_terrain: Res<Terrain>,
+    r_playfield: Res<Playfield>,
+    r_time: Res<Time>,
+) {
+    let dt = r_time.delta_secs();
+    for (entity, mut bomb, mut position) in q_bombs.iter_mut() {
+        bomb.velocity.y -= BOMB_GRAVITY * dt;
+        position.0 += bomb.velocity * dt;
+
+        if !r_terrain.is_below(position.0) {
+            continue;
+        }
+
+        let impact = Vec2::new(position.0.x, r_terrain.height_at(position.0.x));
+        commands.entity(entity).despawn();
+        for (structure_id, mut structure, structure_pos) in q_structures.iter_mut() {
+            // Skip structures already destroyed by another bomb this frame
+            if structure.health == 0
+                || r_playfield.delta(impact, structure_pos.0).x.abs() > BLAST_RADIUS
+            {
+                continue;
+            }
+            structure.health = structure.health.saturating_sub(1);
+            if structure.health == 0 {
+                commands.entity(structure_id).despawn();
+                commands.trigger(StructureDestroyed {
+                    position: structure_pos.0,
+                });
+            }
+        }
+        commands.spawn((
+            AudioPlayer::new(asset_server.load("sounds/smash.ogg")),
+            PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                volume: Volume::Linear(0.6),
+                ..default()
+            },
+        ));
+        commands.spawn((
+            FlareEffect {
+                size: 0.01,
+                velocity: Vec2::default(),
+            },
+            UnitPosition(impact),
+        ));
+    }
+}
+
+/// When a structure is destroyed, leave some wreckage. If none are left, the player is
+/// penalized.
+pub(crate) fn on_structure_destroyed(
+    trigger: Trigger<StructureDestroyed>,
+    mut commands: Commands,
+    q_structures: Query<(), With<Structure>>,
+    asset_server: Res<AssetServer>,
+    mut r_stats: ResMut<RunStats>,
+) {
+    commands.spawn((
+        ShrapnelEffect {
+            velocity: Vec2::Y * 0.1,
+        },
+        UnitPosition(trigger.event().position),
+    ));
+    r_stats.structures_lost += 1;
+    if q_structures.is_empty() {
+        commands.trigger(ScoreEvent {
+            points: -ALL_STRUCTURES_LOST_PENALTY,
+        });
+        commands.spawn((
+            AudioPlayer::new(asset_server.load("sounds/buzzdown.ogg")),
+            PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                ..default()
+            },
+        ));
+    }
+}
+
+/// Award a bonus for each structure which survived the wave.
+pub(crate) fn award_structure_bonus(
+    _trigger: Trigger<WaveComplete>,
+    mut commands: Commands,
+    q_structures: Query<(), With<Structure>>,
+    mut r_stats: ResMut<RunStats>,
+) {
+    let surviving = q_structures.iter().count();
+    r_stats.structures_saved += surviving as u32;
+    commands.trigger(ScoreEvent {
+        points: surviving as i32 * STRUCTURE_BONUS,
+    });
+}
@@ -0,0 +1,198 @@
+//! Swarmlets: small homing drones released by queens. Each queen emits one every few seconds,
+//! up to a cap, and they chase the player ship until shot down or they burn out, so leaving
+//! queens alive makes the wave steadily harder.
+use avian2d::prelude::{Collider, CollisionLayers, RigidBody};
+use bevy::prelude::*;
+
+use crate::{
+    ENEMY_LAYER, Enemy, EnemyDestroyed, EnemyHit, EnemyKind, PLAYER_LAYER, PLAYER_SHOT_LAYER,
+    Playfield, SHIP_DEPTH, SeamMirrored, UnitPosition,
+    ai::{AiTransition, StateMachine},
+    explosion::FlareEffect,
+    game_state::{DespawnOnExit, GameState},
+    saucer::SaucerState,
+    ship::PlayerShip,
+    steering::{apply_steering, seek},
+};
+
+/// A homing drone which chases the player ship
+#[derive(Component, Debug)]
+pub struct Swarmlet {
+    velocity: Vec2,
+
+    /// Time until the swarmlet burns out
+    lifetime: f32,
+}
+
+/// A queen which releases swarmlets
+#[derive(Component, Debug)]
+pub struct SwarmletNest {
+    /// Time until the next swarmlet is released
+    cooldown: f32,
+}
+
+/// Shared mesh and material for swarmlets
+#[derive(Resource, Default, Debug)]
+pub struct SwarmletAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+/// Time between swarmlets from each queen, in seconds
+const SWARMLET_INTERVAL: f32 = 4.0;
+
+/// Most swarmlets alive at once, across all queens
+const MAX_SWARMLETS: usize = 8;
+
+/// Top speed of a swarmlet
+const SWARMLET_SPEED: f32 = 0.35;
+
+/// How strongly a swarmlet steers towards the player
+const SWARMLET_STEERING: f32 = 2.5;
+
+/// Maximum acceleration of a swarmlet
+const SWARMLET_ACCEL: f32 = 0.8;
+
+/// How long a swarmlet lasts before burning out, in seconds
+const SWARMLET_LIFETIME: f32 = 12.0;
+
+/// Radius of a swarmlet's body and collider
+const SWARMLET_RADIUS: f32 = 0.01;
+
+pub(crate) fn setup_swarmlets(
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut r_assets: ResMut<SwarmletAssets>,
+) {
+    r_assets.mesh = meshes.add(
+        Tetrahedron::default()
+            .mesh()
+            .build()
+            .scaled_by(Vec3::splat(SWARMLET_RADIUS * 1.5)),
+    );
+    r_assets.material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.8, 0.2, 1.0),
+        emissive: LinearRgba::new(2.0, 0.4, 2.5, 1.0),
+        ..default()
+    });
+}
+
+/// Saucers which become queens start releasing swarmlets.
+pub(crate) fn nest_queens(trigger: Trigger<AiTransition<SaucerState>>, mut commands: Commands) {
+    if trigger.event().to == SaucerState::Queened {
+        commands.entity(trigger.target()).insert(SwarmletNest {
+            cooldown: SWARMLET_INTERVAL,
+        });
+    }
+}
+
+/// Release a swarmlet from each queen whose cooldown has run out, unless there are already as
+/// many as allowed.
+pub(crate) fn emit_swarmlets(
+    mut commands: Commands,
+    mut q_nests: Query<(&mut SwarmletNest, &UnitPosition, &StateMachine<SaucerState>)>,
+    q_swarmlets: Query<(), With<Swarmlet>>,
+    r_assets: Res<SwarmletAssets>,
+    r_time: Res<Time>,
+) {
+    let mut active = q_swarmlets.iter().count();
+    for (mut nest, position, machine) in q_nests.iter_mut() {
+        if machine.state() != SaucerState::Queened {
+            continue;
+        }
+        nest.cooldown -= r_time.delta_secs();
+        if nest.cooldown > 0.0 || active >= MAX_SWARMLETS {
+            continue;
+        }
+        nest.cooldown = SWARMLET_INTERVAL;
+        active += 1;
+        commands
+            .spawn((
+                Swarmlet {
+                    velocity: Vec2::NEG_Y * SWARMLET_SPEED * 0.5,
+                    lifetime: SWARMLET_LIFETIME,
+                },
+                Enemy,
+                RigidBody::Kinematic,
+                Collider::circle(SWARMLET_RADIUS),
+                CollisionLayers::from_bits(ENEMY_LAYER, PLAYER_LAYER | PLAYER_SHOT_LAYER),
+                UnitPosition(position.0),
+                Mesh3d(r_assets.mesh.clone()),
+                MeshMaterial3d(r_assets.material.clone()),
+                Transform::from_xyz(0., 0., SHIP_DEPTH),
+                SeamMirrored,
+                DespawnOnExit(GameState::Playing),
+            ))
+            .observe(swarmlet_hit);
+    }
+}
+
+/// Steer swarmlets towards the player ship, spinning as they go, and burn them out when they
+/// expire.
+pub(crate) fn update_swarmlets(
+    mut commands: Commands,
+    mut q_swarmlets: Query<(Entity, &mut Swarmlet, &mut UnitPosition, &mut Transform)>,
+    q_player: Query<&UnitPosition, (With<PlayerShip>, Without<Swarmlet>)>,
+    r_playfield: Res<Playfield>,
+    r_time: Res<Time>,
+) {
+    let dt = r_time.delta_secs();
+    let target = q_player.single().ok().map(|position| position.0);
+    for (entity, mut swarmlet, mut position, mut transform) in q_swarmlets.iter_mut() {
+        swarmlet.lifetime -= dt;
+        if swarmlet.lifetime <= 0.0 {
+            commands.entity(entity).despawn();
+            commands.spawn((
+                FlareEffect {
+                    size: 0.005,
+                    velocity: Vec2::default(),
+                },
+                UnitPosition(position.0),
+            ));
+            continue;
+        }
+        if let Some(target) = target {
+            let force = seek(
+                &r_playfield,
+                position.0,
+                swarmlet.velocity,
+                target,
+                SWARMLET_SPEED,
+            ) * SWARMLET_STEERING;
+            swarmlet.velocity = apply_steering(
+                swarmlet.velocity,
+                force,
+                SWARMLET_ACCEL,
+                Vec2::splat(SWARMLET_SPEED),
+                dt,
+            );
+        }
+        position.0 += swarmlet.velocity * dt;
+        position.0.x = r_playfield.wrap(position.0.x);
+        transform.rotate_y(dt * 6.0);
+    }
+}
+
+/// A single hit destroys a swarmlet. Queens release swarmlets for as long as they live, so
+/// shooting them down earns nothing.
+fn swarmlet_hit(
+    trigger: Trigger<EnemyHit>,
+    mut commands: Commands,
+    q_swarmlets: Query<&UnitPosition, With<Swarmlet>>,
+) {
+    let Ok(position) = q_swarmlets.get(trigger.target()) else {
+        return;
+    };
+    commands.entity(trigger.target()).despawn();
+    commands.trigger(EnemyDestroyed {
+        kind: EnemyKind::Swarmlet,
+        position: position.0,
+    });
+    commands.spawn((
+        FlareEffect {
+            size: 0.006,
+            velocity: Vec2::default(),
+        },
+        UnitPosition(position.0),
+    ));
+}
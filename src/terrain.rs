@@ -0,0 +1,33 @@
+//! Gameplay terrain: the ground surface that projectiles and falling objects collide with. This
+//! is separate from the parallax mountain ranges, which are purely decorative.
+use bevy::prelude::*;
+
+use crate::treasure::TREASURE_GROUND_Y;
+
+/// Ground surface of the playfield.
+#[derive(Resource, Debug)]
+pub struct Terrain {
+    /// Height of the ground surface
+    ground_y: f32,
+}
+
+impl Default for Terrain {
+    fn default() -> Self {
+        Self {
+            ground_y: TREASURE_GROUND_Y,
+        }
+    }
+}
+
+impl Terrain {
+    /// Height of the ground surface at the given horizontal position. The ground is currently
+    /// flat, but callers should not rely on that.
+    pub fn height_at(&self, _x: f32) -> f32 {
+        self.ground_y
+    }
+
+    /// Whether the given point is at or below the ground surface.
+    pub fn is_below(&self, position: Vec2) -> bool {
+        position.y <= self.height_at(position.x)
+    }
+}
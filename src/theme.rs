@@ -0,0 +1,185 @@
+//! Level themes: data assets which control the look of the background scenery.
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{game_state::Level, mountains::MountainShape};
+
+/// Themes cycled through by successive levels.
+const THEMES: &[&str] = &[
+    "themes/default.theme.ron",
+    "themes/ice.theme.ron",
+    "themes/desert.theme.ron",
+    "themes/volcanic.theme.ron",
+];
+
+/// Visual theme for a level.
+#[derive(Asset, TypePath, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct LevelTheme {
+    /// Starfield colors
+    pub stars: StarTheme,
+
+    /// Mountain detail colors
+    pub mountains: MountainTheme,
+}
+
+/// Colors of the starfield. Stars are tinted according to their parallax depth, and a small
+/// fraction of them are drawn as larger, colored giants.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct StarTheme {
+    /// Color of the nearest stars
+    pub near_color: Color,
+
+    /// Color of the most distant stars
+    pub far_color: Color,
+
+    /// Chance that any given star is a giant
+    pub giant_chance: f32,
+
+    /// Colors to choose from for giant stars
+    pub giant_colors: Vec<Color>,
+}
+
+impl Default for StarTheme {
+    fn default() -> Self {
+        Self {
+            near_color: Color::srgb(1.0, 0.95, 0.85),
+            far_color: Color::srgb(0.65, 0.75, 1.0),
+            giant_chance: 0.01,
+            giant_colors: vec![Color::srgb(1.0, 0.55, 0.35), Color::srgb(0.55, 0.75, 1.0)],
+        }
+    }
+}
+
+/// Style of terrain used for the mountain ranges.
+#[derive(Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MountainBiome {
+    /// Rolling mountains
+    #[default]
+    Alpine,
+
+    /// Jagged ice spires
+    Ice,
+
+    /// Low, smooth dunes
+    Dunes,
+
+    /// Tall, widely spaced volcanic peaks
+    Volcanic,
+}
+
+impl MountainBiome {
+    /// Outline of the mountain ranges for this biome.
+    pub fn shape(self) -> MountainShape {
+        match self {
+            MountainBiome::Alpine => MountainShape::default(),
+            MountainBiome::Ice => MountainShape {
+                base: 0.18,
+                amplitude: 0.16,
+                peak_spacing: 2,
+                roughness: 0.04,
+            },
+            MountainBiome::Dunes => MountainShape {
+                base: 0.12,
+                amplitude: 0.06,
+                peak_spacing: 16,
+                roughness: 0.004,
+            },
+            MountainBiome::Volcanic => MountainShape {
+                base: 0.12,
+                amplitude: 0.2,
+                peak_spacing: 32,
+                roughness: 0.03,
+            },
+        }
+    }
+}
+
+/// Shape and coloring of the mountain ranges.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct MountainTheme {
+    /// Style of terrain
+    pub biome: MountainBiome,
+
+    /// Height of the snow line, in mountain mesh units. Peaks rise to around 0.3.
+    pub snow_line: f32,
+
+    /// Color of the snow caps
+    pub snow_color: Color,
+
+    /// Color of the highlight along the ridge line; alpha controls its strength
+    pub ridge_color: Color,
+
+    /// Color of the pulsing glow around the peaks; alpha controls its strength
+    pub glow_color: Color,
+}
+
+impl Default for MountainTheme {
+    fn default() -> Self {
+        Self {
+            biome: MountainBiome::Alpine,
+            snow_line: 0.27,
+            snow_color: Color::srgb(0.55, 0.55, 0.65),
+            ridge_color: Color::srgba(0.5, 0.5, 0.7, 0.5),
+            glow_color: Color::NONE,
+        }
+    }
+}
+
+/// The theme for the current level.
+#[derive(Resource, Default, Debug)]
+pub struct CurrentTheme {
+    /// Handle to the current theme
+    pub handle: Handle<LevelTheme>,
+
+    /// All of the level themes, loaded up front so that switching levels is instant
+    all: Vec<Handle<LevelTheme>>,
+
+    /// Rotation applied to the hue of every theme color, in degrees. Shifts with each loop.
+    hue_shift: f32,
+}
+
+impl CurrentTheme {
+    /// Return the current theme, or the default theme if it hasn't loaded yet.
+    pub fn get(&self, themes: &Assets<LevelTheme>) -> LevelTheme {
+        let theme = themes.get(&self.handle).cloned().unwrap_or_default();
+        if self.hue_shift == 0.0 {
+            theme
+        } else {
+            theme.shifted(self.hue_shift)
+        }
+    }
+}
+
+impl LevelTheme {
+    /// Return a copy of the theme with the hue of every color rotated.
+    pub fn shifted(mut self, degrees: f32) -> Self {
+        let shift = |color: &mut Color| *color = Hsla::from(*color).rotate_hue(degrees).into();
+        shift(&mut self.stars.near_color);
+        shift(&mut self.stars.far_color);
+        self.stars.giant_colors.iter_mut().for_each(shift);
+        shift(&mut self.mountains.snow_color);
+        shift(&mut self.mountains.ridge_color);
+        shift(&mut self.mountains.glow_color);
+        self
+    }
+}
+
+/// Hue rotation added by each completed loop, in degrees
+const LOOP_HUE_SHIFT: f32 = 75.0;
+
+pub(crate) fn load_theme(asset_server: Res<AssetServer>, mut r_theme: ResMut<CurrentTheme>) {
+    r_theme.all = THEMES.iter().map(|path| asset_server.load(*path)).collect();
+    r_theme.handle = r_theme.all[0].clone();
+}
+
+/// Choose the theme for the current level, shifting the palette for each completed loop.
+pub(crate) fn select_level_theme(r_level: Res<Level>, mut r_theme: ResMut<CurrentTheme>) {
+    r_theme.hue_shift = (r_level.loop_count() as f32 * LOOP_HUE_SHIFT).rem_euclid(360.0);
+    let index = r_level.number as usize % r_theme.all.len().max(1);
+    if let Some(handle) = r_theme.all.get(index).cloned() {
+        r_theme.handle = handle;
+    }
+}
@@ -0,0 +1,181 @@
+//! Event ticker: a short list of recent happenings in the corner of the screen, so the player can
+//! follow what is going on elsewhere in the world.
+use bevy::prelude::*;
+
+use crate::{
+    TreasureLost, TreasureRescued,
+    ai::AiTransition,
+    game_state::{DespawnOnExit, GameState},
+    saucer::SaucerState,
+    settings::Settings,
+    structures::StructureDestroyed,
+    treasure_count::{AllTreasuresLost, TreasuresCritical},
+};
+
+/// Event which adds a line to the ticker.
+#[derive(Event, Debug)]
+pub struct TickerMessage {
+    pub text: &'static str,
+    pub color: Color,
+}
+
+/// Marker for the ticker container
+#[derive(Component, Default, Debug)]
+pub struct EventTicker;
+
+/// A line in the ticker
+#[derive(Component, Default, Debug)]
+pub struct TickerEntry {
+    /// Time since the line was added, in seconds
+    age: f32,
+
+    /// Color the line was added with
+    color: Color,
+}
+
+/// Maximum number of lines shown; older lines are removed to make room.
+const MAX_ENTRIES: usize = 5;
+
+/// Time a line stays in the ticker, in seconds
+const ENTRY_LIFETIME: f32 = 6.0;
+
+/// Lines fade out over this much of their lifetime, in seconds.
+const ENTRY_FADE: f32 = 1.0;
+
+const ALERT_COLOR: Color = Color::srgb(1.0, 0.5, 0.0);
+const DANGER_COLOR: Color = Color::srgb(1.0, 0.3, 0.9);
+const GOOD_COLOR: Color = Color::srgb(0.4, 1.0, 0.5);
+
+pub(crate) fn spawn_event_ticker(mut commands: Commands, r_settings: Res<Settings>) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(12.0),
+            bottom: Val::Px(56.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::FlexEnd,
+            ..default()
+        },
+        if r_settings.hud.show_ticker {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        },
+        EventTicker,
+        DespawnOnExit(GameState::Playing),
+    ));
+}
+
+/// Add a line to the bottom of the ticker, dropping the oldest if it is full.
+pub(crate) fn on_ticker_message(
+    trigger: Trigger<TickerMessage>,
+    mut commands: Commands,
+    q_ticker: Query<(Entity, Option<&Children>), With<EventTicker>>,
+) {
+    let event = trigger.event();
+    for (ticker, entries) in q_ticker.iter() {
+        let count = entries.map_or(0, |entries| entries.len());
+        if let Some(entries) = entries {
+            for entry in entries.iter().take((count + 1).saturating_sub(MAX_ENTRIES)) {
+                commands.entity(entry).despawn();
+            }
+        }
+        commands.spawn((
+            Text::new(event.text),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+            TextColor(event.color),
+            TickerEntry {
+                age: 0.0,
+                color: event.color,
+            },
+            ChildOf(ticker),
+        ));
+    }
+}
+
+/// Age ticker lines, fading them out at the end of their lifetime. Uses the game clock, so the
+/// ticker holds still while paused.
+pub(crate) fn update_event_ticker(
+    mut commands: Commands,
+    mut q_entries: Query<(Entity, &mut TickerEntry, &mut TextColor)>,
+    r_time: Res<Time>,
+) {
+    for (entity, mut entry, mut color) in q_entries.iter_mut() {
+        entry.age += r_time.delta_secs();
+        let remaining = ENTRY_LIFETIME - entry.age;
+        if remaining <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        color.0 = entry.color.with_alpha((remaining / ENTRY_FADE).min(1.0));
+    }
+}
+
+/// Report saucers carrying off treasure, and saucers becoming queens.
+pub(crate) fn ticker_saucer_transition(
+    trigger: Trigger<AiTransition<SaucerState>>,
+    mut commands: Commands,
+) {
+    match trigger.event().to {
+        SaucerState::Escaping => commands.trigger(TickerMessage {
+            text: "Treasure abducted!",
+            color: ALERT_COLOR,
+        }),
+        SaucerState::Queened => commands.trigger(TickerMessage {
+            text: "Saucer queened!",
+            color: DANGER_COLOR,
+        }),
+        _ => {}
+    }
+}
+
+pub(crate) fn ticker_treasure_lost(_trigger: Trigger<TreasureLost>, mut commands: Commands) {
+    commands.trigger(TickerMessage {
+        text: "Treasure lost!",
+        color: DANGER_COLOR,
+    });
+}
+
+pub(crate) fn ticker_treasure_rescued(_trigger: Trigger<TreasureRescued>, mut commands: Commands) {
+    commands.trigger(TickerMessage {
+        text: "Treasure rescued!",
+        color: GOOD_COLOR,
+    });
+}
+
+pub(crate) fn ticker_treasures_critical(
+    trigger: Trigger<TreasuresCritical>,
+    mut commands: Commands,
+) {
+    commands.trigger(TickerMessage {
+        text: match trigger.event().standing {
+            0 => "No treasures left standing!",
+            1 => "Last treasure standing!",
+            _ => "Treasures critical!",
+        },
+        color: ALERT_COLOR,
+    });
+}
+
+pub(crate) fn ticker_all_treasures_lost(
+    _trigger: Trigger<AllTreasuresLost>,
+    mut commands: Commands,
+) {
+    commands.trigger(TickerMessage {
+        text: "Planet destroyed!",
+        color: DANGER_COLOR,
+    });
+}
+
+pub(crate) fn ticker_structure_destroyed(
+    _trigger: Trigger<StructureDestroyed>,
+    mut commands: Commands,
+) {
+    commands.trigger(TickerMessage {
+        text: "Outpost destroyed!",
+        color: ALERT_COLOR,
+    });
+}
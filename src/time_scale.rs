@@ -0,0 +1,110 @@
+//! Hitstop and slow-motion effects
+use bevy::prelude::*;
+
+use crate::{Enemy, EnemyDestroyed, EnemyKind, damage::ShipLost, settings::Settings};
+
+/// Event which briefly slows down or freezes gameplay, used to emphasize dramatic moments.
+/// Since all simulation systems read the virtual clock, this is done by adjusting the relative
+/// speed of `Time<Virtual>`.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SlowMotion {
+    /// Multiplier applied to the game clock; 0.0 freezes gameplay entirely.
+    pub speed: f32,
+
+    /// How long the effect lasts, in real (unscaled) seconds.
+    pub duration: f32,
+}
+
+impl SlowMotion {
+    /// Brief freeze-frame, used for impacts.
+    pub fn hitstop(duration: f32) -> Self {
+        Self {
+            speed: 0.0,
+            duration,
+        }
+    }
+
+    /// Slow down the game clock by the given factor.
+    pub fn slow(speed: f32, duration: f32) -> Self {
+        Self { speed, duration }
+    }
+}
+
+/// Tracks the currently active slow-motion effect.
+#[derive(Resource, Debug)]
+pub struct TimeScale {
+    /// Clock multiplier while the effect is active
+    speed: f32,
+
+    /// Real time remaining until the effect ends
+    remaining: f32,
+}
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            remaining: 0.0,
+        }
+    }
+}
+
+/// Start a slow-motion effect. If one is already running, the slower of the two wins and the
+/// effect lasts until the later of the two end times.
+pub(crate) fn on_slow_motion(trigger: Trigger<SlowMotion>, mut r_time_scale: ResMut<TimeScale>) {
+    let effect = trigger.event();
+    if r_time_scale.remaining > 0.0 {
+        r_time_scale.speed = r_time_scale.speed.min(effect.speed);
+        r_time_scale.remaining = r_time_scale.remaining.max(effect.duration);
+    } else {
+        r_time_scale.speed = effect.speed;
+        r_time_scale.remaining = effect.duration;
+    }
+}
+
+/// Freeze-frame when a queen or a mothership turret is destroyed, in seconds
+const KILL_HITSTOP: f32 = 0.08;
+
+/// Slow down the game when a dramatic kill is made: a queen or mothership turret freezes the
+/// action for an instant, while the mothership itself or the last enemy of the wave brings on
+/// a longer slow motion.
+pub(crate) fn slow_motion_on_enemy_destroyed(
+    trigger: Trigger<EnemyDestroyed>,
+    mut commands: Commands,
+    q_enemies: Query<(), With<Enemy>>,
+) {
+    // The destroyed enemy has already been despawned.
+    let effect = match trigger.event().kind {
+        _ if q_enemies.is_empty() => SlowMotion::slow(0.2, 1.0),
+        EnemyKind::Boss => SlowMotion::slow(0.2, 1.5),
+        EnemyKind::Queen | EnemyKind::BossTurret => SlowMotion::hitstop(KILL_HITSTOP),
+        _ => return,
+    };
+    commands.trigger(effect);
+}
+
+/// Slow down the game when the player loses a ship.
+pub(crate) fn slow_motion_on_ship_lost(_trigger: Trigger<ShipLost>, mut commands: Commands) {
+    commands.trigger(SlowMotion::slow(0.25, 1.2));
+}
+
+/// Count down the active effect using the real clock, and apply it to the virtual clock along
+/// with the game speed assist.
+pub(crate) fn update_time_scale(
+    mut r_time_scale: ResMut<TimeScale>,
+    mut r_virtual: ResMut<Time<Virtual>>,
+    r_real: Res<Time<Real>>,
+    r_settings: Res<Settings>,
+) {
+    if r_time_scale.remaining > 0.0 {
+        r_time_scale.remaining -= r_real.delta_secs();
+        if r_time_scale.remaining <= 0.0 {
+            r_time_scale.speed = 1.0;
+        }
+    }
+
+    let speed = (r_time_scale.speed * r_settings.assist.game_speed).max(0.0);
+    if r_virtual.relative_speed() != speed {
+        r_virtual.set_relative_speed(speed);
+    }
+}
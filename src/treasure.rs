@@ -1,11 +1,99 @@
-use bevy::prelude::*;
+use avian2d::prelude::{Collider, CollidingEntities, CollisionLayers, RigidBody, Sensor};
+use bevy::{audio::PlaybackMode, prelude::*};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
 
-use crate::{PLAYFIELD_WIDTH, TREASURE_DEPTH, UnitPosition};
+use crate::{
+    PICKUP_LAYER, PLAYER_LAYER, Playfield, TREASURE_DEPTH, TreasureLost, TreasureRescued,
+    UnitPosition,
+    explosion::{FlareEffect, ShrapnelEffect},
+    game_state::{DespawnOnExit, GameState, Level},
+    layout::{LevelLayout, LevelLayouts},
+    parachute::{PARACHUTE_FALL_SPEED, Parachute},
+    score::ScoreEvent,
+    ship::PlayerShip,
+    wave::WaveComplete,
+};
+
+/// Type of treasure. Each type has its own model, value and weight.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize)]
+pub enum TreasureKind {
+    #[default]
+    Tank,
+    Dish,
+    Rover,
+}
+
+impl TreasureKind {
+    pub const ALL: [TreasureKind; 3] =
+        [TreasureKind::Tank, TreasureKind::Dish, TreasureKind::Rover];
+
+    /// Path to the model for this type of treasure
+    pub fn model(self) -> &'static str {
+        match self {
+            TreasureKind::Tank => "models/tank.glb",
+            TreasureKind::Dish => "models/dish.glb",
+            TreasureKind::Rover => "models/rover.glb",
+        }
+    }
+}
+
+/// Scoring and physical properties of a type of treasure.
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct TreasureInfo {
+    /// Points awarded for each treasure of this type still standing at the end of the wave
+    pub points: u32,
+
+    /// Abduction weight: heavier treasures slow down the saucer carrying them
+    pub weight: f32,
+
+    /// Points awarded for catching this treasure after its abductor has been destroyed
+    pub rescue_bonus: u32,
+}
+
+impl Default for TreasureInfo {
+    fn default() -> Self {
+        Self {
+            points: 200,
+            weight: 1.0,
+            rescue_bonus: 300,
+        }
+    }
+}
+
+/// Data asset describing every type of treasure.
+#[derive(Asset, TypePath, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct TreasureTable {
+    pub tank: TreasureInfo,
+    pub dish: TreasureInfo,
+    pub rover: TreasureInfo,
+}
+
+impl TreasureTable {
+    pub fn get(&self, kind: TreasureKind) -> TreasureInfo {
+        match kind {
+            TreasureKind::Tank => self.tank,
+            TreasureKind::Dish => self.dish,
+            TreasureKind::Rover => self.rover,
+        }
+    }
+}
+
+/// Handle to the treasure table asset
+#[derive(Resource, Default, Debug)]
+pub struct TreasureTableHandle(pub Handle<TreasureTable>);
+
+pub(crate) fn load_treasure_table(
+    asset_server: Res<AssetServer>,
+    mut r_table: ResMut<TreasureTableHandle>,
+) {
+    r_table.0 = asset_server.load("data/default.treasures.ron");
+}
 
 /// State of a treasure item
-#[derive(Default, Debug, Copy, Clone)]
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
 pub enum TreasureState {
     /// On the ground
     #[default]
@@ -26,7 +114,16 @@ pub enum TreasureState {
 #[derive(Component, Default, Debug)]
 pub struct Treasure {
     /// What's happening with this treasure
-    state: TreasureState,
+    pub state: TreasureState,
+
+    /// Scoring and weight, copied from the treasure table when spawned
+    pub info: TreasureInfo,
+
+    /// Downward speed while falling
+    fall_speed: f32,
+
+    /// Angle of the pendulum swing while carried by a saucer, in radians
+    pub swing: f32,
     // /// Horizontal velocity
     // speed: f32,
 
@@ -37,32 +134,58 @@ pub struct Treasure {
     // yaw: f32,
 }
 
-const NUM_TREASURES: usize = 16;
+impl Treasure {
+    /// Release the treasure from its abductor, letting it fall.
+    pub fn drop(&mut self) {
+        self.state = TreasureState::Falling;
+        self.fall_speed = 0.0;
+    }
+}
 
-pub(crate) fn spawn_treasure(mut commands: Commands, asset_server: Res<AssetServer>) {
-    // TODO: Seed this with the current level number
-    let mut rng = ChaCha8Rng::seed_from_u64(19878367467712);
+/// Height of the ground on which treasures stand.
+pub const TREASURE_GROUND_Y: f32 = -0.47;
 
-    // Distance between treasures
-    let treasure_interval = PLAYFIELD_WIDTH / NUM_TREASURES as f32;
+/// Acceleration of falling treasure.
+const FALL_GRAVITY: f32 = 0.5;
 
-    // Random displaement of initial position
-    let treasure_displacement = treasure_interval * 0.3;
+/// Treasure which hits the ground faster than this is destroyed.
+const CRASH_SPEED: f32 = 0.3;
+
+/// Radius of the sensor with which the player ship catches falling treasure.
+const CATCH_RADIUS: f32 = 0.08;
+
+/// Scale of the treasure models. Colliders are scaled along with the model.
+const TREASURE_SCALE: f32 = 0.013;
+
+/// Number of treasures per unit of playfield width.
+const TREASURE_DENSITY: f32 = 2.0;
+
+/// Spawn the treasures for the level. If the level has a layout asset, treasures are placed
+/// according to it; otherwise they are spread randomly around the playfield.
+pub(crate) fn spawn_treasure(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    r_playfield: Res<Playfield>,
+    r_table: Res<TreasureTableHandle>,
+    tables: Res<Assets<TreasureTable>>,
+    r_level: Res<Level>,
+    r_layouts: Res<LevelLayouts>,
+    layouts: Res<Assets<LevelLayout>>,
+) {
+    let table = tables.get(&r_table.0).cloned().unwrap_or_default();
+    let mut rng = ChaCha8Rng::seed_from_u64(r_level.seed());
+
+    let placements = match r_layouts.current(&r_level, &layouts) {
+        Some(layout) => layout_placements(layout, &mut rng, r_playfield.width),
+        None => random_placements(&mut rng, r_playfield.width),
+    };
 
-    for i in 0..NUM_TREASURES {
-        let pos = i as f32 * treasure_interval
-            + rng.random_range(-treasure_displacement..treasure_displacement);
-        let treasure_type = rng.random_range(0..3);
+    for (pos, kind) in placements {
         // Treasure model
         commands.spawn((
-            SceneRoot(asset_server.load(match treasure_type {
-                0 => GltfAssetLabel::Scene(0).from_asset("models/tank.glb"),
-                1 => GltfAssetLabel::Scene(0).from_asset("models/dish.glb"),
-                2 => GltfAssetLabel::Scene(0).from_asset("models/rover.glb"),
-                _ => unreachable!(),
-            })),
-            Transform::from_scale(Vec3::splat(0.013))
-                .with_translation(Vec3::new(pos, -0.47, TREASURE_DEPTH))
+            SceneRoot(asset_server.load(GltfAssetLabel::Scene(0).from_asset(kind.model()))),
+            Transform::from_scale(Vec3::splat(TREASURE_SCALE))
+                .with_translation(Vec3::new(pos, TREASURE_GROUND_Y, TREASURE_DEPTH))
                 .with_rotation(Quat::from_euler(
                     EulerRot::XYZ,
                     0.1,
@@ -71,8 +194,160 @@ pub(crate) fn spawn_treasure(mut commands: Commands, asset_server: Res<AssetServ
                 )),
             Treasure {
                 state: TreasureState::Standing,
+                info: table.get(kind),
+                fall_speed: 0.0,
+                swing: 0.0,
             },
-            UnitPosition(Vec2::new(pos, -0.47)),
+            RigidBody::Kinematic,
+            Collider::circle(CATCH_RADIUS / TREASURE_SCALE),
+            Sensor,
+            CollisionLayers::from_bits(PICKUP_LAYER, PLAYER_LAYER),
+            UnitPosition(Vec2::new(pos, TREASURE_GROUND_Y)),
+            DespawnOnExit(GameState::Playing),
         ));
     }
 }
+
+fn random_kind(rng: &mut ChaCha8Rng) -> TreasureKind {
+    TreasureKind::ALL[rng.random_range(0..TreasureKind::ALL.len())]
+}
+
+/// Spread treasures evenly around the playfield, with some random displacement.
+fn random_placements(rng: &mut ChaCha8Rng, width: f32) -> Vec<(f32, TreasureKind)> {
+    // Wider worlds get more treasures
+    let num_treasures = (width * TREASURE_DENSITY).round() as usize;
+
+    // Distance between treasures
+    let treasure_interval = width / num_treasures as f32;
+
+    // Random displaement of initial position
+    let treasure_displacement = treasure_interval * 0.3;
+
+    (0..num_treasures)
+        .map(|i| {
+            let pos = i as f32 * treasure_interval
+                + rng.random_range(-treasure_displacement..treasure_displacement);
+            (pos, random_kind(rng))
+        })
+        .collect()
+}
+
+/// Place treasures according to a level layout.
+fn layout_placements(
+    layout: &LevelLayout,
+    rng: &mut ChaCha8Rng,
+    width: f32,
+) -> Vec<(f32, TreasureKind)> {
+    let placed = layout.treasures.iter().map(|placement| {
+        (
+            placement.x * width,
+            placement.kind.unwrap_or_else(|| random_kind(rng)),
+        )
+    });
+    let mut placements: Vec<_> = placed.collect();
+    for _ in 0..layout.random_treasures {
+        placements.push((rng.random_range(0.0..width), random_kind(rng)));
+    }
+    placements
+}
+
+/// Falling treasure drops to the ground, where it either lands safely or is destroyed if it was
+/// dropped from too high. The player ship can catch it on the way down, by touching its sensor.
+/// Treasure falling by parachute never exceeds a gentle speed, so it always lands safely.
+pub(crate) fn update_treasure(
+    mut commands: Commands,
+    mut q_treasures: Query<(
+        Entity,
+        &mut Treasure,
+        &mut UnitPosition,
+        &mut Transform,
+        Has<Parachute>,
+    )>,
+    q_player: Query<&CollidingEntities, With<PlayerShip>>,
+    asset_server: Res<AssetServer>,
+    r_time: Res<Time>,
+) {
+    let touching = q_player.single().ok();
+    for (entity, mut treasure, mut position, mut transform, parachute) in q_treasures.iter_mut() {
+        if treasure.state != TreasureState::Falling {
+            continue;
+        }
+
+        // Rescued by the player
+        if touching.is_some_and(|collisions| collisions.contains(&entity)) {
+            commands.entity(entity).despawn();
+            commands.trigger(TreasureRescued {
+                position: position.0,
+                bonus: treasure.info.rescue_bonus,
+            });
+            commands.spawn((
+                AudioPlayer::new(asset_server.load("sounds/ming.ogg")),
+                PlaybackSettings {
+                    mode: PlaybackMode::Despawn,
+                    ..default()
+                },
+            ));
+            continue;
+        }
+
+        treasure.fall_speed += FALL_GRAVITY * r_time.delta_secs();
+        if parachute {
+            treasure.fall_speed = treasure.fall_speed.min(PARACHUTE_FALL_SPEED);
+        }
+        position.0.y -= treasure.fall_speed * r_time.delta_secs();
+        if position.0.y > TREASURE_GROUND_Y {
+            continue;
+        }
+
+        if treasure.fall_speed > CRASH_SPEED {
+            commands.entity(entity).despawn();
+            commands.trigger(TreasureLost {
+                position: position.0,
+            });
+            commands.spawn((
+                AudioPlayer::new(asset_server.load("sounds/smash.ogg")),
+                PlaybackSettings {
+                    mode: PlaybackMode::Despawn,
+                    ..default()
+                },
+            ));
+            commands.spawn((
+                FlareEffect {
+                    size: 0.01,
+                    velocity: Vec2::default(),
+                },
+                UnitPosition(position.0),
+            ));
+            commands.spawn((
+                ShrapnelEffect {
+                    velocity: Vec2::default(),
+                },
+                UnitPosition(position.0),
+            ));
+        } else {
+            // Land upright, undoing any swing from being carried.
+            position.0.y = TREASURE_GROUND_Y;
+            transform.rotate_z(-treasure.swing);
+            treasure.swing = 0.0;
+            treasure.state = TreasureState::Standing;
+            treasure.fall_speed = 0.0;
+            commands.entity(entity).remove::<Parachute>();
+        }
+    }
+}
+
+/// Award points for each treasure still standing at the end of the wave.
+pub(crate) fn award_treasure_points(
+    _trigger: Trigger<WaveComplete>,
+    mut commands: Commands,
+    q_treasures: Query<&Treasure>,
+) {
+    let points = q_treasures
+        .iter()
+        .filter(|treasure| treasure.state == TreasureState::Standing)
+        .map(|treasure| treasure.info.points)
+        .sum::<u32>();
+    commands.trigger(ScoreEvent {
+        points: points as i32,
+    });
+}
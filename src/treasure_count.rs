@@ -0,0 +1,137 @@
+//! Treasure count: the HUD shows how many treasures are still standing on the ground, turning
+//! red when few are left, and a warning sting plays when the count becomes critical. Losing
+//! every treasure destroys the planet, turning every saucer into a queen.
+use bevy::prelude::*;
+
+use crate::{
+    TreasureLost,
+    game_state::{DespawnOnExit, GameState},
+    treasure::{Treasure, TreasureState},
+    ui_audio::UiSound,
+};
+
+/// Number of treasures in the current wave.
+#[derive(Resource, Default, Debug)]
+pub struct TreasureCount {
+    /// Treasures standing on the ground
+    pub standing: usize,
+
+    /// Treasures at the start of the wave
+    pub total: usize,
+
+    /// Treasures destroyed or carried off this wave. Rescued treasures are safe, and never count
+    /// as lost.
+    pub lost: usize,
+
+    /// Whether the count has been taken since the wave started. The first count of a wave
+    /// never sounds a warning.
+    counted: bool,
+
+    /// Whether the critical warning has sounded. It can sound again if the count recovers.
+    warned: bool,
+}
+
+/// Event sent when the number of standing treasures drops to the critical level.
+#[derive(Event, Debug)]
+pub struct TreasuresCritical {
+    pub standing: usize,
+}
+
+/// Event sent when the last treasure of the wave has been lost. This is the trigger for the
+/// planet being destroyed.
+#[derive(Event, Debug)]
+pub struct AllTreasuresLost;
+
+/// Marker for the treasure count text
+#[derive(Component, Default, Debug)]
+pub struct TreasureCountDisplay;
+
+/// The count is shown in red below this number of standing treasures.
+const LOW_TREASURES: usize = 4;
+
+/// The warning sounds when this few treasures are left standing.
+const CRITICAL_TREASURES: usize = 2;
+
+const COUNT_COLOR: Color = Color::srgb(0.7, 0.9, 0.7);
+const LOW_COLOR: Color = Color::srgb(1.0, 0.2, 0.2);
+
+pub(crate) fn spawn_treasure_count_display(
+    mut commands: Commands,
+    mut r_count: ResMut<TreasureCount>,
+) {
+    *r_count = TreasureCount::default();
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(12.0),
+            bottom: Val::Px(62.0),
+            ..default()
+        },
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(COUNT_COLOR),
+        TreasureCountDisplay,
+        DespawnOnExit(GameState::Playing),
+    ));
+}
+
+/// Count the standing treasures, warning when the number becomes critical.
+pub(crate) fn update_treasure_count(
+    mut commands: Commands,
+    q_treasures: Query<&Treasure>,
+    mut r_count: ResMut<TreasureCount>,
+) {
+    let standing = q_treasures
+        .iter()
+        .filter(|treasure| treasure.state == TreasureState::Standing)
+        .count();
+    let previous_standing = r_count.standing;
+    r_count.standing = standing;
+    if !r_count.counted {
+        r_count.counted = true;
+        r_count.total = q_treasures.iter().count();
+        r_count.warned = standing <= CRITICAL_TREASURES;
+        return;
+    }
+
+    if standing > CRITICAL_TREASURES {
+        r_count.warned = false;
+    } else if standing < previous_standing && !r_count.warned {
+        r_count.warned = true;
+        commands.trigger(TreasuresCritical { standing });
+        commands.trigger(UiSound::TreasureWarning);
+    }
+}
+
+/// Tally each lost treasure, announcing when every treasure of the wave has been lost.
+pub(crate) fn count_lost_treasure(
+    _trigger: Trigger<TreasureLost>,
+    mut commands: Commands,
+    mut r_count: ResMut<TreasureCount>,
+) {
+    r_count.lost += 1;
+    if r_count.lost == r_count.total {
+        commands.trigger(AllTreasuresLost);
+    }
+}
+
+pub(crate) fn update_treasure_count_display(
+    mut q_display: Query<(&mut Text, &mut TextColor), With<TreasureCountDisplay>>,
+    r_count: Res<TreasureCount>,
+) {
+    let label = format!("Treasures: {}", r_count.standing);
+    let count_color = if r_count.standing < LOW_TREASURES {
+        LOW_COLOR
+    } else {
+        COUNT_COLOR
+    };
+    for (mut text, mut color) in q_display.iter_mut() {
+        if text.0 != label {
+            text.0 = label.clone();
+        }
+        color.set_if_neq(TextColor(count_color));
+    }
+}
@@ -0,0 +1,155 @@
+//! Gameplay tuning: constants which control the feel of the game, loaded from a data asset so
+//! that they can be tweaked while the game is running. With asset hot reloading enabled (as in
+//! debug builds), edits to the file take effect immediately.
+use bevy::prelude::*;
+use serde::Deserialize;
+
+/// Tunable gameplay constants. This is both the data asset, and the resource holding the values
+/// currently in effect.
+#[derive(Asset, Resource, TypePath, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub struct Tuning {
+    pub ship: ShipTuning,
+    pub laser: LaserTuning,
+    pub saucer: SaucerTuning,
+    pub effects: EffectTuning,
+}
+
+/// Player ship handling, applied on top of the stats of the ship variant.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ShipTuning {
+    /// Multiplier for horizontal acceleration
+    pub acceleration_scale: f32,
+
+    /// Multiplier for maximum horizontal speed
+    pub max_speed_scale: f32,
+
+    /// Drag multiplier while the air brake is applied
+    pub brake_drag: f32,
+
+    /// Nose-up kick given to the ship model by each volley, in radians
+    pub recoil_kick: f32,
+
+    /// Rate at which the ship recovers from recoil, in radians per second
+    pub recoil_recovery: f32,
+}
+
+impl Default for ShipTuning {
+    fn default() -> Self {
+        Self {
+            acceleration_scale: 1.0,
+            max_speed_scale: 1.0,
+            brake_drag: 4.0,
+            recoil_kick: 0.06,
+            recoil_recovery: 0.6,
+        }
+    }
+}
+
+/// Player laser shots
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct LaserTuning {
+    /// Horizontal speed of a shot
+    pub speed: f32,
+
+    /// How long a shot lasts, in seconds
+    pub lifetime: f32,
+}
+
+impl Default for LaserTuning {
+    fn default() -> Self {
+        Self {
+            speed: 3.0,
+            lifetime: 0.3,
+        }
+    }
+}
+
+/// Saucer movement
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct SaucerTuning {
+    /// Maximum horizontal speed while patrolling
+    pub speed_x: f32,
+
+    /// Maximum vertical speed while patrolling
+    pub speed_y: f32,
+
+    /// Acceleration towards the next waypoint
+    pub acceleration: f32,
+
+    /// Climb speed while escaping with a treasure, before difficulty and weight are applied
+    pub escape_speed: f32,
+
+    /// Speed multiplier for queens
+    pub queen_speed_scale: f32,
+}
+
+impl SaucerTuning {
+    /// Maximum patrol speed on each axis
+    pub fn speed(&self) -> Vec2 {
+        Vec2::new(self.speed_x, self.speed_y)
+    }
+}
+
+impl Default for SaucerTuning {
+    fn default() -> Self {
+        Self {
+            speed_x: 0.4,
+            speed_y: 0.2,
+            acceleration: 1.0,
+            escape_speed: 0.08,
+            queen_speed_scale: 1.5,
+        }
+    }
+}
+
+/// Durations of explosion effects, in seconds
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct EffectTuning {
+    pub shrapnel_time: f32,
+    pub flare_time: f32,
+    pub dust_time: f32,
+    pub spark_time: f32,
+}
+
+impl Default for EffectTuning {
+    fn default() -> Self {
+        Self {
+            shrapnel_time: 0.8,
+            flare_time: 0.2,
+            dust_time: 0.4,
+            spark_time: 0.3,
+        }
+    }
+}
+
+/// Handle to the tuning asset
+#[derive(Resource, Default, Debug)]
+pub struct TuningHandle(pub Handle<Tuning>);
+
+pub(crate) fn load_tuning(asset_server: Res<AssetServer>, mut r_handle: ResMut<TuningHandle>) {
+    r_handle.0 = asset_server.load("data/default.tuning.ron");
+}
+
+/// Put the values from the tuning asset into effect when it is loaded or changed. Until then,
+/// the built-in defaults are used.
+pub(crate) fn apply_tuning(
+    mut events: EventReader<AssetEvent<Tuning>>,
+    tunings: Res<Assets<Tuning>>,
+    r_handle: Res<TuningHandle>,
+    mut r_tuning: ResMut<Tuning>,
+) {
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&r_handle.0) && !event.is_modified(&r_handle.0) {
+            continue;
+        }
+        if let Some(tuning) = tunings.get(&r_handle.0) {
+            *r_tuning = tuning.clone();
+            info!("Tuning applied");
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Menu and interface sound effects. All interface sounds go through the UI audio bus, so that
+//! their volume can be controlled separately from gameplay sounds.
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+};
+
+/// Interface sound effects
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiSound {
+    /// Selection moved to a different menu entry
+    Hover,
+    /// A menu was opened or an option toggled
+    Click,
+    /// A menu choice was accepted
+    Confirm,
+    /// A menu was dismissed
+    Back,
+    /// The player earned an extra life
+    ExtraLife,
+    /// All enemies in the wave have been destroyed
+    WaveComplete,
+    /// Few treasures are left standing
+    TreasureWarning,
+}
+
+impl UiSound {
+    /// Sound file and playback speed. Several effects share a sample, pitched differently.
+    fn sample(&self) -> (&'static str, f32) {
+        match self {
+            UiSound::Hover => ("sounds/kick.ogg", 2.0),
+            UiSound::Click => ("sounds/kick.ogg", 1.0),
+            UiSound::Confirm => ("sounds/ming.ogg", 1.5),
+            UiSound::Back => ("sounds/buzzdown.ogg", 1.5),
+            UiSound::ExtraLife => ("sounds/ming.ogg", 0.75),
+            UiSound::WaveComplete => ("sounds/warpin.ogg", 1.0),
+            UiSound::TreasureWarning => ("sounds/buzzdown.ogg", 0.6),
+        }
+    }
+}
+
+/// Marker for sounds playing on the UI audio bus
+#[derive(Component, Default, Debug)]
+pub struct UiAudio;
+
+/// Volume of the UI audio bus
+#[derive(Resource, Debug)]
+pub struct UiAudioBus {
+    pub volume: f32,
+}
+
+impl Default for UiAudioBus {
+    fn default() -> Self {
+        Self { volume: 0.6 }
+    }
+}
+
+pub(crate) fn on_ui_sound(
+    trigger: Trigger<UiSound>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    r_bus: Res<UiAudioBus>,
+) {
+    let (path, speed) = trigger.event().sample();
+    commands.spawn((
+        AudioPlayer::new(asset_server.load(path)),
+        PlaybackSettings {
+            mode: PlaybackMode::Despawn,
+            volume: Volume::Linear(r_bus.volume),
+            speed,
+            ..default()
+        },
+        UiAudio,
+    ));
+}
+
+/// Apply changes to the bus volume to any UI sounds already playing.
+pub(crate) fn update_ui_audio_bus(
+    r_bus: Res<UiAudioBus>,
+    mut q_sinks: Query<&mut AudioSink, With<UiAudio>>,
+) {
+    for mut sink in q_sinks.iter_mut() {
+        sink.set_volume(Volume::Linear(r_bus.volume));
+    }
+}
+
+pub(crate) fn play_wave_complete(mut commands: Commands) {
+    commands.trigger(UiSound::WaveComplete);
+}
@@ -0,0 +1,232 @@
+//! Per-wave configuration, including optional mutators which change the rules for one wave.
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    Enemy,
+    game_state::{DespawnOnExit, Difficulty, GameState, Level, WAVES_PER_LOOP},
+    layout::{LevelLayout, LevelLayouts},
+    menu_input::MenuInput,
+    ui_audio::UiSound,
+};
+
+/// A rule change which applies for a single wave. Mutators are listed in the level layout, and
+/// announced in the banner at the start of the wave.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaveMutator {
+    /// Explosion debris and sparks fall slowly
+    LowGravityShrapnel,
+
+    /// Saucers fly twice as fast
+    DoubleSaucerSpeed,
+
+    /// The minimap is hidden
+    NoMinimap,
+
+    /// Points scored are doubled
+    BonusScore,
+
+    /// Dropped treasure falls slowly by parachute
+    Parachutes,
+}
+
+impl WaveMutator {
+    /// Name shown in the wave banner
+    pub fn name(&self) -> &'static str {
+        match self {
+            WaveMutator::LowGravityShrapnel => "Low gravity shrapnel",
+            WaveMutator::DoubleSaucerSpeed => "Double saucer speed",
+            WaveMutator::NoMinimap => "No minimap",
+            WaveMutator::BonusScore => "Bonus score",
+            WaveMutator::Parachutes => "Parachute drops",
+        }
+    }
+}
+
+/// Parameters for the current wave.
+#[derive(Resource, Debug, Clone)]
+pub struct WaveConfig {
+    /// Maximum number of saucers which may be seeking, grabbing or escaping at the same time.
+    /// Early waves keep this low so that abductions are easy to follow.
+    pub max_abductions: usize,
+
+    /// Minimum time between the start of successive abductions, in seconds.
+    pub abduction_interval: f32,
+
+    /// Rule changes in effect for this wave
+    pub mutators: Vec<WaveMutator>,
+}
+
+impl WaveConfig {
+    /// Whether a mutator is in effect for this wave.
+    pub fn has(&self, mutator: WaveMutator) -> bool {
+        self.mutators.contains(&mutator)
+    }
+
+    /// Multiplier for the gravity acting on debris and sparks.
+    pub fn shrapnel_gravity(&self) -> f32 {
+        if self.has(WaveMutator::LowGravityShrapnel) {
+            0.25
+        } else {
+            1.0
+        }
+    }
+
+    /// Multiplier for saucer flying speed.
+    pub fn saucer_speed(&self) -> f32 {
+        if self.has(WaveMutator::DoubleSaucerSpeed) {
+            2.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Multiplier for points scored.
+    pub fn score_multiplier(&self) -> i32 {
+        if self.has(WaveMutator::BonusScore) {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+/// Marker for the banner announcing the wave, with the time it has left on screen.
+#[derive(Component, Default, Debug)]
+pub struct WaveBanner {
+    timer: f32,
+}
+
+/// Difficulty added over the course of each loop, spread evenly across its waves
+const LOOP_DIFFICULTY: f32 = 1.0;
+
+/// Time the wave banner is shown, in seconds
+const BANNER_TIME: f32 = 3.0;
+
+/// The banner fades out over this much of its time, in seconds.
+const BANNER_FADE: f32 = 0.5;
+
+const BANNER_COLOR: Color = Color::srgb(0.9, 0.9, 1.0);
+const MUTATOR_COLOR: Color = Color::srgb(1.0, 0.7, 0.2);
+
+impl Default for WaveConfig {
+    fn default() -> Self {
+        Self {
+            max_abductions: 2,
+            abduction_interval: 3.0,
+            mutators: Vec::new(),
+        }
+    }
+}
+
+/// Event sent when the wave has been cleared, before leaving the playing state. End-of-wave
+/// bonuses are awarded here, while the wave's entities still exist.
+#[derive(Event, Debug)]
+pub struct WaveComplete;
+
+/// Start the next level when the player dismisses the level complete screen.
+pub(crate) fn continue_to_next_level(
+    mut commands: Commands,
+    r_keys: Res<ButtonInput<KeyCode>>,
+    r_menu: Res<MenuInput>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if r_menu.confirm || r_keys.just_pressed(KeyCode::Space) {
+        next_state.set(GameState::Playing);
+        commands.trigger(UiSound::Confirm);
+    }
+}
+
+/// The wave is complete once all enemies have been destroyed.
+pub(crate) fn check_wave_complete(
+    mut commands: Commands,
+    q_enemies: Query<(), With<Enemy>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if q_enemies.is_empty() {
+        commands.trigger(WaveComplete);
+        next_state.set(GameState::LevelComplete);
+    }
+}
+
+/// Set up the wave: saucers grow more aggressive with every wave, abductions become more frequent
+/// with each completed loop, and the mutators come from the level layout.
+pub(crate) fn configure_wave(
+    mut r_wave: ResMut<WaveConfig>,
+    mut r_difficulty: ResMut<Difficulty>,
+    r_level: Res<Level>,
+    r_layouts: Res<LevelLayouts>,
+    layouts: Res<Assets<LevelLayout>>,
+) {
+    let loops = r_level.loop_count();
+    let defaults = WaveConfig::default();
+    r_difficulty.0 = r_level.number as f32 / WAVES_PER_LOOP as f32 * LOOP_DIFFICULTY;
+    r_wave.max_abductions = defaults.max_abductions + loops as usize;
+    r_wave.abduction_interval = defaults.abduction_interval / (1.0 + loops as f32 * 0.25);
+    r_wave.mutators = r_layouts
+        .current(&r_level, &layouts)
+        .map(|layout| layout.mutators.clone())
+        .unwrap_or_default();
+}
+
+/// Announce the wave number, and any mutators in effect.
+pub(crate) fn spawn_wave_banner(
+    mut commands: Commands,
+    r_wave: Res<WaveConfig>,
+    r_level: Res<Level>,
+) {
+    let mut banner = commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            right: Val::Px(0.0),
+            top: Val::Percent(30.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(4.0),
+            ..default()
+        },
+        WaveBanner { timer: BANNER_TIME },
+        DespawnOnExit(GameState::Playing),
+    ));
+    banner.with_child((
+        Text::new(r_level.name().to_uppercase()),
+        TextFont {
+            font_size: 40.0,
+            ..default()
+        },
+        TextColor(BANNER_COLOR),
+    ));
+    for mutator in r_wave.mutators.iter() {
+        banner.with_child((
+            Text::new(mutator.name()),
+            TextFont {
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(MUTATOR_COLOR),
+        ));
+    }
+}
+
+/// Fade out and remove the wave banner.
+pub(crate) fn update_wave_banner(
+    mut commands: Commands,
+    mut q_banner: Query<(Entity, &mut WaveBanner, &Children)>,
+    mut q_text: Query<&mut TextColor>,
+    r_time: Res<Time>,
+) {
+    for (entity, mut banner, children) in q_banner.iter_mut() {
+        banner.timer -= r_time.delta_secs();
+        if banner.timer <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let alpha = (banner.timer / BANNER_FADE).min(1.0);
+        for child in children.iter() {
+            if let Ok(mut color) = q_text.get_mut(child) {
+                color.0.set_alpha(alpha);
+            }
+        }
+    }
+}